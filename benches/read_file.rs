@@ -0,0 +1,34 @@
+//! Criterion benchmarks for `read_file_contents` across file sizes.
+//!
+//! Run with `cargo bench --bench read_file`.
+
+use std::fs;
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+const SIZES: &[usize] = &[64, 1024, 8 * 1024, 64 * 1024, 1024 * 1024];
+
+/// Text of exactly `size` bytes, in 64-byte lines
+fn text_of_size(size: usize) -> String {
+    let line = format!("{:063}\n", 0);
+    line.repeat(size / line.len() + 1)[..size].to_string()
+}
+
+fn read_file_contents(c: &mut Criterion) {
+    let dir = tempfile::tempdir().unwrap();
+    let mut group = c.benchmark_group("read_file_contents");
+    for &size in SIZES {
+        let path = dir.path().join(format!("{}.txt", size));
+        fs::write(&path, text_of_size(size)).unwrap();
+
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &path, |b, path| {
+            b.iter(|| catboard::read_file_contents(black_box(path)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, read_file_contents);
+criterion_main!(benches);
@@ -0,0 +1,238 @@
+//! Removing terminal escape sequences for `--strip-ansi`.
+//!
+//! Colored output from `ls`, `grep`, compilers and test runners is full of
+//! escape sequences that mean nothing once pasted. [`AnsiStripper`] is a
+//! small state machine after the ECMA-48 / VT500 parser: it drops CSI
+//! sequences (colors, cursor movement, erase), OSC sequences (window
+//! titles, hyperlinks) and the DCS, SOS, PM and APC strings, and keeps
+//! everything else byte for byte. Its state survives between chunks, so a
+//! sequence split across two reads is still removed whole.
+//!
+//! Only 7-bit escapes (introduced by ESC) are recognized; 8-bit C1 controls
+//! would clash with UTF-8.
+
+use crate::error::Result;
+use crate::transform::Transform;
+
+const ESC: u8 = 0x1B;
+const BEL: u8 = 0x07;
+
+/// Where the stripper is within an escape sequence
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum State {
+    /// Plain text
+    #[default]
+    Ground,
+    /// After ESC
+    Escape,
+    /// After ESC and one or more intermediate bytes (`ESC ( B`)
+    EscapeIntermediate,
+    /// Inside a control sequence (`ESC [ 1 ; 31 m`)
+    Csi,
+    /// Inside an OSC or other string, which ends with BEL or ESC `\`
+    String,
+    /// After ESC inside a string: `\` ends it, anything else starts a new
+    /// escape
+    StringEscape,
+}
+
+/// Strips escape sequences from text fed to it in chunks
+#[derive(Debug, Clone, Default)]
+pub struct AnsiStripper {
+    state: State,
+}
+
+impl AnsiStripper {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append `chunk` to `out` without its escape sequences.
+    ///
+    /// A sequence cut off at the end of `chunk` is remembered, and the rest
+    /// of it is dropped from the start of the next chunk. Chunks may split
+    /// UTF-8 characters; escape bytes are all ASCII, so characters pass
+    /// through intact.
+    pub fn push(&mut self, chunk: &[u8], out: &mut Vec<u8>) {
+        for &byte in chunk {
+            if let Some(kept) = self.step(byte) {
+                out.push(kept);
+            }
+        }
+    }
+
+    /// Whether the last chunk ended part way through an escape sequence
+    pub fn in_sequence(&self) -> bool {
+        self.state != State::Ground
+    }
+
+    /// Advance over one byte, returning it if it's text
+    fn step(&mut self, byte: u8) -> Option<u8> {
+        match self.state {
+            State::Ground => {
+                if byte == ESC {
+                    self.state = State::Escape;
+                    None
+                } else {
+                    Some(byte)
+                }
+            }
+            State::Escape => match byte {
+                b'[' => self.enter(State::Csi),
+                b']' | b'P' | b'X' | b'^' | b'_' => self.enter(State::String),
+                0x20..=0x2F => self.enter(State::EscapeIntermediate),
+                // A lone ESC before another starts over
+                ESC => None,
+                0x30..=0x7E => self.enter(State::Ground),
+                // Not an escape after all: drop the ESC, keep the byte
+                _ => self.abandon(byte),
+            },
+            State::EscapeIntermediate => match byte {
+                0x20..=0x2F => None,
+                0x30..=0x7E => self.enter(State::Ground),
+                _ => self.abandon(byte),
+            },
+            State::Csi => match byte {
+                // Parameters and intermediates
+                0x20..=0x3F => None,
+                // Final byte
+                0x40..=0x7E => self.enter(State::Ground),
+                _ => self.abandon(byte),
+            },
+            State::String => match byte {
+                BEL => self.enter(State::Ground),
+                ESC => self.enter(State::StringEscape),
+                _ => None,
+            },
+            State::StringEscape => match byte {
+                b'\\' => self.enter(State::Ground),
+                _ => {
+                    // ST was never sent; read this as a fresh escape
+                    self.state = State::Escape;
+                    self.step(byte)
+                }
+            },
+        }
+    }
+
+    fn enter(&mut self, state: State) -> Option<u8> {
+        self.state = state;
+        None
+    }
+
+    /// End a malformed sequence at `byte`, which is read as plain text
+    fn abandon(&mut self, byte: u8) -> Option<u8> {
+        self.state = State::Ground;
+        self.step(byte)
+    }
+}
+
+/// Remove the escape sequences from `text`
+pub fn strip_ansi(text: &str) -> String {
+    let mut out = Vec::with_capacity(text.len());
+    AnsiStripper::new().push(text.as_bytes(), &mut out);
+    String::from_utf8(out).expect("only ASCII escape bytes are removed")
+}
+
+/// The `--strip-ansi` transform
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StripAnsi;
+
+impl Transform for StripAnsi {
+    fn apply(&self, input: &str) -> Result<String> {
+        Ok(strip_ansi(input))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_strips_colored_ls_output() {
+        let colored = fs::read_to_string("tests/ls-color.txt").unwrap();
+        assert!(colored.contains('\x1b'));
+        assert_eq!(
+            strip_ansi(&colored),
+            "LINK\nREADME.md\nbuild.sh\ndocs\nsrc\n"
+        );
+    }
+
+    #[test]
+    fn test_strips_colored_grep_output() {
+        let colored = fs::read_to_string("tests/grep-color.txt").unwrap();
+        assert_eq!(
+            strip_ansi(&colored),
+            "src/main.rs:2:    let pattern = \"needle\";\n\
+             src/main.rs:3:    println!(\"{}\", pattern);\n"
+        );
+    }
+
+    #[test]
+    fn test_strips_osc_titles_and_hyperlinks() {
+        // Title ended by BEL, hyperlink ended by ST
+        let text =
+            "\x1b]0;build: café\x07ok \x1b]8;;https://example.com\x1b\\link\x1b]8;;\x1b\\ done";
+        assert_eq!(strip_ansi(text), "ok link done");
+    }
+
+    #[test]
+    fn test_strips_other_sequences() {
+        let text = "\x1b[2J\x1b[1;1Hhome\x1b(Bplain\x1b[?25l\x1b7saved\x1b8\x1bPdcs data\x1b\\!";
+        assert_eq!(strip_ansi(text), "homeplainsaved!");
+    }
+
+    #[test]
+    fn test_keeps_text_and_unicode() {
+        let text = "tabs\tand\r\nnewlines, naïve ✓ [brackets] ]";
+        assert_eq!(strip_ansi(text), text);
+    }
+
+    #[test]
+    fn test_malformed_escapes_keep_following_text() {
+        // A CSI broken by a newline ends there; a bare ESC before a
+        // control character is dropped alone
+        assert_eq!(strip_ansi("\x1b[31\nred"), "\nred");
+        assert_eq!(strip_ansi("a\x1b\tb"), "a\tb");
+        assert_eq!(strip_ansi("a\x1b\x1b[1mb"), "ab");
+        // An unterminated OSC ended by a new escape
+        assert_eq!(strip_ansi("\x1b]0;title\x1b[0mtext"), "text");
+    }
+
+    #[test]
+    fn test_sequences_split_across_chunks() {
+        let text = "\x1b[01;31mred\x1b[0m \x1b]8;;https://example.com\x1b\\é\x1b]8;;\x1b\\";
+        let bytes = text.as_bytes();
+        // Every split point, including inside escapes and inside "é"
+        for split in 0..=bytes.len() {
+            let mut stripper = AnsiStripper::new();
+            let mut out = Vec::new();
+            stripper.push(&bytes[..split], &mut out);
+            stripper.push(&bytes[split..], &mut out);
+            assert_eq!(
+                String::from_utf8(out).unwrap(),
+                "red é",
+                "split at {}",
+                split
+            );
+            assert!(!stripper.in_sequence());
+        }
+    }
+
+    #[test]
+    fn test_incomplete_escape_at_end() {
+        let mut stripper = AnsiStripper::new();
+        let mut out = Vec::new();
+        stripper.push(b"done\x1b[1", &mut out);
+        assert_eq!(out, b"done");
+        assert!(stripper.in_sequence());
+        assert_eq!(strip_ansi("done\x1b"), "done");
+    }
+
+    #[test]
+    fn test_as_transform() {
+        let transform: &dyn Transform = &StripAnsi;
+        assert_eq!(transform.apply("\x1b[1mbold\x1b[0m").unwrap(), "bold");
+    }
+}
@@ -0,0 +1,799 @@
+//! Bundling inputs into a tar or zip archive for `--as-tar` and `--as-zip`,
+//! and unpacking one with `--extract`.
+//!
+//! The clipboard holds text, so archives travel base64-encoded. Tar archives
+//! are uncompressed ustar and zip entries are deflated; both unpack with the
+//! usual tools after `base64 -d`. Only regular files are stored. When
+//! reading an archive made elsewhere, GNU and pax long names are followed,
+//! and directory, link and other special entries are skipped, since
+//! directories are implied by the file names. Sizes in headers aren't
+//! trusted: nothing is allocated or inflated beyond the data actually there.
+
+use crate::error::{CatboardError, Result, ResultExt};
+use crate::file::write_atomic;
+use crate::template::civil_from_days;
+use base64::prelude::{Engine, BASE64_STANDARD};
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::{Compression, Crc};
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The container used by `--as-tar` or `--as-zip`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Tar,
+    Zip,
+}
+
+impl fmt::Display for ArchiveFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ArchiveFormat::Tar => "tar",
+            ArchiveFormat::Zip => "zip",
+        })
+    }
+}
+
+/// One file in an archive, named by its `/`-separated relative path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveEntry {
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+fn invalid(message: impl Into<String>) -> CatboardError {
+    CatboardError::ArchiveError(message.into())
+}
+
+/// The name to store `path` under: the path as given when it is relative
+/// and stays below the current directory, otherwise just its file name
+pub fn entry_name(path: &Path) -> String {
+    let mut parts = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_string_lossy()),
+            Component::CurDir => {}
+            _ => {
+                return path.file_name().map_or_else(
+                    || path.display().to_string(),
+                    |name| name.to_string_lossy().into_owned(),
+                )
+            }
+        }
+    }
+    parts.join("/")
+}
+
+/// Where `name` goes under `destination`, refusing names that would land
+/// outside it
+fn destination(destination: &Path, name: &str) -> Result<PathBuf> {
+    let relative = Path::new(name);
+    let inside = relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    if name.is_empty() || !inside || relative.components().next().is_none() {
+        return Err(invalid(format!(
+            "'{}' is not a relative path below the destination",
+            name
+        )));
+    }
+    Ok(destination.join(relative))
+}
+
+/// Reject unsafe or repeated entry names
+fn check_names(entries: &[ArchiveEntry]) -> Result<()> {
+    let mut seen = HashSet::new();
+    for entry in entries {
+        destination(Path::new("."), &entry.name)?;
+        if !seen.insert(entry.name.as_str()) {
+            return Err(invalid(format!(
+                "more than one file is named '{}'",
+                entry.name
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Bundle `entries` into an archive, stamping each with `modified`
+///
+/// # Errors
+/// - `ArchiveError` for unsafe or repeated names, or files too large for
+///   the format (8 GiB for tar, 4 GiB for zip)
+pub fn write_archive(
+    format: ArchiveFormat,
+    entries: &[ArchiveEntry],
+    modified: SystemTime,
+) -> Result<Vec<u8>> {
+    check_names(entries)?;
+    let secs = modified
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    match format {
+        ArchiveFormat::Tar => write_tar(entries, secs),
+        ArchiveFormat::Zip => write_zip(entries, secs),
+    }
+}
+
+/// The regular files in a tar or zip archive, told apart by their leading bytes
+pub fn read_archive(bytes: &[u8]) -> Result<Vec<ArchiveEntry>> {
+    if bytes.starts_with(b"PK\x03\x04") || bytes.starts_with(b"PK\x05\x06") {
+        read_zip(bytes)
+    } else if bytes.get(257..262) == Some(b"ustar") {
+        read_tar(bytes)
+    } else {
+        Err(invalid("not a tar or zip archive"))
+    }
+}
+
+/// [`write_archive`], base64-encoded for the clipboard
+pub fn encode_archive(
+    format: ArchiveFormat,
+    entries: &[ArchiveEntry],
+    modified: SystemTime,
+) -> Result<String> {
+    Ok(BASE64_STANDARD.encode(write_archive(format, entries, modified)?))
+}
+
+/// [`read_archive`] on base64 text, ignoring any line breaks or spaces that
+/// chat clients add
+pub fn decode_archive(text: &str) -> Result<Vec<ArchiveEntry>> {
+    let compact: String = text.chars().filter(|c| !c.is_ascii_whitespace()).collect();
+    let bytes = BASE64_STANDARD
+        .decode(compact)
+        .map_err(|e| invalid(format!("not base64 ({})", e)))?;
+    read_archive(&bytes)
+}
+
+/// Write `entries` under `dir`, creating directories as needed
+///
+/// Nothing is written if any entry's name is unsafe or its file already
+/// exists. Returns the paths written.
+pub fn extract_entries(entries: &[ArchiveEntry], dir: &Path) -> Result<Vec<PathBuf>> {
+    check_names(entries)?;
+    let targets = entries
+        .iter()
+        .map(|entry| destination(dir, &entry.name))
+        .collect::<Result<Vec<_>>>()?;
+    if let Some(existing) = targets.iter().find(|target| target.exists()) {
+        return Err(invalid(format!(
+            "'{}' already exists; extract somewhere else",
+            existing.display()
+        )));
+    }
+
+    for (entry, target) in entries.iter().zip(&targets) {
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).with_path(parent)?;
+        }
+        write_atomic(target, &entry.data)?;
+    }
+    Ok(targets)
+}
+
+const BLOCK: usize = 512;
+
+/// `len` rounded up to a whole number of tar blocks
+fn padded(len: usize) -> usize {
+    len + (BLOCK - len % BLOCK) % BLOCK
+}
+
+/// Split a long name into ustar's 155-byte prefix and 100-byte name
+fn split_tar_name(name: &str) -> Result<(&str, &str)> {
+    if name.len() <= 100 {
+        return Ok(("", name));
+    }
+    name.match_indices('/')
+        .map(|(slash, _)| slash)
+        .find(|&slash| slash <= 155 && name.len() - slash - 1 <= 100)
+        .map(|slash| (&name[..slash], &name[slash + 1..]))
+        .ok_or_else(|| invalid(format!("'{}' is too long for a tar archive", name)))
+}
+
+/// Fill `field` with zero-padded octal digits and a closing NUL
+fn put_octal(field: &mut [u8], value: u64) {
+    let width = field.len() - 1;
+    let digits = format!("{:0width$o}", value, width = width);
+    field[..width].copy_from_slice(digits.as_bytes());
+    field[width] = 0;
+}
+
+/// A ustar header block; `prefix` and `name` must fit their fields
+fn tar_header(prefix: &str, name: &str, typeflag: u8, size: u64, mtime: u64) -> [u8; BLOCK] {
+    let mut header = [0u8; BLOCK];
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    put_octal(&mut header[100..108], 0o644);
+    put_octal(&mut header[108..116], 0);
+    put_octal(&mut header[116..124], 0);
+    put_octal(&mut header[124..136], size);
+    put_octal(&mut header[136..148], mtime);
+    header[156] = typeflag;
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+    header[345..345 + prefix.len()].copy_from_slice(prefix.as_bytes());
+
+    // The checksum is summed with its own field as spaces, then written
+    // as six digits, a NUL and one of those spaces
+    header[148..156].fill(b' ');
+    let checksum: u64 = header.iter().map(|&b| u64::from(b)).sum();
+    put_octal(&mut header[148..155], checksum);
+    header
+}
+
+fn write_tar(entries: &[ArchiveEntry], mtime: u64) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    for entry in entries {
+        // Eleven octal digits of size
+        if entry.data.len() as u64 >= 1 << 33 {
+            return Err(invalid(format!(
+                "'{}' is too large for a tar archive",
+                entry.name
+            )));
+        }
+        let (prefix, name) = split_tar_name(&entry.name)?;
+        let header = tar_header(prefix, name, b'0', entry.data.len() as u64, mtime);
+        out.extend_from_slice(&header);
+        out.extend_from_slice(&entry.data);
+        out.resize(padded(out.len()), 0);
+    }
+    // Two empty blocks end the archive
+    out.resize(out.len() + 2 * BLOCK, 0);
+    Ok(out)
+}
+
+/// A NUL-terminated header field as text
+fn field_str(field: &[u8]) -> String {
+    let end = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+    String::from_utf8_lossy(&field[..end]).into_owned()
+}
+
+/// The name in a ustar header, joined to its prefix
+fn header_name(header: &[u8]) -> String {
+    let name = field_str(&header[..100]);
+    let prefix = if &header[257..263] == b"ustar\0" {
+        field_str(&header[345..500])
+    } else {
+        String::new()
+    };
+    if prefix.is_empty() {
+        name
+    } else {
+        format!("{}/{}", prefix, name)
+    }
+}
+
+fn parse_octal(field: &[u8]) -> Result<u64> {
+    let text = field_str(field);
+    u64::from_str_radix(text.trim(), 8).map_err(|_| invalid("malformed number in tar header"))
+}
+
+/// The `path` and `size` records of a pax extended header, which apply
+/// to the entry that follows it
+fn parse_pax(data: &[u8]) -> Result<(Option<String>, Option<u64>)> {
+    let malformed = || invalid("malformed pax extended header");
+    let (mut path, mut size) = (None, None);
+    let mut rest = data;
+    // Each record is "<length> <key>=<value>\n", the length counting itself
+    while !rest.is_empty() {
+        let space = rest.iter().position(|&b| b == b' ').ok_or_else(malformed)?;
+        let len: usize = std::str::from_utf8(&rest[..space])
+            .ok()
+            .and_then(|len| len.parse().ok())
+            .filter(|&len| len > space + 1 && len <= rest.len())
+            .ok_or_else(malformed)?;
+        let record = rest[space + 1..len]
+            .strip_suffix(b"\n")
+            .ok_or_else(malformed)?;
+        let equals = record
+            .iter()
+            .position(|&b| b == b'=')
+            .ok_or_else(malformed)?;
+        let value = &record[equals + 1..];
+        match &record[..equals] {
+            b"path" => path = Some(String::from_utf8_lossy(value).into_owned()),
+            b"size" => {
+                let value = std::str::from_utf8(value).map_err(|_| malformed())?;
+                size = Some(value.parse().map_err(|_| malformed())?);
+            }
+            _ => {}
+        }
+        rest = &rest[len..];
+    }
+    Ok((path, size))
+}
+
+/// Reads ustar, GNU and pax archives. GNU long names (`L`) and pax `path`
+/// and `size` records (`x`) apply to the entry after them; other pax keys
+/// and global headers (`g`) are ignored.
+fn read_tar(bytes: &[u8]) -> Result<Vec<ArchiveEntry>> {
+    let mut entries = Vec::new();
+    let mut offset = 0;
+    let mut long_name: Option<String> = None;
+    let mut pax_size: Option<u64> = None;
+    loop {
+        let header = bytes
+            .get(offset..offset + BLOCK)
+            .ok_or_else(|| invalid("tar archive is truncated"))?;
+        if header.iter().all(|&b| b == 0) {
+            break;
+        }
+
+        let checksum: u64 = header
+            .iter()
+            .enumerate()
+            .map(|(i, &b)| u64::from(if (148..156).contains(&i) { b' ' } else { b }))
+            .sum();
+        if parse_octal(&header[148..156])? != checksum {
+            return Err(invalid("tar header checksum mismatch"));
+        }
+
+        let typeflag = header[156];
+        let size = match pax_size.take() {
+            Some(size) if !matches!(typeflag, b'x' | b'g' | b'L' | b'K') => size,
+            _ => parse_octal(&header[124..136])?,
+        };
+        let start = offset + BLOCK;
+        let end = usize::try_from(size)
+            .ok()
+            .and_then(|size| start.checked_add(size))
+            .filter(|&end| end <= bytes.len())
+            .ok_or_else(|| invalid("tar entry runs past the end of the archive"))?;
+        let data = &bytes[start..end];
+
+        match typeflag {
+            // Regular and contiguous files
+            b'0' | 0 | b'7' => {
+                let name = match long_name.take() {
+                    Some(name) => name,
+                    None => header_name(header),
+                };
+                entries.push(ArchiveEntry {
+                    name,
+                    data: data.to_vec(),
+                });
+            }
+            b'L' => long_name = Some(field_str(data)),
+            b'x' => {
+                let (path, size) = parse_pax(data)?;
+                long_name = path.or(long_name);
+                pax_size = size;
+            }
+            // Global headers, long link names and other entry types
+            b'g' | b'K' => {}
+            _ => long_name = None,
+        }
+        offset = start + padded(end - start);
+    }
+    Ok(entries)
+}
+
+const LOCAL_HEADER: u32 = 0x0403_4b50;
+const CENTRAL_HEADER: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x0605_4b50;
+const STORED: u16 = 0;
+const DEFLATED: u16 = 8;
+/// Zip 2.0, the version that introduced deflate
+const ZIP_VERSION: u16 = 20;
+/// General-purpose flag bit marking names as UTF-8
+const UTF8_NAMES: u16 = 1 << 11;
+/// "Made by" Unix, so the external attributes carry permissions
+const MADE_BY_UNIX: u16 = 3 << 8;
+
+/// Seconds since the Unix epoch as MS-DOS `(time, date)`, which starts in 1980
+fn dos_datetime(secs: u64) -> (u16, u16) {
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    if year < 1980 {
+        return (0, (1 << 5) | 1);
+    }
+    let time_of_day = secs % 86_400;
+    let time =
+        ((time_of_day / 3600) << 11) | ((time_of_day / 60 % 60) << 5) | (time_of_day % 60 / 2);
+    let date = (((year - 1980).min(127) as u64) << 9) | ((month as u64) << 5) | day as u64;
+    (time as u16, date as u16)
+}
+
+fn write_zip(entries: &[ArchiveEntry], mtime: u64) -> Result<Vec<u8>> {
+    let count =
+        u16::try_from(entries.len()).map_err(|_| invalid("too many files for a zip archive"))?;
+    let (time, date) = dos_datetime(mtime);
+    let mut out = Vec::new();
+    let mut central = Vec::new();
+
+    for entry in entries {
+        let too_large = || invalid(format!("'{}' is too large for a zip archive", entry.name));
+        let mut crc = Crc::new();
+        crc.update(&entry.data);
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        let compressed = encoder
+            .write_all(&entry.data)
+            .and_then(|()| encoder.finish())
+            .map_err(|e| invalid(e.to_string()))?;
+
+        let size = u32::try_from(entry.data.len()).map_err(|_| too_large())?;
+        let compressed_size = u32::try_from(compressed.len()).map_err(|_| too_large())?;
+        let offset = u32::try_from(out.len()).map_err(|_| too_large())?;
+        let name = entry.name.as_bytes();
+        let name_len = u16::try_from(name.len()).map_err(|_| too_large())?;
+
+        for field in [
+            &LOCAL_HEADER.to_le_bytes()[..],
+            &ZIP_VERSION.to_le_bytes(),
+            &UTF8_NAMES.to_le_bytes(),
+            &DEFLATED.to_le_bytes(),
+            &time.to_le_bytes(),
+            &date.to_le_bytes(),
+            &crc.sum().to_le_bytes(),
+            &compressed_size.to_le_bytes(),
+            &size.to_le_bytes(),
+            &name_len.to_le_bytes(),
+            &0u16.to_le_bytes(),
+            name,
+            &compressed,
+        ] {
+            out.extend_from_slice(field);
+        }
+
+        for field in [
+            &CENTRAL_HEADER.to_le_bytes()[..],
+            &(MADE_BY_UNIX | ZIP_VERSION).to_le_bytes(),
+            &ZIP_VERSION.to_le_bytes(),
+            &UTF8_NAMES.to_le_bytes(),
+            &DEFLATED.to_le_bytes(),
+            &time.to_le_bytes(),
+            &date.to_le_bytes(),
+            &crc.sum().to_le_bytes(),
+            &compressed_size.to_le_bytes(),
+            &size.to_le_bytes(),
+            &name_len.to_le_bytes(),
+            // Extra field, comment, disk number and internal attributes
+            &[0; 8],
+            // A regular file with mode 0644
+            &(0o100644u32 << 16).to_le_bytes(),
+            &offset.to_le_bytes(),
+            name,
+        ] {
+            central.extend_from_slice(field);
+        }
+    }
+
+    let too_large = || invalid("archive is too large for a zip file");
+    let central_offset = u32::try_from(out.len()).map_err(|_| too_large())?;
+    let central_size = u32::try_from(central.len()).map_err(|_| too_large())?;
+    out.extend_from_slice(&central);
+    for field in [
+        &END_OF_CENTRAL_DIRECTORY.to_le_bytes()[..],
+        // This disk and the disk the directory starts on
+        &[0; 4],
+        &count.to_le_bytes(),
+        &count.to_le_bytes(),
+        &central_size.to_le_bytes(),
+        &central_offset.to_le_bytes(),
+        // Comment length
+        &[0; 2],
+    ] {
+        out.extend_from_slice(field);
+    }
+    Ok(out)
+}
+
+fn u16_at(bytes: &[u8], at: usize) -> Result<u16> {
+    bytes
+        .get(at..at + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .ok_or_else(|| invalid("zip archive is truncated"))
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> Result<u32> {
+    bytes
+        .get(at..at + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| invalid("zip archive is truncated"))
+}
+
+/// Reads entries through the central directory, which also works for zips
+/// whose sizes only follow the data
+fn read_zip(bytes: &[u8]) -> Result<Vec<ArchiveEntry>> {
+    let end = (0..=bytes.len().saturating_sub(22))
+        .rev()
+        .find(|&at| u32_at(bytes, at).ok() == Some(END_OF_CENTRAL_DIRECTORY))
+        .ok_or_else(|| invalid("zip archive has no central directory"))?;
+    let count = u16_at(bytes, end + 10)?;
+    let mut offset = u32_at(bytes, end + 16)? as usize;
+
+    let mut entries = Vec::new();
+    for _ in 0..count {
+        if u32_at(bytes, offset)? != CENTRAL_HEADER {
+            return Err(invalid("malformed zip central directory"));
+        }
+        let method = u16_at(bytes, offset + 10)?;
+        let crc = u32_at(bytes, offset + 16)?;
+        let compressed_size = u32_at(bytes, offset + 20)? as usize;
+        let size = u32_at(bytes, offset + 24)? as usize;
+        let name_len = usize::from(u16_at(bytes, offset + 28)?);
+        let extra_len = usize::from(u16_at(bytes, offset + 30)?);
+        let comment_len = usize::from(u16_at(bytes, offset + 32)?);
+        let local = u32_at(bytes, offset + 42)? as usize;
+        let name = bytes
+            .get(offset + 46..offset + 46 + name_len)
+            .map(|name| String::from_utf8_lossy(name).into_owned())
+            .ok_or_else(|| invalid("zip archive is truncated"))?;
+        offset += 46 + name_len + extra_len + comment_len;
+
+        if name.ends_with('/') {
+            continue;
+        }
+        if u32_at(bytes, local)? != LOCAL_HEADER {
+            return Err(invalid(format!("malformed zip entry for '{}'", name)));
+        }
+        let start = local
+            + 30
+            + usize::from(u16_at(bytes, local + 26)?)
+            + usize::from(u16_at(bytes, local + 28)?);
+        let compressed = bytes
+            .get(start..start + compressed_size)
+            .ok_or_else(|| invalid("zip archive is truncated"))?;
+
+        let data = match method {
+            STORED => compressed.to_vec(),
+            DEFLATED => {
+                // The stored size is untrusted, so read at most one byte past
+                // it rather than reserving it or inflating without bound
+                let mut data = Vec::new();
+                DeflateDecoder::new(compressed)
+                    .take(size as u64 + 1)
+                    .read_to_end(&mut data)
+                    .map_err(|e| invalid(format!("can't inflate '{}': {}", name, e)))?;
+                data
+            }
+            other => {
+                return Err(invalid(format!(
+                    "'{}' uses unsupported zip compression method {}",
+                    name, other
+                )))
+            }
+        };
+        if data.len() != size {
+            return Err(invalid(format!("'{}' doesn't match its stored size", name)));
+        }
+        let mut actual = Crc::new();
+        actual.update(&data);
+        if actual.sum() != crc {
+            return Err(invalid(format!("'{}' fails its CRC check", name)));
+        }
+        entries.push(ArchiveEntry { name, data });
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn entry(name: &str, data: &[u8]) -> ArchiveEntry {
+        ArchiveEntry {
+            name: name.to_string(),
+            data: data.to_vec(),
+        }
+    }
+
+    fn sample() -> Vec<ArchiveEntry> {
+        vec![
+            entry("notes.txt", b"first file\n"),
+            entry("src/main.rs", b"fn main() {}\n"),
+            entry("empty", b""),
+            entry("blob.bin", &[0, 159, 146, 150, 255]),
+            entry("large.txt", &b"0123456789".repeat(200)),
+        ]
+    }
+
+    fn modified() -> SystemTime {
+        // 2024-03-01 12:34:56 UTC
+        UNIX_EPOCH + Duration::from_secs(1_709_296_496)
+    }
+
+    #[test]
+    fn test_tar_round_trip() {
+        let bytes = write_archive(ArchiveFormat::Tar, &sample(), modified()).unwrap();
+        assert_eq!(bytes.len() % BLOCK, 0);
+        assert_eq!(&bytes[257..263], b"ustar\0");
+        assert_eq!(read_archive(&bytes).unwrap(), sample());
+    }
+
+    #[test]
+    fn test_zip_round_trip() {
+        let bytes = write_archive(ArchiveFormat::Zip, &sample(), modified()).unwrap();
+        assert!(bytes.starts_with(b"PK\x03\x04"));
+        assert_eq!(read_archive(&bytes).unwrap(), sample());
+    }
+
+    #[test]
+    fn test_empty_zip_round_trip() {
+        let bytes = write_archive(ArchiveFormat::Zip, &[], modified()).unwrap();
+        assert_eq!(read_archive(&bytes).unwrap(), Vec::new());
+    }
+
+    #[test]
+    fn test_base64_round_trip_ignores_line_breaks() {
+        for format in [ArchiveFormat::Tar, ArchiveFormat::Zip] {
+            let text = encode_archive(format, &sample(), modified()).unwrap();
+            let wrapped: String = text
+                .as_bytes()
+                .chunks(76)
+                .map(|line| format!("{}\r\n", std::str::from_utf8(line).unwrap()))
+                .collect();
+            assert_eq!(decode_archive(&wrapped).unwrap(), sample());
+        }
+    }
+
+    #[test]
+    fn test_long_tar_names_use_the_prefix() {
+        let name = format!("{}/{}", "d".repeat(120), "f".repeat(90));
+        let entries = vec![entry(&name, b"deep")];
+        let bytes = write_archive(ArchiveFormat::Tar, &entries, modified()).unwrap();
+        assert_eq!(read_archive(&bytes).unwrap(), entries);
+
+        let unsplittable = vec![entry(&"n".repeat(101), b"")];
+        assert!(write_archive(ArchiveFormat::Tar, &unsplittable, modified()).is_err());
+    }
+
+    #[test]
+    fn test_dos_datetime() {
+        // 12:34:56 on 2024-03-01
+        let (time, date) = dos_datetime(1_709_296_496);
+        assert_eq!(time, (12 << 11) | (34 << 5) | 28);
+        assert_eq!(date, (44 << 9) | (3 << 5) | 1);
+        assert_eq!(dos_datetime(0), (0, (1 << 5) | 1));
+    }
+
+    #[test]
+    fn test_rejects_unsafe_and_repeated_names() {
+        for name in ["", "../escape", "a/../../b", "/etc/passwd"] {
+            let result = write_archive(ArchiveFormat::Zip, &[entry(name, b"")], modified());
+            assert!(
+                matches!(result, Err(CatboardError::ArchiveError(_))),
+                "{:?} accepted",
+                name
+            );
+        }
+        let repeated = [entry("a", b"1"), entry("a", b"2")];
+        assert!(write_archive(ArchiveFormat::Tar, &repeated, modified()).is_err());
+    }
+
+    #[test]
+    fn test_rejects_corrupt_archives() {
+        assert!(decode_archive("not base64!").is_err());
+        assert!(read_archive(b"plain text").is_err());
+
+        let mut tar = write_archive(ArchiveFormat::Tar, &sample(), modified()).unwrap();
+        tar[0] ^= 1;
+        assert!(read_archive(&tar).is_err());
+
+        let mut zip = write_archive(ArchiveFormat::Zip, &[entry("a", b"")], modified()).unwrap();
+        // Flip the stored CRC of the only entry
+        zip[14] ^= 1;
+        let central = zip.len() - 22 - 47;
+        zip[central + 16] ^= 1;
+        let err = read_archive(&zip).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid archive: 'a' fails its CRC check");
+
+        let tar = write_archive(ArchiveFormat::Tar, &sample(), modified()).unwrap();
+        assert!(read_archive(&tar[..700]).is_err());
+    }
+
+    /// A tar member: header, data and padding
+    fn tar_member(name: &str, typeflag: u8, data: &[u8]) -> Vec<u8> {
+        let mut member = tar_header("", name, typeflag, data.len() as u64, 0).to_vec();
+        member.extend_from_slice(data);
+        member.resize(padded(member.len()), 0);
+        member
+    }
+
+    #[test]
+    fn test_reads_gnu_long_names() {
+        let long = format!("{}/file.txt", "d".repeat(150));
+        let mut tar = tar_member("././@LongLink", b'L', format!("{}\0", long).as_bytes());
+        tar.extend(tar_member(&long[..100], b'0', b"long"));
+        tar.extend(tar_member("short.txt", b'0', b"short"));
+        tar.resize(tar.len() + 2 * BLOCK, 0);
+
+        let entries = read_archive(&tar).unwrap();
+        assert_eq!(
+            entries,
+            [entry(&long, b"long"), entry("short.txt", b"short")]
+        );
+    }
+
+    #[test]
+    fn test_reads_pax_headers() {
+        let long = format!("{}/caf\u{E9}.txt", "p".repeat(200));
+        let records = format!("{} path={}\n", long.len() + 10, long);
+        assert_eq!(records.len(), long.len() + 10);
+        let mut tar = tar_member("PaxHeaders/g", b'g', b"6 a=b\n");
+        tar.extend(tar_member("PaxHeaders/x", b'x', records.as_bytes()));
+        tar.extend(tar_member("truncated-name", b'0', b"pax"));
+        // A pax size record overrides the header's
+        tar.extend(tar_member("PaxHeaders/y", b'x', b"10 size=3\n"));
+        tar.extend(tar_header("", "sized.txt", b'0', 0, 0));
+        tar.extend(b"abc");
+        tar.resize(padded(tar.len()), 0);
+        tar.resize(tar.len() + 2 * BLOCK, 0);
+
+        let entries = read_archive(&tar).unwrap();
+        assert_eq!(entries, [entry(&long, b"pax"), entry("sized.txt", b"abc")]);
+
+        let mut bad = tar_member("PaxHeaders/x", b'x', b"99 path=x\n");
+        bad.resize(bad.len() + 2 * BLOCK, 0);
+        let err = read_archive(&bad).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid archive: malformed pax extended header"
+        );
+    }
+
+    #[test]
+    fn test_long_names_apply_to_one_entry() {
+        // A long name before a directory isn't carried over to the next file
+        let mut tar = tar_member("././@LongLink", b'L', b"long-dir/\0");
+        tar.extend(tar_member("long-d", b'5', b""));
+        tar.extend(tar_member("file.txt", b'0', b"x"));
+        tar.resize(tar.len() + 2 * BLOCK, 0);
+        assert_eq!(read_archive(&tar).unwrap(), [entry("file.txt", b"x")]);
+    }
+
+    #[test]
+    fn test_zip_inflate_is_bounded_by_stored_size() {
+        let data = vec![b'a'; 64 * 1024];
+        let mut zip =
+            write_archive(ArchiveFormat::Zip, &[entry("bomb.txt", &data)], modified()).unwrap();
+        // Claim far less than the data inflates to
+        let central = (0..zip.len())
+            .find(|&at| u32_at(&zip, at).ok() == Some(CENTRAL_HEADER))
+            .unwrap();
+        zip[central + 24..central + 28].copy_from_slice(&16u32.to_le_bytes());
+        let err = read_archive(&zip).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid archive: 'bomb.txt' doesn't match its stored size"
+        );
+    }
+
+    #[test]
+    fn test_entry_name() {
+        assert_eq!(entry_name(Path::new("src/main.rs")), "src/main.rs");
+        assert_eq!(entry_name(Path::new("./notes.txt")), "notes.txt");
+        assert_eq!(entry_name(Path::new("/tmp/x/notes.txt")), "notes.txt");
+        assert_eq!(entry_name(Path::new("../up/notes.txt")), "notes.txt");
+    }
+
+    #[test]
+    fn test_extract_entries() {
+        let dir = TempDir::new().unwrap();
+        let written = extract_entries(&sample(), dir.path()).unwrap();
+        assert_eq!(written.len(), 5);
+        assert_eq!(
+            fs::read(dir.path().join("src").join("main.rs")).unwrap(),
+            b"fn main() {}\n"
+        );
+        assert_eq!(fs::read(dir.path().join("empty")).unwrap(), b"");
+    }
+
+    #[test]
+    fn test_extract_refuses_existing_files() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("notes.txt"), "keep me").unwrap();
+
+        let err = extract_entries(&sample(), dir.path()).unwrap_err();
+        assert!(err.to_string().contains("already exists"), "{}", err);
+        // Nothing was written, not even the entries that didn't clash
+        assert_eq!(fs::read(dir.path().join("notes.txt")).unwrap(), b"keep me");
+        assert!(!dir.path().join("src").exists());
+    }
+}
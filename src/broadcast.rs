@@ -0,0 +1,208 @@
+//! Copying to several clipboards at once for `--broadcast`.
+//!
+//! Each backend is tried in turn and gets its own report, so one failing
+//! clipboard (say, OSC 52 without a terminal) doesn't stop the others.
+//! Whether a partial failure fails the copy is up to the [`Require`] policy.
+
+use crate::clipboard::Clipboard;
+use crate::error::{CatboardError, Result};
+use std::fmt;
+use std::str::FromStr;
+
+/// How many backends must succeed for a broadcast to count as a success
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Require {
+    /// At least one backend
+    #[default]
+    Any,
+    /// Every backend
+    All,
+}
+
+impl FromStr for Require {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "any" => Ok(Require::Any),
+            "all" => Ok(Require::All),
+            _ => Err(format!("unknown policy '{}' (expected any or all)", s)),
+        }
+    }
+}
+
+impl fmt::Display for Require {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Require::Any => "any",
+            Require::All => "all",
+        })
+    }
+}
+
+/// The outcome of copying to one backend
+#[derive(Debug)]
+pub struct BackendReport {
+    pub name: String,
+    pub result: Result<()>,
+}
+
+/// A set of named clipboards that all receive the same text
+#[derive(Default)]
+pub struct Broadcast<'a> {
+    backends: Vec<(String, Result<Box<dyn Clipboard + 'a>>)>,
+}
+
+impl<'a> Broadcast<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a backend, or the error from failing to open it, which is then
+    /// reported as that backend's failure
+    pub fn add(&mut self, name: impl Into<String>, clipboard: Result<Box<dyn Clipboard + 'a>>) {
+        self.backends.push((name.into(), clipboard));
+    }
+
+    /// Copy `text` to every backend, in the order they were added
+    pub fn send(self, text: &str) -> Vec<BackendReport> {
+        self.backends
+            .into_iter()
+            .map(|(name, clipboard)| BackendReport {
+                name,
+                result: clipboard.and_then(|mut clipboard| clipboard.set_text(text)),
+            })
+            .collect()
+    }
+}
+
+/// Check `reports` against `require`
+///
+/// # Errors
+/// - `ClipboardError` naming the failed backends when the policy isn't met
+pub fn check_reports(reports: &[BackendReport], require: Require) -> Result<()> {
+    let failed: Vec<String> = reports
+        .iter()
+        .filter_map(|report| match &report.result {
+            Ok(()) => None,
+            Err(e) => Some(format!("{} ({})", report.name, e)),
+        })
+        .collect();
+    let met = match require {
+        Require::Any => failed.len() < reports.len(),
+        Require::All => failed.is_empty(),
+    };
+    if met {
+        return Ok(());
+    }
+    if reports.is_empty() {
+        return Err(CatboardError::ClipboardError(
+            "no clipboards to copy to".to_string(),
+        ));
+    }
+    Err(CatboardError::ClipboardError(format!(
+        "{} of {} clipboard(s) failed: {}",
+        failed.len(),
+        reports.len(),
+        failed.join(", ")
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    /// Records what it was given, or always fails
+    struct MockClipboard<'a> {
+        content: &'a RefCell<Option<String>>,
+        fail: bool,
+    }
+
+    impl Clipboard for MockClipboard<'_> {
+        fn set_text(&mut self, text: &str) -> Result<()> {
+            if self.fail {
+                return Err(CatboardError::ClipboardError("mock failure".to_string()));
+            }
+            *self.content.borrow_mut() = Some(text.to_string());
+            Ok(())
+        }
+
+        fn get_text(&mut self) -> Result<String> {
+            Ok(self.content.borrow().clone().unwrap_or_default())
+        }
+    }
+
+    fn mock(content: &RefCell<Option<String>>, fail: bool) -> Result<Box<dyn Clipboard + '_>> {
+        Ok(Box::new(MockClipboard { content, fail }))
+    }
+
+    #[test]
+    fn test_send_reaches_every_backend() {
+        let (a, b) = (RefCell::new(None), RefCell::new(None));
+        let mut broadcast = Broadcast::new();
+        broadcast.add("clipboard", mock(&a, false));
+        broadcast.add("primary", mock(&b, false));
+
+        let reports = broadcast.send("hello");
+        assert_eq!(reports.len(), 2);
+        assert!(reports.iter().all(|r| r.result.is_ok()));
+        assert_eq!(a.borrow().as_deref(), Some("hello"));
+        assert_eq!(b.borrow().as_deref(), Some("hello"));
+        assert!(check_reports(&reports, Require::All).is_ok());
+    }
+
+    #[test]
+    fn test_failure_does_not_stop_later_backends() {
+        let (a, b) = (RefCell::new(None), RefCell::new(None));
+        let mut broadcast = Broadcast::new();
+        broadcast.add("clipboard", mock(&a, true));
+        broadcast.add(
+            "osc52",
+            Err(CatboardError::ClipboardError("no terminal".to_string())),
+        );
+        broadcast.add("primary", mock(&b, false));
+
+        let reports = broadcast.send("text");
+        let names: Vec<_> = reports.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["clipboard", "osc52", "primary"]);
+        assert!(reports[0].result.is_err());
+        assert!(reports[1].result.is_err());
+        assert!(reports[2].result.is_ok());
+        assert_eq!(b.borrow().as_deref(), Some("text"));
+    }
+
+    #[test]
+    fn test_check_reports_policies() {
+        let (a, b) = (RefCell::new(None), RefCell::new(None));
+        let mut broadcast = Broadcast::new();
+        broadcast.add("clipboard", mock(&a, false));
+        broadcast.add("primary", mock(&b, true));
+        let reports = broadcast.send("x");
+
+        assert!(check_reports(&reports, Require::Any).is_ok());
+        let err = check_reports(&reports, Require::All).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Clipboard error: 1 of 2 clipboard(s) failed: primary (Clipboard error: mock failure)"
+        );
+    }
+
+    #[test]
+    fn test_check_reports_all_failed() {
+        let a = RefCell::new(None);
+        let mut broadcast = Broadcast::new();
+        broadcast.add("clipboard", mock(&a, true));
+        let reports = broadcast.send("x");
+        assert!(check_reports(&reports, Require::Any).is_err());
+        assert!(check_reports(&[], Require::Any).is_err());
+    }
+
+    #[test]
+    fn test_require_from_str() {
+        assert_eq!("any".parse::<Require>().unwrap(), Require::Any);
+        assert_eq!("ALL".parse::<Require>().unwrap(), Require::All);
+        assert!("some".parse::<Require>().is_err());
+        assert_eq!(Require::default().to_string(), "any");
+    }
+}
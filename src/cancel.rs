@@ -0,0 +1,80 @@
+//! Cooperative cancellation for long PDF extractions and directory walks.
+//!
+//! Set [`ReadOptions::cancel`](crate::ReadOptions::cancel) or
+//! [`WalkOptions::cancel`](crate::walk::WalkOptions::cancel) and call
+//! [`CancellationToken::cancel`] from another thread, such as a GUI's cancel
+//! button. The work checks the token between pages or files and returns
+//! `CatboardError::Cancelled`; a page or file already in progress is
+//! finished first.
+
+use crate::error::{CatboardError, Result};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// A shared cancel flag; clones refer to the same flag
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wrap a flag the caller already shares, where `true` means cancelled
+    pub fn from_flag(flag: Arc<AtomicBool>) -> Self {
+        Self(flag)
+    }
+
+    /// Ask the work holding this token to stop
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// `Cancelled` with the message from `what` if `token` has been cancelled
+pub(crate) fn check(
+    token: Option<&CancellationToken>,
+    what: impl FnOnce() -> String,
+) -> Result<()> {
+    match token {
+        Some(token) if token.is_cancelled() => Err(CatboardError::Cancelled(what())),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clones_share_the_flag() {
+        let token = CancellationToken::new();
+        let worker = token.clone();
+        assert!(!worker.is_cancelled());
+        token.cancel();
+        assert!(worker.is_cancelled());
+    }
+
+    #[test]
+    fn test_from_flag_uses_the_callers_flag() {
+        let flag = Arc::new(AtomicBool::new(false));
+        let token = CancellationToken::from_flag(Arc::clone(&flag));
+        flag.store(true, Ordering::Relaxed);
+        assert!(token.is_cancelled());
+    }
+
+    #[test]
+    fn test_check() {
+        let token = CancellationToken::new();
+        assert!(check(None, || unreachable!()).is_ok());
+        assert!(check(Some(&token), || unreachable!()).is_ok());
+
+        token.cancel();
+        let err = check(Some(&token), || "walking 'src'".to_string()).unwrap_err();
+        assert_eq!(err.to_string(), "Cancelled: walking 'src'");
+    }
+}
@@ -1,9 +1,185 @@
 use crate::error::{CatboardError, Result};
+use std::ffi::OsString;
+use std::fmt;
+use std::io::Write;
+use std::path::Path;
+
+/// Kinds of data the clipboard can hold
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipboardFormat {
+    Text,
+    Html,
+    Image,
+}
+
+impl fmt::Display for ClipboardFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ClipboardFormat::Text => "text",
+            ClipboardFormat::Html => "HTML",
+            ClipboardFormat::Image => "image",
+        };
+        f.write_str(name)
+    }
+}
+
+/// An image read from the clipboard, as 8-bit RGBA pixels
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClipboardImage {
+    pub width: usize,
+    pub height: usize,
+    /// Row-major RGBA bytes, `width * height * 4` long
+    pub rgba: Vec<u8>,
+}
 
 /// Trait for clipboard operations, allowing for mocking in tests
 pub trait Clipboard {
     fn set_text(&mut self, text: &str) -> Result<()>;
     fn get_text(&mut self) -> Result<String>;
+
+    /// Remove all clipboard contents (defaults to setting empty text)
+    fn clear(&mut self) -> Result<()> {
+        self.set_text("")
+    }
+
+    /// Read HTML from the clipboard (unsupported by default)
+    fn get_html(&mut self) -> Result<String> {
+        Err(CatboardError::ClipboardFormatUnavailable(
+            ClipboardFormat::Html,
+        ))
+    }
+
+    /// Read an image from the clipboard (unsupported by default)
+    fn get_image(&mut self) -> Result<ClipboardImage> {
+        Err(CatboardError::ClipboardFormatUnavailable(
+            ClipboardFormat::Image,
+        ))
+    }
+
+    /// Formats currently on the clipboard (defaults to text, if readable)
+    fn get_available_formats(&mut self) -> Vec<ClipboardFormat> {
+        if self.get_text().is_ok() {
+            vec![ClipboardFormat::Text]
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Put raw `data` on the clipboard under the MIME type `mime`
+    /// (unsupported by default)
+    fn set_custom(&mut self, mime: &str, _data: &[u8]) -> Result<()> {
+        Err(CatboardError::ClipboardError(format!(
+            "this clipboard backend can't copy custom MIME types such as '{}'",
+            mime
+        )))
+    }
+}
+
+/// A boxed clipboard, so a backend can be chosen at runtime
+impl<C: Clipboard + ?Sized> Clipboard for Box<C> {
+    fn set_text(&mut self, text: &str) -> Result<()> {
+        (**self).set_text(text)
+    }
+
+    fn get_text(&mut self) -> Result<String> {
+        (**self).get_text()
+    }
+
+    fn clear(&mut self) -> Result<()> {
+        (**self).clear()
+    }
+
+    fn get_html(&mut self) -> Result<String> {
+        (**self).get_html()
+    }
+
+    fn get_image(&mut self) -> Result<ClipboardImage> {
+        (**self).get_image()
+    }
+
+    fn get_available_formats(&mut self) -> Vec<ClipboardFormat> {
+        (**self).get_available_formats()
+    }
+
+    fn set_custom(&mut self, mime: &str, data: &[u8]) -> Result<()> {
+        (**self).set_custom(mime, data)
+    }
+}
+
+/// Wraps a clipboard so every `set_text` is read back and compared.
+///
+/// This catches backends that report success without updating the
+/// clipboard. Another application writing the clipboard between the set and
+/// the read also shows up as a mismatch, so a failure means "not confirmed"
+/// rather than "definitely lost".
+///
+/// Every mismatch gives the offset of the first byte that differs. A
+/// read-back that is a shorter prefix of what was copied is reported as
+/// truncation, since some backends silently cap how much they store.
+pub struct VerifiedClipboard<C> {
+    inner: C,
+}
+
+impl<C: Clipboard> VerifiedClipboard<C> {
+    pub fn new(inner: C) -> Self {
+        Self { inner }
+    }
+}
+
+impl<C: Clipboard> Clipboard for VerifiedClipboard<C> {
+    fn set_text(&mut self, text: &str) -> Result<()> {
+        self.inner.set_text(text)?;
+        let actual = self.inner.get_text()?;
+        if actual == text {
+            return Ok(());
+        }
+        let offset = text
+            .bytes()
+            .zip(actual.bytes())
+            .position(|(expected, found)| expected != found)
+            .unwrap_or_else(|| actual.len().min(text.len()));
+        if offset == actual.len() && actual.len() < text.len() {
+            return Err(CatboardError::ClipboardError(format!(
+                "verification failed: clipboard truncated the copy to {} of {} bytes \
+                 (first missing byte at offset {})",
+                actual.len(),
+                text.len(),
+                offset
+            )));
+        }
+        Err(CatboardError::ClipboardError(format!(
+            "verification failed: read back {} bytes after copying {} \
+             (first difference at byte offset {})",
+            actual.len(),
+            text.len(),
+            offset
+        )))
+    }
+
+    fn get_text(&mut self) -> Result<String> {
+        self.inner.get_text()
+    }
+
+    fn clear(&mut self) -> Result<()> {
+        self.inner.clear()
+    }
+
+    fn get_html(&mut self) -> Result<String> {
+        self.inner.get_html()
+    }
+
+    fn get_image(&mut self) -> Result<ClipboardImage> {
+        self.inner.get_image()
+    }
+
+    fn get_available_formats(&mut self) -> Vec<ClipboardFormat> {
+        self.inner.get_available_formats()
+    }
+
+    /// Custom formats can't be read back, so they are passed through unverified
+    fn set_custom(&mut self, mime: &str, data: &[u8]) -> Result<()> {
+        self.inner.set_custom(mime, data)
+    }
 }
 
 /// System clipboard implementation using arboard
@@ -29,8 +205,344 @@ impl Clipboard for SystemClipboard {
     fn get_text(&mut self) -> Result<String> {
         self.clipboard
             .get_text()
+            .map_err(|e| map_get_error(e, ClipboardFormat::Text))
+    }
+
+    fn clear(&mut self) -> Result<()> {
+        self.clipboard
+            .clear()
             .map_err(|e| CatboardError::ClipboardError(e.to_string()))
     }
+
+    fn get_html(&mut self) -> Result<String> {
+        self.clipboard
+            .get()
+            .html()
+            .map_err(|e| map_get_error(e, ClipboardFormat::Html))
+    }
+
+    fn get_image(&mut self) -> Result<ClipboardImage> {
+        let image = self
+            .clipboard
+            .get_image()
+            .map_err(|e| map_get_error(e, ClipboardFormat::Image))?;
+        Ok(ClipboardImage {
+            width: image.width,
+            height: image.height,
+            rgba: image.bytes.into_owned(),
+        })
+    }
+
+    fn get_available_formats(&mut self) -> Vec<ClipboardFormat> {
+        // arboard has no format listing, so probe each format in turn
+        let mut formats = Vec::new();
+        if self.clipboard.get().text().is_ok() {
+            formats.push(ClipboardFormat::Text);
+        }
+        if self.clipboard.get().html().is_ok() {
+            formats.push(ClipboardFormat::Html);
+        }
+        if self.clipboard.get().image().is_ok() {
+            formats.push(ClipboardFormat::Image);
+        }
+        formats
+    }
+
+    #[cfg(all(
+        unix,
+        not(any(target_os = "macos", target_os = "android", target_os = "emscripten"))
+    ))]
+    fn set_custom(&mut self, mime: &str, data: &[u8]) -> Result<()> {
+        run_custom_target_command(custom_target_command(&Session::detect(), mime), mime, data)
+    }
+
+    #[cfg(not(all(
+        unix,
+        not(any(target_os = "macos", target_os = "android", target_os = "emscripten"))
+    )))]
+    fn set_custom(&mut self, mime: &str, _data: &[u8]) -> Result<()> {
+        Err(CatboardError::ClipboardError(format!(
+            "copying as '{}' is only supported on Linux and BSD",
+            mime
+        )))
+    }
+}
+
+/// The command that puts stdin on the clipboard as `mime`: `wl-copy` on
+/// Wayland, `xclip` otherwise. arboard only offers text, HTML and images.
+#[cfg_attr(
+    not(all(
+        unix,
+        not(any(target_os = "macos", target_os = "android", target_os = "emscripten"))
+    )),
+    allow(dead_code)
+)]
+fn custom_target_command(session: &Session, mime: &str) -> (&'static str, Vec<String>) {
+    if session.wayland {
+        ("wl-copy", vec!["--type".to_string(), mime.to_string()])
+    } else {
+        let args = ["-selection", "clipboard", "-t", mime, "-i"];
+        ("xclip", args.iter().map(|arg| arg.to_string()).collect())
+    }
+}
+
+/// Run `command`, feeding it `data` on stdin
+#[cfg(all(
+    unix,
+    not(any(target_os = "macos", target_os = "android", target_os = "emscripten"))
+))]
+fn run_custom_target_command(
+    (program, args): (&'static str, Vec<String>),
+    mime: &str,
+    data: &[u8],
+) -> Result<()> {
+    use std::process::{Command, Stdio};
+
+    // Both tools fork to keep serving the selection, so their output is not
+    // captured: a pipe held open by the background child would never close
+    let mut child = Command::new(program)
+        .args(&args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => CatboardError::ClipboardError(format!(
+                "copying as '{}' needs {}, which was not found",
+                mime, program
+            )),
+            _ => CatboardError::ClipboardError(format!("could not run {}: {}", program, e)),
+        })?;
+
+    let written = child
+        .stdin
+        .take()
+        .map_or(Ok(()), |mut stdin| stdin.write_all(data));
+    let status = child
+        .wait()
+        .map_err(|e| CatboardError::ClipboardError(format!("{} failed: {}", program, e)))?;
+    written.map_err(|e| CatboardError::ClipboardError(format!("{} failed: {}", program, e)))?;
+    if !status.success() {
+        return Err(CatboardError::ClipboardError(format!(
+            "{} failed to copy as '{}' ({})",
+            program, mime, status
+        )));
+    }
+    Ok(())
+}
+
+/// The X11/Wayland PRIMARY selection, which middle-click pastes from
+///
+/// Only Linux and the BSDs have one; [`PrimarySelection::new`] fails
+/// elsewhere.
+pub struct PrimarySelection {
+    #[cfg(all(
+        unix,
+        not(any(target_os = "macos", target_os = "android", target_os = "emscripten"))
+    ))]
+    clipboard: arboard::Clipboard,
+}
+
+#[cfg(all(
+    unix,
+    not(any(target_os = "macos", target_os = "android", target_os = "emscripten"))
+))]
+impl PrimarySelection {
+    pub fn new() -> Result<Self> {
+        let clipboard =
+            arboard::Clipboard::new().map_err(|e| CatboardError::ClipboardError(e.to_string()))?;
+        Ok(Self { clipboard })
+    }
+}
+
+#[cfg(all(
+    unix,
+    not(any(target_os = "macos", target_os = "android", target_os = "emscripten"))
+))]
+impl Clipboard for PrimarySelection {
+    fn set_text(&mut self, text: &str) -> Result<()> {
+        use arboard::{LinuxClipboardKind, SetExtLinux};
+        self.clipboard
+            .set()
+            .clipboard(LinuxClipboardKind::Primary)
+            .text(text)
+            .map_err(|e| CatboardError::ClipboardError(e.to_string()))
+    }
+
+    fn get_text(&mut self) -> Result<String> {
+        use arboard::{GetExtLinux, LinuxClipboardKind};
+        self.clipboard
+            .get()
+            .clipboard(LinuxClipboardKind::Primary)
+            .text()
+            .map_err(|e| map_get_error(e, ClipboardFormat::Text))
+    }
+}
+
+#[cfg(not(all(
+    unix,
+    not(any(target_os = "macos", target_os = "android", target_os = "emscripten"))
+)))]
+impl PrimarySelection {
+    pub fn new() -> Result<Self> {
+        Err(Self::unsupported())
+    }
+
+    fn unsupported() -> CatboardError {
+        CatboardError::ClipboardError(
+            "the PRIMARY selection only exists on Linux and BSD".to_string(),
+        )
+    }
+}
+
+#[cfg(not(all(
+    unix,
+    not(any(target_os = "macos", target_os = "android", target_os = "emscripten"))
+)))]
+impl Clipboard for PrimarySelection {
+    fn set_text(&mut self, _text: &str) -> Result<()> {
+        Err(Self::unsupported())
+    }
+
+    fn get_text(&mut self) -> Result<String> {
+        Err(Self::unsupported())
+    }
+}
+
+/// Map a failed clipboard read, distinguishing a missing format from other failures
+fn map_get_error(error: arboard::Error, format: ClipboardFormat) -> CatboardError {
+    match error {
+        arboard::Error::ContentNotAvailable => CatboardError::ClipboardFormatUnavailable(format),
+        e => CatboardError::ClipboardError(e.to_string()),
+    }
+}
+
+/// Whether the system clipboard can be opened, without reading or writing it.
+///
+/// This only proves a connection can be made. On Linux the X11 or Wayland
+/// server may still refuse or drop a later `set_text`, so treat `true` as
+/// "worth trying" rather than a guarantee.
+pub fn clipboard_available() -> bool {
+    SystemClipboard::new().is_ok()
+}
+
+/// The kind of session catboard is running in, as far as the environment
+/// tells, for choosing a clipboard backend and explaining the choice
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Session {
+    /// `DISPLAY` is set (which includes XWayland under Wayland)
+    pub x11: bool,
+    /// `WAYLAND_DISPLAY` is set
+    pub wayland: bool,
+    /// `SSH_CONNECTION` or `SSH_TTY` is set
+    pub ssh: bool,
+    /// `TMUX` is set
+    pub tmux: bool,
+}
+
+impl Session {
+    /// Detect the session from this process's environment
+    pub fn detect() -> Self {
+        Self::from_env(|var| std::env::var_os(var))
+    }
+
+    /// Detect the session from `var`, which looks up an environment variable.
+    /// Empty values count as unset.
+    pub fn from_env(var: impl Fn(&str) -> Option<OsString>) -> Self {
+        let set = |name: &str| var(name).is_some_and(|v| !v.is_empty());
+        Self {
+            x11: set("DISPLAY"),
+            wayland: set("WAYLAND_DISPLAY"),
+            ssh: set("SSH_CONNECTION") || set("SSH_TTY"),
+            tmux: set("TMUX"),
+        }
+    }
+
+    /// Detect the session from a fixed set of `(name, value)` variables,
+    /// as if they were the whole environment
+    pub fn from_vars(vars: &[(&str, &str)]) -> Self {
+        Self::from_env(|name| {
+            vars.iter()
+                .find(|(var, _)| *var == name)
+                .map(|(_, value)| OsString::from(value))
+        })
+    }
+
+    /// Whether a graphical display is reachable. Only X11/Wayland systems
+    /// can lack one; on macOS and Windows this is always `true`.
+    pub fn has_display(&self) -> bool {
+        cfg!(any(target_os = "macos", windows)) || self.x11 || self.wayland
+    }
+}
+
+/// Summarizes the session for diagnostics, e.g. "Wayland with XWayland, SSH"
+impl fmt::Display for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let display = if cfg!(target_os = "macos") {
+            "macOS"
+        } else if cfg!(windows) {
+            "Windows"
+        } else {
+            match (self.x11, self.wayland) {
+                (true, true) => "Wayland with XWayland",
+                (false, true) => "Wayland",
+                (true, false) => "X11",
+                (false, false) => "no display",
+            }
+        };
+        f.write_str(display)?;
+        if self.ssh {
+            f.write_str(", SSH")?;
+        }
+        if self.tmux {
+            f.write_str(", tmux")?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether a graphical display is reachable for the clipboard.
+///
+/// Only Linux and other X11/Wayland systems can lack one; elsewhere this is
+/// always `true`.
+pub fn display_detected() -> bool {
+    Session::detect().has_display()
+}
+
+/// Whether this process is running inside an SSH session
+pub fn is_ssh_session() -> bool {
+    Session::detect().ssh
+}
+
+/// Where [`copy_or_print`] delivered the text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CopyTarget {
+    Clipboard,
+    /// The clipboard failed for `reason`, so the text went to the writer
+    Stdout {
+        reason: String,
+    },
+}
+
+/// Copy text to `clipboard`, or write it to `out` if the clipboard can't be
+/// opened or refuses the text.
+///
+/// Only clipboard failures fall back; any other error is returned as is.
+pub fn copy_or_print<C: Clipboard>(
+    clipboard: Result<C>,
+    text: &str,
+    out: &mut dyn Write,
+) -> Result<CopyTarget> {
+    match clipboard.and_then(|mut clipboard| clipboard.set_text(text)) {
+        Ok(()) => Ok(CopyTarget::Clipboard),
+        Err(CatboardError::ClipboardError(reason)) => {
+            out.write_all(text.as_bytes())
+                .and_then(|()| out.flush())
+                .map_err(|source| CatboardError::io("-", source))?;
+            Ok(CopyTarget::Stdout { reason })
+        }
+        Err(e) => Err(e),
+    }
 }
 
 /// Copy text to the system clipboard
@@ -39,6 +551,75 @@ pub fn copy_to_clipboard(text: &str) -> Result<()> {
     clipboard.set_text(text)
 }
 
+/// Read text from the system clipboard
+pub fn paste_from_clipboard() -> Result<String> {
+    let mut clipboard = SystemClipboard::new()?;
+    clipboard.get_text()
+}
+
+/// Read HTML from the system clipboard
+pub fn paste_html_from_clipboard() -> Result<String> {
+    let mut clipboard = SystemClipboard::new()?;
+    clipboard.get_html()
+}
+
+/// Save the image on the system clipboard to `path` as a PNG.
+///
+/// Returns the image's width and height.
+///
+/// # Errors
+/// - `ClipboardFormatUnavailable` if the clipboard holds no image
+/// - `IoError` if the PNG cannot be written
+pub fn paste_image_to_file(path: &Path) -> Result<(usize, usize)> {
+    let mut clipboard = SystemClipboard::new()?;
+    let image = clipboard.get_image()?;
+    write_png(&image, path)?;
+    Ok((image.width, image.height))
+}
+
+/// Encode a clipboard image as PNG at `path`, replacing it atomically
+#[cfg(feature = "image")]
+pub fn write_png(image: &ClipboardImage, path: &Path) -> Result<()> {
+    use image::{ImageError, RgbaImage};
+    use std::io::Cursor;
+
+    let io_err = |source| CatboardError::io(path, source);
+    let buffer = RgbaImage::from_raw(image.width as u32, image.height as u32, image.rgba.clone())
+        .ok_or_else(|| {
+        CatboardError::ClipboardError("Malformed image data on clipboard".to_string())
+    })?;
+
+    let mut png = Cursor::new(Vec::new());
+    buffer
+        .write_to(&mut png, image::ImageFormat::Png)
+        .map_err(|e| match e {
+            ImageError::IoError(e) => io_err(e),
+            e => io_err(std::io::Error::new(std::io::ErrorKind::Other, e)),
+        })?;
+    crate::file::write_atomic(path, &png.into_inner())
+}
+
+/// Stub for builds without the `image` feature
+#[cfg(not(feature = "image"))]
+pub fn write_png(_image: &ClipboardImage, path: &Path) -> Result<()> {
+    Err(CatboardError::FeatureDisabled {
+        path: path.to_path_buf(),
+        feature: "image",
+    })
+}
+
+/// List the formats currently on the system clipboard
+pub fn clipboard_formats() -> Result<Vec<ClipboardFormat>> {
+    let mut clipboard = SystemClipboard::new()?;
+    Ok(clipboard.get_available_formats())
+}
+
+/// Clear the system clipboard
+pub fn clear_clipboard() -> Result<()> {
+    let mut clipboard = SystemClipboard::new()?;
+    clipboard.clear()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -148,6 +729,171 @@ mod tests {
         assert_eq!(result, "Second");
     }
 
+    #[test]
+    fn test_mock_clipboard_default_clear() {
+        let mut clipboard = MockClipboard::new();
+
+        clipboard.set_text("Something").unwrap();
+        clipboard.clear().unwrap();
+
+        let result = clipboard.get_text().unwrap();
+        assert_eq!(result, "");
+    }
+
+    #[test]
+    fn test_mock_clipboard_default_formats() {
+        let mut clipboard = MockClipboard::new();
+        assert_eq!(
+            clipboard.get_available_formats(),
+            vec![ClipboardFormat::Text]
+        );
+
+        let mut clipboard = MockClipboard::with_failure();
+        assert!(clipboard.get_available_formats().is_empty());
+    }
+
+    #[test]
+    fn test_mock_clipboard_default_html_unavailable() {
+        let mut clipboard = MockClipboard::new();
+        assert!(matches!(
+            clipboard.get_html(),
+            Err(CatboardError::ClipboardFormatUnavailable(
+                ClipboardFormat::Html
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_map_get_error() {
+        assert!(matches!(
+            map_get_error(arboard::Error::ContentNotAvailable, ClipboardFormat::Image),
+            CatboardError::ClipboardFormatUnavailable(ClipboardFormat::Image)
+        ));
+        assert!(matches!(
+            map_get_error(arboard::Error::ClipboardOccupied, ClipboardFormat::Text),
+            CatboardError::ClipboardError(_)
+        ));
+    }
+
+    #[test]
+    fn test_mock_clipboard_default_image_unavailable() {
+        let mut clipboard = MockClipboard::new();
+        assert!(matches!(
+            clipboard.get_image(),
+            Err(CatboardError::ClipboardFormatUnavailable(
+                ClipboardFormat::Image
+            ))
+        ));
+    }
+
+    fn red_pixel_image() -> ClipboardImage {
+        ClipboardImage {
+            width: 2,
+            height: 1,
+            rgba: vec![255, 0, 0, 255, 0, 0, 255, 128],
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "image")]
+    fn test_write_png_round_trip() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("shot.png");
+
+        write_png(&red_pixel_image(), &path).unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        assert!(bytes.starts_with(&[0x89, b'P', b'N', b'G']));
+        let decoded = image::load_from_memory(&bytes).unwrap().to_rgba8();
+        assert_eq!(decoded.dimensions(), (2, 1));
+        assert_eq!(decoded.into_raw(), red_pixel_image().rgba);
+    }
+
+    #[test]
+    #[cfg(feature = "image")]
+    fn test_write_png_malformed_data() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let image = ClipboardImage {
+            width: 10,
+            height: 10,
+            rgba: vec![0; 4],
+        };
+        let result = write_png(&image, &dir.path().join("bad.png"));
+        assert!(matches!(result, Err(CatboardError::ClipboardError(_))));
+    }
+
+    #[test]
+    #[cfg(feature = "image")]
+    fn test_write_png_missing_dir() {
+        let result = write_png(&red_pixel_image(), Path::new("/nonexistent/dir/x.png"));
+        assert!(matches!(result, Err(CatboardError::IoError { .. })));
+    }
+
+    #[test]
+    #[cfg(not(feature = "image"))]
+    fn test_write_png_without_feature() {
+        let result = write_png(&red_pixel_image(), Path::new("x.png"));
+        assert!(matches!(
+            result,
+            Err(CatboardError::FeatureDisabled {
+                feature: "image",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_session_from_env() {
+        assert_eq!(Session::from_vars(&[]), Session::default());
+        assert_eq!(
+            Session::from_vars(&[("DISPLAY", ":0"), ("WAYLAND_DISPLAY", "wayland-0")]),
+            Session {
+                x11: true,
+                wayland: true,
+                ..Session::default()
+            }
+        );
+        assert!(Session::from_vars(&[("SSH_TTY", "/dev/pts/1")]).ssh);
+        assert!(Session::from_vars(&[("SSH_CONNECTION", "10.0.0.1 22 10.0.0.2 22")]).ssh);
+        assert!(Session::from_vars(&[("TMUX", "/tmp/tmux-1000/default,1,0")]).tmux);
+    }
+
+    #[test]
+    fn test_session_ignores_empty_vars() {
+        let empty = Session::from_vars(&[("DISPLAY", ""), ("SSH_TTY", ""), ("TMUX", "")]);
+        assert_eq!(empty, Session::default());
+    }
+
+    #[test]
+    #[cfg(all(unix, not(target_os = "macos")))]
+    fn test_session_display_names() {
+        assert_eq!(Session::from_vars(&[]).to_string(), "no display");
+        assert!(!Session::from_vars(&[]).has_display());
+        assert_eq!(Session::from_vars(&[("DISPLAY", ":0")]).to_string(), "X11");
+        assert_eq!(
+            Session::from_vars(&[("WAYLAND_DISPLAY", "w")]).to_string(),
+            "Wayland"
+        );
+        assert_eq!(
+            Session::from_vars(&[
+                ("DISPLAY", ":0"),
+                ("WAYLAND_DISPLAY", "w"),
+                ("SSH_TTY", "t"),
+                ("TMUX", "x")
+            ])
+            .to_string(),
+            "Wayland with XWayland, SSH, tmux"
+        );
+        assert!(Session::from_vars(&[("WAYLAND_DISPLAY", "w")]).has_display());
+    }
+
+    #[test]
+    fn test_clipboard_format_display() {
+        assert_eq!(ClipboardFormat::Text.to_string(), "text");
+        assert_eq!(ClipboardFormat::Html.to_string(), "HTML");
+        assert_eq!(ClipboardFormat::Image.to_string(), "image");
+    }
+
     #[test]
     fn test_mock_clipboard_large_content() {
         let mut clipboard = MockClipboard::new();
@@ -172,4 +918,228 @@ mod tests {
             assert_eq!(content, "Integration test content");
         }
     }
+
+    #[test]
+    fn test_clipboard_available_matches_open() {
+        // Headless CI has no clipboard; either way the probe must agree
+        // with opening one and must not panic
+        assert_eq!(clipboard_available(), SystemClipboard::new().is_ok());
+    }
+
+    #[test]
+    fn test_copy_or_print_uses_clipboard() {
+        let mut out = Vec::new();
+        let target = copy_or_print(Ok(MockClipboard::new()), "hi", &mut out).unwrap();
+        assert_eq!(target, CopyTarget::Clipboard);
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn test_copy_or_print_falls_back_when_set_fails() {
+        let mut out = Vec::new();
+        let target = copy_or_print(Ok(MockClipboard::with_failure()), "hi\n", &mut out).unwrap();
+        assert_eq!(
+            target,
+            CopyTarget::Stdout {
+                reason: "Mock clipboard failure".to_string()
+            }
+        );
+        assert_eq!(out, b"hi\n");
+    }
+
+    #[test]
+    fn test_copy_or_print_falls_back_when_open_fails() {
+        let mut out = Vec::new();
+        let unavailable: Result<MockClipboard> =
+            Err(CatboardError::ClipboardError("no display".to_string()));
+        let target = copy_or_print(unavailable, "text", &mut out).unwrap();
+        assert!(matches!(target, CopyTarget::Stdout { reason } if reason == "no display"));
+        assert_eq!(out, b"text");
+    }
+
+    #[test]
+    fn test_copy_or_print_keeps_other_errors() {
+        let mut out = Vec::new();
+        let failed: Result<MockClipboard> = Err(CatboardError::NoFilesSpecified);
+        assert!(matches!(
+            copy_or_print(failed, "text", &mut out),
+            Err(CatboardError::NoFilesSpecified)
+        ));
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn test_boxed_clipboard_forwards() {
+        let mut clipboard: Box<dyn Clipboard> = Box::new(MockClipboard::new());
+        clipboard.set_text("boxed").unwrap();
+        assert_eq!(clipboard.get_text().unwrap(), "boxed");
+        assert_eq!(
+            clipboard.get_available_formats(),
+            vec![ClipboardFormat::Text]
+        );
+    }
+
+    /// Accepts every write but keeps returning the old content
+    struct StaleClipboard {
+        content: String,
+    }
+
+    impl Clipboard for StaleClipboard {
+        fn set_text(&mut self, _text: &str) -> Result<()> {
+            Ok(())
+        }
+
+        fn get_text(&mut self) -> Result<String> {
+            Ok(self.content.clone())
+        }
+    }
+
+    #[test]
+    fn test_verified_clipboard_accepts_round_trip() {
+        let mut clipboard = VerifiedClipboard::new(MockClipboard::new());
+        clipboard.set_text("checked").unwrap();
+        assert_eq!(clipboard.get_text().unwrap(), "checked");
+    }
+
+    #[test]
+    fn test_verified_clipboard_detects_stale_content() {
+        let mut clipboard = VerifiedClipboard::new(StaleClipboard {
+            content: "old".to_string(),
+        });
+        let err = clipboard.set_text("new text").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Clipboard error: verification failed: read back 3 bytes after copying 8 \
+             (first difference at byte offset 0)"
+        );
+
+        // Same length, differing part way
+        let err = clipboard.set_text("ole").unwrap_err();
+        assert!(err
+            .to_string()
+            .ends_with("(first difference at byte offset 2)"));
+        // Longer than what was copied
+        let err = clipboard.set_text("ol").unwrap_err();
+        assert!(err
+            .to_string()
+            .ends_with("(first difference at byte offset 2)"));
+    }
+
+    /// Stores only the first `limit` bytes of each write
+    struct TruncatingClipboard {
+        limit: usize,
+        content: String,
+    }
+
+    impl Clipboard for TruncatingClipboard {
+        fn set_text(&mut self, text: &str) -> Result<()> {
+            self.content = text[..text.len().min(self.limit)].to_string();
+            Ok(())
+        }
+
+        fn get_text(&mut self) -> Result<String> {
+            Ok(self.content.clone())
+        }
+    }
+
+    #[test]
+    fn test_verified_clipboard_detects_truncation() {
+        let mut clipboard = VerifiedClipboard::new(TruncatingClipboard {
+            limit: 5,
+            content: String::new(),
+        });
+        let err = clipboard.set_text("0123456789").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Clipboard error: verification failed: clipboard truncated the copy to 5 of 10 \
+             bytes (first missing byte at offset 5)"
+        );
+
+        // Content within the limit round-trips
+        clipboard.set_text("01234").unwrap();
+    }
+
+    #[test]
+    fn test_verified_clipboard_propagates_set_failure() {
+        let mut clipboard = VerifiedClipboard::new(MockClipboard::with_failure());
+        assert!(matches!(
+            clipboard.set_text("x"),
+            Err(CatboardError::ClipboardError(msg)) if msg == "Mock clipboard failure"
+        ));
+    }
+
+    /// Records every custom-format write
+    #[derive(Default)]
+    struct RecordingClipboard {
+        custom: Vec<(String, Vec<u8>)>,
+    }
+
+    impl Clipboard for RecordingClipboard {
+        fn set_text(&mut self, text: &str) -> Result<()> {
+            self.set_custom("text/plain", text.as_bytes())
+        }
+
+        fn get_text(&mut self) -> Result<String> {
+            Err(CatboardError::ClipboardFormatUnavailable(
+                ClipboardFormat::Text,
+            ))
+        }
+
+        fn set_custom(&mut self, mime: &str, data: &[u8]) -> Result<()> {
+            self.custom.push((mime.to_string(), data.to_vec()));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_set_custom_unsupported_by_default() {
+        let mut clipboard = MockClipboard::new();
+        let err = clipboard
+            .set_custom("image/svg+xml", b"<svg/>")
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Clipboard error: this clipboard backend can't copy custom MIME types such as 'image/svg+xml'"
+        );
+        assert_eq!(clipboard.get_text().unwrap(), "");
+    }
+
+    #[test]
+    fn test_set_custom_forwarded_by_wrappers() {
+        let mut boxed = Box::<RecordingClipboard>::default();
+        Clipboard::set_custom(&mut boxed, "image/svg+xml", b"<svg/>").unwrap();
+        assert_eq!(
+            boxed.custom,
+            vec![("image/svg+xml".to_string(), b"<svg/>".to_vec())]
+        );
+
+        let mut verified = VerifiedClipboard::new(RecordingClipboard::default());
+        verified
+            .set_custom("application/x-figma", &[0, 1, 2])
+            .unwrap();
+        assert_eq!(
+            verified.inner.custom,
+            vec![("application/x-figma".to_string(), vec![0, 1, 2])]
+        );
+    }
+
+    #[test]
+    fn test_custom_target_command_per_session() {
+        let wayland = Session::from_vars(&[("WAYLAND_DISPLAY", "wayland-0"), ("DISPLAY", ":0")]);
+        assert_eq!(
+            custom_target_command(&wayland, "image/svg+xml"),
+            (
+                "wl-copy",
+                vec!["--type".to_string(), "image/svg+xml".to_string()]
+            )
+        );
+
+        let x11 = Session::from_vars(&[("DISPLAY", ":0")]);
+        let (program, args) = custom_target_command(&x11, "image/svg+xml");
+        assert_eq!(program, "xclip");
+        assert_eq!(
+            args,
+            ["-selection", "clipboard", "-t", "image/svg+xml", "-i"]
+        );
+    }
 }
@@ -0,0 +1,186 @@
+//! Language-aware filename comments for `--include-filename-comment`.
+
+use crate::error::Result;
+use crate::transform::Transform;
+use std::path::Path;
+
+/// How a language spells a comment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentStyle {
+    /// Comment runs to end of line, e.g. `//` or `#`
+    Line(&'static str),
+    /// Comment is wrapped in delimiters, e.g. `<!--` and `-->`
+    Block(&'static str, &'static str),
+}
+
+/// Style used for extensions not in [`COMMENT_STYLES`]
+pub const FALLBACK_STYLE: CommentStyle = CommentStyle::Line("#");
+
+/// Comment syntax by lowercase file extension
+const COMMENT_STYLES: &[(&[&str], CommentStyle)] = &[
+    (
+        &[
+            "c", "h", "cc", "cpp", "cxx", "hpp", "cs", "java", "kt", "kts", "scala", "go", "rs",
+            "swift", "js", "jsx", "mjs", "cjs", "ts", "tsx", "dart", "php", "groovy", "gradle",
+            "zig", "proto", "jsonc",
+        ],
+        CommentStyle::Line("//"),
+    ),
+    (
+        &[
+            "sh",
+            "bash",
+            "zsh",
+            "fish",
+            "py",
+            "rb",
+            "pl",
+            "r",
+            "toml",
+            "yaml",
+            "yml",
+            "conf",
+            "ini",
+            "cfg",
+            "mk",
+            "cmake",
+            "dockerfile",
+            "nix",
+            "ex",
+            "exs",
+            "tf",
+        ],
+        CommentStyle::Line("#"),
+    ),
+    (
+        &["sql", "lua", "hs", "elm", "ada"],
+        CommentStyle::Line("--"),
+    ),
+    (
+        &["lisp", "clj", "cljs", "el", "scm"],
+        CommentStyle::Line(";;"),
+    ),
+    (&["erl", "hrl", "tex", "m"], CommentStyle::Line("%")),
+    (&["vim"], CommentStyle::Line("\"")),
+    (&["bat", "cmd"], CommentStyle::Line("REM")),
+    (
+        &[
+            "html", "htm", "xml", "svg", "xhtml", "vue", "md", "markdown",
+        ],
+        CommentStyle::Block("<!--", "-->"),
+    ),
+    (&["css", "scss", "less"], CommentStyle::Block("/*", "*/")),
+    (&["ml", "mli"], CommentStyle::Block("(*", "*)")),
+];
+
+/// Look up the comment style for an extension (case-insensitive, leading dot allowed)
+pub fn comment_style_for_extension(extension: &str) -> CommentStyle {
+    let extension = extension.trim_start_matches('.').to_ascii_lowercase();
+    COMMENT_STYLES
+        .iter()
+        .find(|(extensions, _)| extensions.contains(&extension.as_str()))
+        .map(|&(_, style)| style)
+        .unwrap_or(FALLBACK_STYLE)
+}
+
+/// Look up the comment style for a path by its extension
+pub fn comment_style_for_path(path: &Path) -> CommentStyle {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some(extension) => comment_style_for_extension(extension),
+        None if is_makefile(path) => CommentStyle::Line("#"),
+        None => FALLBACK_STYLE,
+    }
+}
+
+fn is_makefile(path: &Path) -> bool {
+    matches!(
+        path.file_name().and_then(|n| n.to_str()),
+        Some("Makefile" | "makefile" | "GNUmakefile" | "Dockerfile")
+    )
+}
+
+/// A one-line comment naming `label`, followed by a newline
+pub fn filename_comment(style: CommentStyle, label: &str) -> String {
+    match style {
+        CommentStyle::Line(prefix) => format!("{} {}\n", prefix, label),
+        CommentStyle::Block(open, close) => format!("{} {} {}\n", open, label, close),
+    }
+}
+
+/// Transform that prefixes text with a filename comment
+#[derive(Debug, Clone)]
+pub struct FilenameComment {
+    pub style: CommentStyle,
+    pub label: String,
+}
+
+impl Transform for FilenameComment {
+    fn apply(&self, input: &str) -> Result<String> {
+        Ok(filename_comment(self.style, &self.label) + input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment_for(path: &str) -> String {
+        filename_comment(comment_style_for_path(Path::new(path)), path)
+    }
+
+    #[test]
+    fn test_c_like_languages() {
+        assert_eq!(comment_for("src/main.rs"), "// src/main.rs\n");
+        assert_eq!(comment_for("app.ts"), "// app.ts\n");
+        assert_eq!(comment_for("Main.java"), "// Main.java\n");
+        assert_eq!(comment_for("lib.c"), "// lib.c\n");
+        assert_eq!(comment_for("server.go"), "// server.go\n");
+    }
+
+    #[test]
+    fn test_hash_languages() {
+        assert_eq!(comment_for("build.sh"), "# build.sh\n");
+        assert_eq!(comment_for("script.py"), "# script.py\n");
+        assert_eq!(comment_for("Cargo.toml"), "# Cargo.toml\n");
+        assert_eq!(comment_for("ci.yml"), "# ci.yml\n");
+        assert_eq!(comment_for("Makefile"), "# Makefile\n");
+    }
+
+    #[test]
+    fn test_markup_languages() {
+        assert_eq!(comment_for("index.html"), "<!-- index.html -->\n");
+        assert_eq!(comment_for("README.md"), "<!-- README.md -->\n");
+        assert_eq!(comment_for("feed.xml"), "<!-- feed.xml -->\n");
+    }
+
+    #[test]
+    fn test_other_languages() {
+        assert_eq!(comment_for("query.sql"), "-- query.sql\n");
+        assert_eq!(comment_for("init.lua"), "-- init.lua\n");
+        assert_eq!(comment_for("style.css"), "/* style.css */\n");
+        assert_eq!(comment_for("core.clj"), ";; core.clj\n");
+        assert_eq!(comment_for("run.bat"), "REM run.bat\n");
+    }
+
+    #[test]
+    fn test_extension_case_insensitive() {
+        assert_eq!(comment_for("MAIN.RS"), "// MAIN.RS\n");
+        assert_eq!(comment_style_for_extension(".Py"), CommentStyle::Line("#"));
+    }
+
+    #[test]
+    fn test_filename_comment_transform() {
+        let transform = FilenameComment {
+            style: CommentStyle::Block("/*", "*/"),
+            label: "a.css".to_string(),
+        };
+        assert_eq!(transform.apply("p {}").unwrap(), "/* a.css */\np {}");
+    }
+
+    #[test]
+    fn test_fallback_style() {
+        assert_eq!(comment_for("notes.txt"), "# notes.txt\n");
+        assert_eq!(comment_for("LICENSE"), "# LICENSE\n");
+        assert_eq!(comment_style_for_extension("unknown"), FALLBACK_STYLE);
+    }
+}
@@ -0,0 +1,164 @@
+//! Yes/no prompts for `--confirm`, and password prompts for encrypted PDFs.
+
+use crate::error::{CatboardError, Result};
+use std::io::{self, BufRead, Write};
+
+/// A failure to show a prompt or read its answer
+fn prompt_error(source: io::Error) -> CatboardError {
+    CatboardError::io("-", source)
+}
+
+/// Whether `answer` accepts a prompt: `y` or `yes` in any case
+pub fn is_yes(answer: &str) -> bool {
+    matches!(answer.trim().to_ascii_lowercase().as_str(), "y" | "yes")
+}
+
+/// Ask `question` on `prompt` and read the answer from `input`.
+///
+/// Anything but yes, including an empty line or end of input, is a no.
+pub fn ask<R: BufRead, W: Write>(question: &str, mut input: R, mut prompt: W) -> Result<bool> {
+    write!(prompt, "{} [y/N] ", question).map_err(prompt_error)?;
+    prompt.flush().map_err(prompt_error)?;
+
+    let mut line = String::new();
+    input.read_line(&mut line).map_err(prompt_error)?;
+    Ok(is_yes(&line))
+}
+
+/// Ask for a secret such as a password on `prompt` and read it from
+/// `input`, without its line ending.
+///
+/// The prompt line is ended after the answer, as the Enter that ended it
+/// isn't echoed when typed on a terminal (see [`ask_secret_on_stdin`]).
+/// End of input gives an empty answer.
+pub fn ask_secret<R: BufRead, W: Write>(
+    question: &str,
+    mut input: R,
+    mut prompt: W,
+) -> Result<String> {
+    write!(prompt, "{}: ", question).map_err(prompt_error)?;
+    prompt.flush().map_err(prompt_error)?;
+
+    let mut line = String::new();
+    input.read_line(&mut line).map_err(prompt_error)?;
+    writeln!(prompt).map_err(prompt_error)?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// [`ask_secret`], reading the answer from stdin with echo turned off
+/// while it's typed if stdin is a terminal
+pub fn ask_secret_on_stdin<W: Write>(question: &str, prompt: W) -> Result<String> {
+    let stdin = io::stdin();
+    let _echo = sys::EchoOff::stdin();
+    ask_secret(question, stdin.lock(), prompt)
+}
+
+#[cfg(unix)]
+mod sys {
+    /// Turns off echo on stdin while alive, if stdin is a terminal
+    pub(super) struct EchoOff(Option<libc::termios>);
+
+    impl EchoOff {
+        pub(super) fn stdin() -> Self {
+            // SAFETY: termios is plain data, and tcgetattr fills it in
+            // before it's used
+            unsafe {
+                let mut saved: libc::termios = std::mem::zeroed();
+                if libc::tcgetattr(libc::STDIN_FILENO, &mut saved) != 0 {
+                    return Self(None);
+                }
+                let mut quiet = saved;
+                quiet.c_lflag &= !libc::ECHO;
+                libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &quiet);
+                Self(Some(saved))
+            }
+        }
+    }
+
+    impl Drop for EchoOff {
+        fn drop(&mut self) {
+            if let Some(saved) = &self.0 {
+                // SAFETY: restoring settings read by tcgetattr
+                unsafe {
+                    libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, saved);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod sys {
+    pub(super) struct EchoOff;
+
+    impl EchoOff {
+        pub(super) fn stdin() -> Self {
+            EchoOff
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_is_yes() {
+        for answer in ["y", "Y", "yes", "YES\n", "  yes  "] {
+            assert!(is_yes(answer), "{:?}", answer);
+        }
+        for answer in ["", "\n", "n", "no", "yep", "y es"] {
+            assert!(!is_yes(answer), "{:?}", answer);
+        }
+    }
+
+    #[test]
+    fn test_ask_writes_prompt_and_reads_answer() {
+        let mut prompt = Vec::new();
+        assert!(ask("Copy 3 bytes from a.txt?", Cursor::new("y\n"), &mut prompt).unwrap());
+        assert_eq!(prompt, b"Copy 3 bytes from a.txt? [y/N] ");
+    }
+
+    #[test]
+    fn test_ask_defaults_to_no() {
+        assert!(!ask("Copy?", Cursor::new("\n"), Vec::new()).unwrap());
+        assert!(!ask("Copy?", Cursor::new(""), Vec::new()).unwrap());
+        assert!(!ask("Copy?", Cursor::new("nope\n"), Vec::new()).unwrap());
+    }
+
+    #[test]
+    fn test_ask_secret_strips_line_ending() {
+        let mut prompt = Vec::new();
+        let answer = ask_secret(
+            "Password for 'a.pdf'",
+            Cursor::new("p4ss word\r\n"),
+            &mut prompt,
+        );
+        assert_eq!(answer.unwrap(), "p4ss word");
+        assert_eq!(prompt, b"Password for 'a.pdf': \n");
+        assert_eq!(
+            ask_secret("Password", Cursor::new(""), Vec::new()).unwrap(),
+            ""
+        );
+    }
+
+    /// Fails every read with `kind`
+    struct Broken(io::ErrorKind);
+
+    impl io::Read for Broken {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(self.0.into())
+        }
+    }
+
+    #[test]
+    fn test_prompt_errors_are_io_errors() {
+        // Not mistaken for a missing or unreadable input file
+        for kind in [io::ErrorKind::NotFound, io::ErrorKind::PermissionDenied] {
+            let input = io::BufReader::new(Broken(kind));
+            let err = ask_secret("Password", input, Vec::new()).unwrap_err();
+            assert!(matches!(err, CatboardError::IoError { .. }), "{:?}", err);
+        }
+    }
+}
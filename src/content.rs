@@ -0,0 +1,264 @@
+//! Structured extraction results.
+
+use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+
+/// How a file's text was obtained
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceKind {
+    /// Read directly as UTF-8 text
+    PlainText,
+    /// Embedded text extracted from a PDF
+    PdfText,
+    /// OCR of a scanned PDF with no embedded text
+    PdfOcr,
+    /// OCR of an image file
+    ImageOcr,
+}
+
+impl fmt::Display for SourceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            SourceKind::PlainText => "plain text",
+            SourceKind::PdfText => "PDF text",
+            SourceKind::PdfOcr => "PDF OCR",
+            SourceKind::ImageOcr => "image OCR",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A library used to extract text from PDFs.
+///
+/// The one chosen is tried first; if it can't open the file or finds no
+/// text in it, the others are tried in turn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PdfBackend {
+    /// pdf_oxide, a full PDF parser
+    #[default]
+    Oxide,
+    /// A built-in scanner that finds objects without the cross-reference
+    /// table, for damaged files; simple text and FlateDecode streams only
+    Basic,
+}
+
+impl PdfBackend {
+    /// Every backend, in the default fallback order
+    pub const ALL: [PdfBackend; 2] = [PdfBackend::Oxide, PdfBackend::Basic];
+
+    /// This backend followed by the others, in the order they're tried
+    pub fn fallback_order(self) -> Vec<PdfBackend> {
+        let mut order = vec![self];
+        order.extend(Self::ALL.iter().copied().filter(|&other| other != self));
+        order
+    }
+}
+
+impl fmt::Display for PdfBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PdfBackend::Oxide => "oxide",
+            PdfBackend::Basic => "basic",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for PdfBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "oxide" | "pdf_oxide" => Ok(PdfBackend::Oxide),
+            "basic" => Ok(PdfBackend::Basic),
+            _ => Err(format!(
+                "unknown PDF backend '{}' (expected oxide or basic)",
+                s
+            )),
+        }
+    }
+}
+
+/// A PDF form field and the value filled in to it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormField {
+    /// Fully qualified name, with parent field names joined by `.`
+    pub name: String,
+    pub value: FieldValue,
+}
+
+impl fmt::Display for FormField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.value {
+            FieldValue::Empty => write!(f, "{}:", self.name),
+            value => write!(f, "{}: {}", self.name, value),
+        }
+    }
+}
+
+/// The value of a form field; serializes as a JSON string, boolean, array
+/// or null
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum FieldValue {
+    /// Text, or the chosen option of a list or set of radio buttons
+    Text(String),
+    /// Whether a checkbox is ticked
+    Checked(bool),
+    /// The options picked in a multiple-choice list
+    Choices(Vec<String>),
+    /// Nothing filled in
+    Empty,
+}
+
+impl fmt::Display for FieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldValue::Text(text) => f.write_str(text),
+            FieldValue::Checked(true) => f.write_str("yes"),
+            FieldValue::Checked(false) => f.write_str("no"),
+            FieldValue::Choices(choices) => f.write_str(&choices.join(", ")),
+            FieldValue::Empty => Ok(()),
+        }
+    }
+}
+
+/// A page that could not be extracted from a paged document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageError {
+    /// 1-based page number
+    pub page: usize,
+    pub message: String,
+}
+
+impl fmt::Display for PageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "page {}: {}", self.page, self.message)
+    }
+}
+
+/// Extracted text along with how it was obtained
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractedContent {
+    pub text: String,
+    pub source: SourceKind,
+    /// Page count for paged documents such as PDFs
+    pub pages: Option<usize>,
+    /// Text encoding of the source, when it was read as text
+    pub detected_encoding: Option<String>,
+    /// Pages that failed to extract and were left out of `text`
+    pub failed_pages: Vec<PageError>,
+    /// The backend that extracted a PDF's text
+    pub pdf_backend: Option<PdfBackend>,
+    /// Whether the text keeps the page layout (`--pdf-layout`) rather than
+    /// following reading order
+    pub layout: bool,
+}
+
+impl ExtractedContent {
+    /// Content read directly from a UTF-8 text source
+    pub(crate) fn plain_text(text: String) -> Self {
+        Self::decoded_text(text, "utf-8")
+    }
+
+    /// Content read as text in the given encoding
+    pub(crate) fn decoded_text(text: String, encoding: &str) -> Self {
+        Self {
+            text,
+            source: SourceKind::PlainText,
+            pages: None,
+            detected_encoding: Some(encoding.to_string()),
+            failed_pages: Vec::new(),
+            pdf_backend: None,
+            layout: false,
+        }
+    }
+
+    /// Content produced by an extractor rather than read as text
+    #[cfg_attr(not(any(feature = "pdf", feature = "ocr")), allow(dead_code))]
+    pub(crate) fn extracted(text: String, source: SourceKind, pages: Option<usize>) -> Self {
+        Self {
+            text,
+            source,
+            pages,
+            detected_encoding: None,
+            failed_pages: Vec::new(),
+            pdf_backend: None,
+            layout: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_kind_display() {
+        assert_eq!(SourceKind::PlainText.to_string(), "plain text");
+        assert_eq!(SourceKind::PdfText.to_string(), "PDF text");
+        assert_eq!(SourceKind::PdfOcr.to_string(), "PDF OCR");
+        assert_eq!(SourceKind::ImageOcr.to_string(), "image OCR");
+    }
+
+    #[test]
+    fn test_plain_text_reports_utf8() {
+        let content = ExtractedContent::plain_text("hi".to_string());
+        assert_eq!(content.source, SourceKind::PlainText);
+        assert_eq!(content.pages, None);
+        assert_eq!(content.detected_encoding.as_deref(), Some("utf-8"));
+        assert!(content.failed_pages.is_empty());
+    }
+
+    #[test]
+    fn test_page_error_display() {
+        let err = PageError {
+            page: 3,
+            message: "bad stream".to_string(),
+        };
+        assert_eq!(err.to_string(), "page 3: bad stream");
+    }
+
+    #[test]
+    fn test_form_field_display_and_json() {
+        let field = |name: &str, value| FormField {
+            name: name.to_string(),
+            value,
+        };
+        let fields = [
+            field("name", FieldValue::Text("Ada".to_string())),
+            field("subscribe", FieldValue::Checked(true)),
+            field("tags", FieldValue::Choices(vec!["a".into(), "b".into()])),
+            field("notes", FieldValue::Empty),
+        ];
+        let lines: Vec<String> = fields.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            lines,
+            ["name: Ada", "subscribe: yes", "tags: a, b", "notes:"]
+        );
+
+        let values: Vec<String> = fields
+            .iter()
+            .map(|f| serde_json::to_string(&f.value).unwrap())
+            .collect();
+        assert_eq!(values, [r#""Ada""#, "true", r#"["a","b"]"#, "null"]);
+    }
+
+    #[test]
+    fn test_pdf_backend_parsing_and_order() {
+        assert_eq!("oxide".parse(), Ok(PdfBackend::Oxide));
+        assert_eq!("PDF_OXIDE".parse(), Ok(PdfBackend::Oxide));
+        assert_eq!("basic".parse(), Ok(PdfBackend::Basic));
+        assert!("poppler"
+            .parse::<PdfBackend>()
+            .unwrap_err()
+            .contains("poppler"));
+
+        assert_eq!(
+            PdfBackend::Basic.fallback_order(),
+            vec![PdfBackend::Basic, PdfBackend::Oxide]
+        );
+        assert_eq!(PdfBackend::default().fallback_order(), PdfBackend::ALL);
+    }
+}
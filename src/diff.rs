@@ -0,0 +1,71 @@
+//! Unified diffs between two texts, for `--diff`.
+
+use similar::TextDiff;
+
+/// Default number of unchanged lines shown around each change
+pub const DEFAULT_CONTEXT: usize = 3;
+
+/// Produce a unified diff from `old` to `new`.
+///
+/// Returns an empty string when the texts are identical.
+pub fn unified_diff(
+    old_name: &str,
+    old: &str,
+    new_name: &str,
+    new: &str,
+    context: usize,
+) -> String {
+    TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(context)
+        .header(old_name, new_name)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unified_diff_single_change() {
+        let old = "one\ntwo\nthree\n";
+        let new = "one\n2\nthree\n";
+
+        let diff = unified_diff("a.txt", old, "b.txt", new, DEFAULT_CONTEXT);
+        assert_eq!(
+            diff,
+            "--- a.txt\n+++ b.txt\n@@ -1,3 +1,3 @@\n one\n-two\n+2\n three\n"
+        );
+    }
+
+    #[test]
+    fn test_unified_diff_context_radius() {
+        let old = "1\n2\n3\n4\n5\n6\n7\n";
+        let new = "1\n2\n3\nfour\n5\n6\n7\n";
+
+        let diff = unified_diff("a", old, "b", new, 1);
+        assert_eq!(diff, "--- a\n+++ b\n@@ -3,3 +3,3 @@\n 3\n-4\n+four\n 5\n");
+
+        let diff = unified_diff("a", old, "b", new, 0);
+        assert_eq!(diff, "--- a\n+++ b\n@@ -4 +4 @@\n-4\n+four\n");
+    }
+
+    #[test]
+    fn test_unified_diff_separate_hunks() {
+        let old: String = (1..=20).map(|n| format!("line {}\n", n)).collect();
+        let new = old
+            .replace("line 2\n", "line two\n")
+            .replace("line 19\n", "line nineteen\n");
+
+        let diff = unified_diff("a", &old, "b", &new, 1);
+        assert_eq!(diff.matches("@@ -").count(), 2);
+    }
+
+    #[test]
+    fn test_unified_diff_identical() {
+        assert_eq!(
+            unified_diff("a", "same\n", "b", "same\n", DEFAULT_CONTEXT),
+            ""
+        );
+    }
+}
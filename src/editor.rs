@@ -0,0 +1,146 @@
+//! Composing text in the user's editor for `--edit`.
+//!
+//! The editor is `$VISUAL`, then `$EDITOR`, then a platform default. It may
+//! include arguments, as in `code --wait`. On Unix it runs through `sh` the
+//! way git runs editors, so quoting works; elsewhere it is split on
+//! whitespace.
+
+use crate::error::{CatboardError, Result};
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Editor used when neither `$VISUAL` nor `$EDITOR` is set
+#[cfg(windows)]
+pub const DEFAULT_EDITOR: &str = "notepad";
+
+/// Editor used when neither `$VISUAL` nor `$EDITOR` is set
+#[cfg(not(windows))]
+pub const DEFAULT_EDITOR: &str = "vi";
+
+/// The editor command to run: `$VISUAL`, `$EDITOR` or [`DEFAULT_EDITOR`],
+/// skipping variables that are set but blank
+pub fn editor_command() -> String {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_EDITOR.to_string())
+}
+
+/// Build the command that opens `path` in `editor`
+fn command_for(editor: &str, path: &Path) -> Result<Command> {
+    if cfg!(unix) {
+        let mut command = Command::new("sh");
+        command
+            .arg("-c")
+            .arg(format!("{} \"$@\"", editor))
+            .arg(editor)
+            .arg(path);
+        return Ok(command);
+    }
+
+    let mut words = editor.split_whitespace();
+    let program = words
+        .next()
+        .ok_or_else(|| CatboardError::EditorError("no editor command given".to_string()))?;
+    let mut command = Command::new(program);
+    command.args(words).arg(path);
+    Ok(command)
+}
+
+/// Open a temporary file holding `initial` in `editor` and return what it
+/// holds once the editor exits.
+///
+/// The temporary file is removed afterwards, whether or not editing
+/// succeeded.
+///
+/// # Errors
+/// - `EditorError` if the editor can't be started, exits unsuccessfully or
+///   leaves the file empty
+/// - `EncodingError` if the saved file isn't valid UTF-8
+pub fn edit_text(editor: &str, initial: &str) -> Result<String> {
+    let temp = tempfile::Builder::new()
+        .prefix("catboard-edit-")
+        .suffix(".txt")
+        .tempfile()
+        .map_err(|e| CatboardError::io(env::temp_dir(), e))?
+        // Closed, so editors on Windows can replace it; removed on drop
+        .into_temp_path();
+    fs::write(&temp, initial).map_err(|e| CatboardError::io(&*temp, e))?;
+
+    let status = command_for(editor, &temp)?
+        .status()
+        .map_err(|e| CatboardError::EditorError(format!("could not run '{}': {}", editor, e)))?;
+    if !status.success() {
+        let code = status
+            .code()
+            .map_or_else(|| "a signal".to_string(), |code| format!("status {}", code));
+        return Err(CatboardError::EditorError(format!(
+            "'{}' exited with {}; nothing was copied",
+            editor, code
+        )));
+    }
+
+    let text = crate::file::read_verbatim(&temp, 0)?;
+    if text.trim().is_empty() {
+        return Err(CatboardError::EditorError(
+            "the file was left empty; nothing was copied".to_string(),
+        ));
+    }
+    Ok(text)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// An editor command running `body` as a shell function, which works
+    /// because editors run through `sh`
+    fn fake_editor(body: &str) -> String {
+        format!("edit() {{ {}; }}; edit", body)
+    }
+
+    #[test]
+    fn test_edit_text_returns_what_the_editor_saved() {
+        let dir = TempDir::new().unwrap();
+        let seen = dir.path().join("seen");
+        let editor = fake_editor(&format!(
+            "cp \"$1\" '{}'; printf 'fn main() {{}}\\n' >> \"$1\"; echo \"$1\" > '{}.path'",
+            seen.display(),
+            seen.display()
+        ));
+
+        let text = edit_text(&editor, "// draft\n").unwrap();
+        assert_eq!(text, "// draft\nfn main() {}\n");
+        // The editor was given the initial text, and the file is gone
+        assert_eq!(fs::read_to_string(&seen).unwrap(), "// draft\n");
+        let temp = fs::read_to_string(seen.with_extension("path")).unwrap();
+        assert!(!Path::new(temp.trim()).exists());
+    }
+
+    #[test]
+    fn test_edit_text_passes_editor_arguments() {
+        let editor = fake_editor("printf '%s' \"$1\" > \"$2\"");
+        assert_eq!(
+            edit_text(&format!("{} --wait", editor), "").unwrap(),
+            "--wait"
+        );
+    }
+
+    #[test]
+    fn test_edit_text_aborts_on_failure() {
+        let editor = fake_editor("echo text > \"$1\"; exit 3");
+        let err = edit_text(&editor, "").unwrap_err();
+        assert!(err.to_string().contains("exited with status 3"), "{}", err);
+
+        let editor = fake_editor("true");
+        let err = edit_text(&editor, "").unwrap_err();
+        assert!(err.to_string().contains("left empty"), "{}", err);
+
+        let err = edit_text("/nonexistent/editor", "").unwrap_err();
+        assert!(matches!(err, CatboardError::EditorError(_)));
+    }
+}
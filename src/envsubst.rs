@@ -0,0 +1,168 @@
+//! Environment variable expansion for `--expand-env`.
+//!
+//! `${NAME}` and `$NAME` are replaced with the variable's value, where a
+//! name is a letter or underscore followed by letters, digits and
+//! underscores. `$$` is a literal `$`. A `$` that doesn't start a reference,
+//! and a `${` without a valid name and closing brace, are copied as they
+//! are, so shell snippets with `$1` or `${x:-y}` survive. References to
+//! unset variables are kept intact unless strict mode makes them an error.
+
+use crate::error::{CatboardError, Result};
+use crate::transform::Transform;
+use std::env;
+
+fn is_name_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// Expand references in `text` using `lookup` for their values
+///
+/// # Errors
+/// - `TransformError` in `strict` mode, naming every unset variable
+pub fn expand_env_with<F>(text: &str, strict: bool, lookup: F) -> Result<String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut output = String::with_capacity(text.len());
+    let mut unset: Vec<&str> = Vec::new();
+    let mut rest = text;
+
+    while let Some(dollar) = rest.find('$') {
+        output.push_str(&rest[..dollar]);
+        let after = &rest[dollar + 1..];
+
+        if let Some(after) = after.strip_prefix('$') {
+            output.push('$');
+            rest = after;
+            continue;
+        }
+
+        // The name, and how much of `rest` the reference covers
+        let reference = if let Some(braced) = after.strip_prefix('{') {
+            braced
+                .find('}')
+                .map(|close| &braced[..close])
+                .filter(|name| name.starts_with(is_name_start) && name.chars().all(is_name_char))
+                .map(|name| (name, dollar + name.len() + 3))
+        } else if after.starts_with(is_name_start) {
+            let len = after.find(|c| !is_name_char(c)).unwrap_or(after.len());
+            Some((&after[..len], dollar + len + 1))
+        } else {
+            None
+        };
+
+        match reference {
+            Some((name, end)) => {
+                match lookup(name) {
+                    Some(value) => output.push_str(&value),
+                    None => {
+                        if !unset.contains(&name) {
+                            unset.push(name);
+                        }
+                        output.push_str(&rest[dollar..end]);
+                    }
+                }
+                rest = &rest[end..];
+            }
+            None => {
+                output.push('$');
+                rest = after;
+            }
+        }
+    }
+    output.push_str(rest);
+
+    if strict && !unset.is_empty() {
+        return Err(CatboardError::TransformError {
+            name: "expand-env",
+            message: format!("not set: {}", unset.join(", ")),
+        });
+    }
+    Ok(output)
+}
+
+/// Expand references in `text` from the process environment
+pub fn expand_env(text: &str, strict: bool) -> Result<String> {
+    expand_env_with(text, strict, |name| env::var(name).ok())
+}
+
+/// `--expand-env` as a transform; `strict` is `--strict-env`
+#[derive(Debug, Clone, Copy)]
+pub struct ExpandEnv {
+    pub strict: bool,
+}
+
+impl Transform for ExpandEnv {
+    fn apply(&self, input: &str) -> Result<String> {
+        expand_env(input, self.strict)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "HOME" => Some("/home/pat".to_string()),
+            "PORT" => Some("8080".to_string()),
+            "EMPTY" => Some(String::new()),
+            "PRICE" => Some("$5".to_string()),
+            _ => None,
+        }
+    }
+
+    fn expand(text: &str) -> String {
+        expand_env_with(text, false, lookup).unwrap()
+    }
+
+    #[test]
+    fn test_expands_set_variables() {
+        assert_eq!(expand("dir=${HOME}/src"), "dir=/home/pat/src");
+        assert_eq!(expand("listen $PORT;"), "listen 8080;");
+        assert_eq!(expand("$HOME:$PORT"), "/home/pat:8080");
+        assert_eq!(expand("a${EMPTY}b"), "ab");
+        // Unbraced names run as far as name characters do
+        assert_eq!(expand("$PORT_NUMBER $PORT-1"), "$PORT_NUMBER 8080-1");
+    }
+
+    #[test]
+    fn test_keeps_unset_variables() {
+        assert_eq!(expand("${MISSING} and $MISSING"), "${MISSING} and $MISSING");
+    }
+
+    #[test]
+    fn test_strict_names_unset_variables() {
+        let err = expand_env_with("$A ${B} $A $HOME", true, lookup).unwrap_err();
+        assert_eq!(err.to_string(), "expand-env failed: not set: A, B");
+        assert_eq!(expand_env_with("$HOME", true, lookup).unwrap(), "/home/pat");
+    }
+
+    #[test]
+    fn test_escaped_dollars() {
+        assert_eq!(expand("cost: $$5"), "cost: $5");
+        assert_eq!(expand("$${HOME}"), "${HOME}");
+        assert_eq!(expand("$$$HOME"), "$/home/pat");
+        assert_eq!(expand("$$$$"), "$$");
+    }
+
+    #[test]
+    fn test_leaves_other_dollars_alone() {
+        assert_eq!(expand("echo $1 $? $"), "echo $1 $? $");
+        assert_eq!(
+            expand("${x:-default} ${} ${HOME"),
+            "${x:-default} ${} ${HOME"
+        );
+        assert_eq!(expand("${1X} $€"), "${1X} $€");
+        assert_eq!(expand_env_with("${x:-y}", true, lookup).unwrap(), "${x:-y}");
+    }
+
+    #[test]
+    fn test_values_are_not_expanded_again() {
+        assert_eq!(expand("$PRICE"), "$5");
+    }
+}
@@ -1,4 +1,8 @@
-use std::path::PathBuf;
+use crate::clipboard::ClipboardFormat;
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use thiserror::Error;
 
 /// Errors that can occur during catboard operations
@@ -14,24 +18,175 @@ pub enum CatboardError {
     BinaryFile(PathBuf),
 
     #[error("Failed to extract text from '{path}': {message}")]
-    ExtractionError { path: PathBuf, message: String },
+    ExtractionError {
+        path: PathBuf,
+        message: String,
+        /// Boxed so thiserror doesn't generate the nightly-only `provide()`
+        backtrace: Box<Backtrace>,
+    },
 
     #[error("Failed to read file '{path}': {source}")]
     IoError {
         path: PathBuf,
         #[source]
         source: std::io::Error,
+        backtrace: Box<Backtrace>,
     },
 
     #[error("Clipboard error: {0}")]
     ClipboardError(String),
 
+    #[error("Clipboard has no {0} content")]
+    ClipboardFormatUnavailable(ClipboardFormat),
+
     #[error("No files specified")]
     NoFilesSpecified,
+
+    #[error("Invalid arguments: {0}")]
+    InvalidArguments(String),
+
+    #[error("No data received on stdin within {0:?}")]
+    StdinTimeout(Duration),
+
+    #[error("Watch error: {0}")]
+    WatchError(String),
+
+    #[error("Invalid template: {0}")]
+    TemplateError(String),
+
+    #[error("Refusing to copy: found {0} likely secret(s)")]
+    SecretsDetected(usize),
+
+    #[error("Input too small: '{path}' is {size} bytes, below the minimum of {min}")]
+    TooSmall {
+        path: PathBuf,
+        size: usize,
+        min: usize,
+    },
+
+    #[error("Input too large: '{path}' is {size} bytes, above --max-size of {max}; use --stream-to to write it to a file without loading it into memory")]
+    TooLarge { path: PathBuf, size: u64, max: u64 },
+
+    #[error("Invalid UTF-8 in '{path}' at byte offset {offset} (0x{byte:02X}); use --force-text to replace invalid bytes")]
+    EncodingError {
+        path: PathBuf,
+        offset: usize,
+        byte: u8,
+    },
+
+    #[error("Input is empty: {0}")]
+    EmptyInput(PathBuf),
+
+    #[error("Line range starts at line {start}, but '{path}' has only {lines} line(s)")]
+    LineRangeOutOfBounds {
+        path: PathBuf,
+        start: usize,
+        lines: usize,
+    },
+
+    #[error("Cannot read '{path}': catboard was built without the '{feature}' feature")]
+    FeatureDisabled {
+        path: PathBuf,
+        feature: &'static str,
+    },
+
+    #[error("{name} failed: {message}")]
+    TransformError { name: &'static str, message: String },
+
+    #[error("'{0}' already exists; use --force to overwrite it or --backup to keep a copy")]
+    DestinationExists(PathBuf),
+
+    #[error("Invalid archive: {0}")]
+    ArchiveError(String),
+
+    #[error("Editor failed: {0}")]
+    EditorError(String),
+
+    #[error("'{0}' is password-protected; pass --pdf-password to open it")]
+    PasswordRequired(PathBuf),
+
+    #[error("Wrong password for '{0}'")]
+    WrongPassword(PathBuf),
+
+    #[error("'{0}' has no form fields")]
+    NoFormFields(PathBuf),
+
+    #[error("Cancelled: {0}")]
+    Cancelled(String),
+
+    #[error("None of the {tried} file(s) given to --first could be read; last error: {last}")]
+    NoReadableCandidate {
+        tried: usize,
+        last: Box<CatboardError>,
+    },
+}
+
+impl CatboardError {
+    /// An I/O failure on `path`, capturing a backtrace when
+    /// `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE` is set
+    pub fn io(path: impl Into<PathBuf>, source: io::Error) -> Self {
+        CatboardError::IoError {
+            path: path.into(),
+            source,
+            backtrace: Box::new(Backtrace::capture()),
+        }
+    }
+
+    /// Map an I/O error on `path` to `FileNotFound` or `PermissionDenied`
+    /// when its kind says so, and to `IoError` otherwise
+    pub fn from_io(path: impl Into<PathBuf>, source: io::Error) -> Self {
+        match source.kind() {
+            io::ErrorKind::NotFound => CatboardError::FileNotFound(path.into()),
+            io::ErrorKind::PermissionDenied => CatboardError::PermissionDenied(path.into()),
+            _ => CatboardError::io(path, source),
+        }
+    }
+
+    /// A failure to extract text from `path`, capturing a backtrace when
+    /// `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE` is set
+    pub fn extraction(path: impl Into<PathBuf>, message: impl Into<String>) -> Self {
+        CatboardError::ExtractionError {
+            path: path.into(),
+            message: message.into(),
+            backtrace: Box::new(Backtrace::capture()),
+        }
+    }
+
+    /// The backtrace captured when the error was created, if any
+    pub fn backtrace(&self) -> Option<&Backtrace> {
+        match self {
+            CatboardError::ExtractionError { backtrace, .. }
+            | CatboardError::IoError { backtrace, .. }
+                if backtrace.status() == BacktraceStatus::Captured =>
+            {
+                Some(backtrace.as_ref())
+            }
+            _ => None,
+        }
+    }
 }
 
 pub type Result<T> = std::result::Result<T, CatboardError>;
 
+/// Attach a path to the error of an [`io::Result`]
+///
+/// ```
+/// use catboard::error::ResultExt;
+///
+/// let err = std::fs::read("missing.txt").with_path("missing.txt").unwrap_err();
+/// assert!(matches!(err, catboard::CatboardError::FileNotFound(_)));
+/// ```
+pub trait ResultExt<T> {
+    /// Map the error with [`CatboardError::from_io`], naming `path`
+    fn with_path(self, path: impl AsRef<Path>) -> Result<T>;
+}
+
+impl<T> ResultExt<T> for io::Result<T> {
+    fn with_path(self, path: impl AsRef<Path>) -> Result<T> {
+        self.map_err(|e| CatboardError::from_io(path.as_ref(), e))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -66,25 +221,227 @@ mod tests {
         assert_eq!(err.to_string(), "No files specified");
     }
 
+    #[test]
+    fn test_stdin_timeout_error_display() {
+        let err = CatboardError::StdinTimeout(Duration::from_secs(5));
+        assert_eq!(err.to_string(), "No data received on stdin within 5s");
+    }
+
+    #[test]
+    fn test_watch_error_display() {
+        let err = CatboardError::WatchError("Cannot watch stdin".to_string());
+        assert_eq!(err.to_string(), "Watch error: Cannot watch stdin");
+    }
+
+    #[test]
+    fn test_template_error_display() {
+        let err = CatboardError::TemplateError("unknown placeholder '{author}'".to_string());
+        assert_eq!(
+            err.to_string(),
+            "Invalid template: unknown placeholder '{author}'"
+        );
+    }
+
+    #[test]
+    fn test_invalid_arguments_error_display() {
+        let err = CatboardError::InvalidArguments("--diff needs exactly two files".to_string());
+        assert_eq!(
+            err.to_string(),
+            "Invalid arguments: --diff needs exactly two files"
+        );
+    }
+
     #[test]
     fn test_io_error_display() {
         let io_err = std::io::Error::new(std::io::ErrorKind::Other, "disk full");
-        let err = CatboardError::IoError {
-            path: PathBuf::from("data.txt"),
-            source: io_err,
-        };
+        let err = CatboardError::io(PathBuf::from("data.txt"), io_err);
         assert_eq!(err.to_string(), "Failed to read file 'data.txt': disk full");
     }
 
     #[test]
-    fn test_extraction_error_display() {
-        let err = CatboardError::ExtractionError {
+    fn test_io_error_source_is_wrapped_error() {
+        use std::error::Error;
+
+        let io_err = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "locked");
+        let err = CatboardError::io("data.txt", io_err);
+        let source = err
+            .source()
+            .and_then(|s| s.downcast_ref::<std::io::Error>())
+            .expect("source should be the io::Error");
+        assert_eq!(source.kind(), std::io::ErrorKind::PermissionDenied);
+        assert_eq!(source.to_string(), "locked");
+    }
+
+    #[test]
+    fn test_with_path_maps_not_found() {
+        let result: io::Result<()> = Err(io::Error::from(io::ErrorKind::NotFound));
+        let err = result.with_path("gone.txt").unwrap_err();
+        assert!(matches!(err, CatboardError::FileNotFound(p) if p == Path::new("gone.txt")));
+    }
+
+    #[test]
+    fn test_with_path_maps_permission_denied() {
+        let result: io::Result<()> = Err(io::Error::from(io::ErrorKind::PermissionDenied));
+        let err = result.with_path("/root/secret").unwrap_err();
+        assert!(
+            matches!(err, CatboardError::PermissionDenied(p) if p == Path::new("/root/secret"))
+        );
+    }
+
+    #[test]
+    fn test_with_path_wraps_other_kinds() {
+        let result: io::Result<()> = Err(io::Error::new(io::ErrorKind::InvalidData, "bad bytes"));
+        let err = result.with_path("data.bin").unwrap_err();
+        assert!(matches!(
+            &err,
+            CatboardError::IoError { path, source, .. }
+                if path == Path::new("data.bin") && source.kind() == io::ErrorKind::InvalidData
+        ));
+        assert_eq!(err.to_string(), "Failed to read file 'data.bin': bad bytes");
+    }
+
+    #[test]
+    fn test_with_path_passes_ok_through() {
+        let result: io::Result<u8> = Ok(7);
+        assert_eq!(result.with_path("any").unwrap(), 7);
+    }
+
+    #[test]
+    fn test_extraction_error_has_no_source() {
+        use std::error::Error;
+
+        let err = CatboardError::extraction("document.pdf", "Invalid PDF format");
+        assert!(err.source().is_none());
+    }
+
+    #[test]
+    fn test_backtrace_only_when_captured() {
+        let captured = CatboardError::IoError {
+            path: PathBuf::from("data.txt"),
+            source: std::io::Error::new(std::io::ErrorKind::Other, "disk full"),
+            backtrace: Box::new(Backtrace::force_capture()),
+        };
+        assert!(captured.backtrace().is_some());
+
+        let disabled = CatboardError::ExtractionError {
             path: PathBuf::from("document.pdf"),
             message: "Invalid PDF format".to_string(),
+            backtrace: Box::new(Backtrace::disabled()),
         };
+        assert!(disabled.backtrace().is_none());
+        assert!(CatboardError::NoFilesSpecified.backtrace().is_none());
+    }
+
+    #[test]
+    fn test_extraction_error_display() {
+        let err = CatboardError::extraction(PathBuf::from("document.pdf"), "Invalid PDF format");
         assert_eq!(
             err.to_string(),
             "Failed to extract text from 'document.pdf': Invalid PDF format"
         );
     }
+
+    #[test]
+    fn test_secrets_detected_error_display() {
+        let err = CatboardError::SecretsDetected(2);
+        assert_eq!(
+            err.to_string(),
+            "Refusing to copy: found 2 likely secret(s)"
+        );
+    }
+
+    #[test]
+    fn test_feature_disabled_error_display() {
+        let err = CatboardError::FeatureDisabled {
+            path: PathBuf::from("report.pdf"),
+            feature: "pdf",
+        };
+        assert_eq!(
+            err.to_string(),
+            "Cannot read 'report.pdf': catboard was built without the 'pdf' feature"
+        );
+    }
+
+    #[test]
+    fn test_no_readable_candidate_display() {
+        let err = CatboardError::NoReadableCandidate {
+            tried: 3,
+            last: Box::new(CatboardError::FileNotFound(PathBuf::from("c.toml"))),
+        };
+        assert_eq!(
+            err.to_string(),
+            "None of the 3 file(s) given to --first could be read; last error: File not found: c.toml"
+        );
+    }
+
+    #[test]
+    fn test_transform_error_display() {
+        let err = CatboardError::TransformError {
+            name: "url-decode",
+            message: "invalid utf-8".to_string(),
+        };
+        assert_eq!(err.to_string(), "url-decode failed: invalid utf-8");
+    }
+
+    #[test]
+    fn test_empty_input_error_display() {
+        let err = CatboardError::EmptyInput(PathBuf::from("-"));
+        assert_eq!(err.to_string(), "Input is empty: -");
+    }
+
+    #[test]
+    fn test_too_large_error_display() {
+        let err = CatboardError::TooLarge {
+            path: PathBuf::from("dump.sql"),
+            size: 900,
+            max: 512,
+        };
+        assert_eq!(
+            err.to_string(),
+            "Input too large: 'dump.sql' is 900 bytes, above --max-size of 512; use --stream-to to write it to a file without loading it into memory"
+        );
+    }
+
+    #[test]
+    fn test_encoding_error_display() {
+        let err = CatboardError::EncodingError {
+            path: PathBuf::from("latin1.txt"),
+            offset: 7,
+            byte: 0xE9,
+        };
+        assert_eq!(
+            err.to_string(),
+            "Invalid UTF-8 in 'latin1.txt' at byte offset 7 (0xE9); use --force-text to replace invalid bytes"
+        );
+    }
+
+    #[test]
+    fn test_too_small_error_display() {
+        let err = CatboardError::TooSmall {
+            path: PathBuf::from("download.csv"),
+            size: 3,
+            min: 100,
+        };
+        assert_eq!(
+            err.to_string(),
+            "Input too small: 'download.csv' is 3 bytes, below the minimum of 100"
+        );
+    }
+
+    #[test]
+    fn test_clipboard_format_unavailable_display() {
+        let err = CatboardError::ClipboardFormatUnavailable(ClipboardFormat::Html);
+        assert_eq!(err.to_string(), "Clipboard has no HTML content");
+    }
+
+    #[test]
+    fn test_pdf_password_errors_display() {
+        let err = CatboardError::PasswordRequired(PathBuf::from("statement.pdf"));
+        assert_eq!(
+            err.to_string(),
+            "'statement.pdf' is password-protected; pass --pdf-password to open it"
+        );
+        let err = CatboardError::WrongPassword(PathBuf::from("statement.pdf"));
+        assert_eq!(err.to_string(), "Wrong password for 'statement.pdf'");
+    }
 }
@@ -1,26 +1,195 @@
-use crate::error::{CatboardError, Result};
+use crate::cancel::CancellationToken;
+#[cfg(feature = "ocr")]
+use crate::content::SourceKind;
+use crate::content::{ExtractedContent, FormField, PdfBackend};
+use crate::error::{CatboardError, Result, ResultExt};
 use crate::ocr;
-use pdf_oxide::PdfDocument;
+#[cfg(feature = "pdf")]
+use crate::pdf::{extract_pdf_fields as extract_form_fields, extract_pdf_text};
 use std::ffi::OsStr;
 use std::fs;
-use std::io::{self, Read};
+use std::io::{self, BufRead, Read, Write};
 use std::path::Path;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
 
 /// Maximum bytes to check for binary content detection
-const BINARY_CHECK_SIZE: usize = 8192;
+pub const BINARY_CHECK_SIZE: usize = 8192;
+
+/// Files up to this size are read in one go and then sniffed, checked and
+/// decoded in memory, rather than opened separately for each step
+const SMALL_FILE_SIZE: u64 = BINARY_CHECK_SIZE as u64;
+
+/// Number of leading bytes read when sniffing a file's content type
+const SNIFF_SIZE: usize = 16;
+
+/// UTF-16 byte order marks, which mark text that would otherwise look binary
+const UTF16LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// Magic number at the start of every PDF file
+const PDF_SIGNATURE: &[u8] = b"%PDF-";
+
+/// Default text inserted between PDF pages
+pub const DEFAULT_PAGE_SEPARATOR: &str = "\n";
+
+/// Options controlling how files are read and extracted
+#[derive(Debug, Clone)]
+pub struct ReadOptions {
+    /// Inserted between PDF pages; `{page}` expands to the number of the
+    /// page that follows (e.g. `"\n--- page {page} ---\n"`)
+    pub page_separator: String,
+    /// Fail the whole PDF on the first page that cannot be extracted,
+    /// instead of skipping it and keeping the pages that worked
+    pub strict_pages: bool,
+    /// PDF backend tried first; the others are fallbacks
+    pub pdf_backend: PdfBackend,
+    /// Keep the page layout of PDF text, so columns and tables stay side by
+    /// side; backends that can't place text fall back to reading order
+    pub pdf_layout: bool,
+    /// User password for encrypted PDFs (basic backend only, as pdf_oxide
+    /// only opens files with an empty password)
+    pub pdf_password: Option<String>,
+    /// Keep a leading UTF-8 byte order mark instead of stripping it
+    pub keep_bom: bool,
+    /// OCR a PDF with no embedded text, such as a scan (macOS only,
+    /// requires the `catboard-ocr` helper). Off by default, as with the
+    /// CLI's `--ocr-fallback`.
+    pub ocr_fallback: bool,
+    /// Leading bytes searched for a null byte to detect binary files; `0`
+    /// searches the whole file
+    pub binary_check_bytes: usize,
+    /// Read every input as text: skip PDF and image routing and binary
+    /// detection, and replace invalid UTF-8 with U+FFFD instead of failing
+    pub force_text: bool,
+    /// Checked between PDF pages; once cancelled, extraction stops with
+    /// `Cancelled`
+    pub cancel: Option<CancellationToken>,
+}
+
+impl Default for ReadOptions {
+    fn default() -> Self {
+        Self {
+            page_separator: DEFAULT_PAGE_SEPARATOR.to_string(),
+            strict_pages: false,
+            pdf_backend: PdfBackend::default(),
+            pdf_layout: false,
+            pdf_password: None,
+            ocr_fallback: false,
+            keep_bom: false,
+            binary_check_bytes: BINARY_CHECK_SIZE,
+            force_text: false,
+            cancel: None,
+        }
+    }
+}
+
+/// Kind of content a file holds, used to pick an extractor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ContentKind {
+    Pdf,
+    Image,
+    Text,
+}
+
+/// Path that stands for stdin, following the usual CLI convention
+const STDIN_PATH: &str = "-";
+
+/// Check if a path is the `-` stdin indicator
+pub fn is_stdin_path(path: &Path) -> bool {
+    path == Path::new(STDIN_PATH)
+}
 
 /// Check if extension matches PDF (case-insensitive)
 fn is_pdf_extension(ext: Option<&str>) -> bool {
     ext.map(|e| e.eq_ignore_ascii_case("pdf")).unwrap_or(false)
 }
 
+/// Classify content by its leading bytes (magic number)
+fn content_kind_from_header(header: &[u8]) -> ContentKind {
+    if header.starts_with(PDF_SIGNATURE) {
+        ContentKind::Pdf
+    } else if ocr::is_image_header(header) {
+        ContentKind::Image
+    } else {
+        ContentKind::Text
+    }
+}
+
+/// Open a file, mapping I/O errors to catboard errors
+pub(crate) fn open_file(path: &Path) -> Result<fs::File> {
+    fs::File::open(path).with_path(path)
+}
+
+/// Where routing and text reads get file bytes, so dispatch can be tested
+/// without touching disk
+pub(crate) trait FileSource {
+    /// Up to `len` leading bytes of the file
+    fn read_prefix(&self, path: &Path, len: usize) -> Result<Vec<u8>>;
+
+    /// The whole file
+    fn read_all(&self, path: &Path) -> Result<Vec<u8>>;
+}
+
+/// [`FileSource`] backed by the real file system
+pub(crate) struct DiskSource;
+
+impl FileSource for DiskSource {
+    fn read_prefix(&self, path: &Path, len: usize) -> Result<Vec<u8>> {
+        let file = open_file(path)?;
+        let mut prefix = Vec::with_capacity(len);
+        file.take(len as u64)
+            .read_to_end(&mut prefix)
+            .with_path(path)?;
+        Ok(prefix)
+    }
+
+    fn read_all(&self, path: &Path) -> Result<Vec<u8>> {
+        fs::read(path).with_path(path)
+    }
+}
+
+/// The extractor a `.pdf` or image extension picks, if any
+fn route_by_extension(path: &Path) -> Option<ContentKind> {
+    let extension = path.extension().and_then(OsStr::to_str);
+
+    if is_pdf_extension(extension) {
+        Some(ContentKind::Pdf)
+    } else if ocr::is_image_file(path) {
+        Some(ContentKind::Image)
+    } else {
+        None
+    }
+}
+
+/// Pick an extractor for `path`.
+///
+/// The extension is a fast-path hint; otherwise the magic number is sniffed.
+fn route(path: &Path, source: &dyn FileSource) -> Result<ContentKind> {
+    match route_by_extension(path) {
+        Some(kind) => Ok(kind),
+        None => {
+            let header = source.read_prefix(path, SNIFF_SIZE)?;
+            Ok(content_kind_from_header(&header))
+        }
+    }
+}
+
 /// Reads the contents of a file as a UTF-8 string.
 ///
 /// Supports multiple file types:
 /// - **Text files**: Read directly with binary detection
-/// - **PDF files**: Extract embedded text, with OCR fallback for scanned pages
+/// - **PDF files**: Extract embedded text; scanned pages fail unless
+///   [`ReadOptions::ocr_fallback`] is set (see [`read_file_contents_with`])
 /// - **Image files**: OCR using macOS Vision framework (macOS only)
 ///
+/// A `.pdf` or known image extension routes the file directly; otherwise the
+/// leading bytes are sniffed so mislabeled or extensionless PDFs and images
+/// still reach the right extractor.
+///
+/// A path of `-` reads from stdin instead, matching the CLI convention.
+///
 /// # Errors
 /// - `FileNotFound` if the file doesn't exist
 /// - `PermissionDenied` if the file can't be accessed
@@ -28,136 +197,360 @@ fn is_pdf_extension(ext: Option<&str>) -> bool {
 /// - `ExtractionError` if text extraction or OCR fails
 /// - `IoError` for other I/O failures
 pub fn read_file_contents<P: AsRef<Path>>(path: P) -> Result<String> {
+    read_file_contents_with(path, &ReadOptions::default())
+}
+
+/// Like [`read_file_contents`], with explicit [`ReadOptions`]
+pub fn read_file_contents_with<P: AsRef<Path>>(path: P, options: &ReadOptions) -> Result<String> {
+    extract_content_with(path, options).map(|content| content.text)
+}
+
+/// Extracts a file's text along with how it was obtained.
+///
+/// Routing is the same as [`read_file_contents`]; the result also reports
+/// the [`SourceKind`], page count for PDFs, and encoding for text sources.
+///
+/// # Errors
+/// Same as [`read_file_contents`].
+pub fn extract_content<P: AsRef<Path>>(path: P) -> Result<ExtractedContent> {
+    extract_content_with(path, &ReadOptions::default())
+}
+
+/// Like [`extract_content`], with explicit [`ReadOptions`]
+pub fn extract_content_with<P: AsRef<Path>>(
+    path: P,
+    options: &ReadOptions,
+) -> Result<ExtractedContent> {
     let path = path.as_ref();
 
+    if is_stdin_path(path) {
+        if options.force_text {
+            let bytes = read_stdin_bytes()?;
+            return Ok(ExtractedContent::plain_text(
+                String::from_utf8_lossy(&bytes).into_owned(),
+            ));
+        }
+        return read_stdin().map(ExtractedContent::plain_text);
+    }
+
     // Check if file exists and is accessible
     if !path.exists() {
         return Err(CatboardError::FileNotFound(path.to_path_buf()));
     }
 
-    // Check file extension for special handling
-    let extension = path.extension().and_then(OsStr::to_str);
+    if options.force_text {
+        return read_text_file(path, &DiskSource, options)
+            .map(|(text, encoding)| ExtractedContent::decoded_text(text, encoding));
+    }
 
-    if is_pdf_extension(extension) {
-        extract_pdf_text(path)
-    } else if ocr::is_image_file(path) {
-        ocr::extract_text_from_image(path)
-    } else {
-        read_text_file(path)
+    let metadata = fs::metadata(path).ok();
+
+    // Pipes and devices can only be read once, so skip sniffing and re-reading
+    if metadata.as_ref().is_some_and(|m| is_stream(&m.file_type())) {
+        return read_stream(path, options.binary_check_bytes).map(ExtractedContent::plain_text);
     }
-}
 
-/// Extract text from a PDF file.
-///
-/// First attempts to extract embedded text. If the PDF appears to be scanned
-/// (no text but has images), falls back to OCR on macOS.
-fn extract_pdf_text(path: &Path) -> Result<String> {
-    let mut doc = PdfDocument::open(path).map_err(|e| CatboardError::ExtractionError {
-        path: path.to_path_buf(),
-        message: e.to_string(),
-    })?;
-
-    let page_count = doc
-        .page_count()
-        .map_err(|e| CatboardError::ExtractionError {
-            path: path.to_path_buf(),
-            message: e.to_string(),
-        })?;
-    let mut all_text = String::new();
-
-    for page_num in 0..page_count {
-        match doc.extract_text(page_num) {
-            Ok(text) => {
-                if !all_text.is_empty() {
-                    all_text.push('\n');
-                }
-                all_text.push_str(&text);
-            }
-            Err(e) => {
-                return Err(CatboardError::ExtractionError {
-                    path: path.to_path_buf(),
-                    message: format!("Failed to extract page {}: {}", page_num + 1, e),
-                });
-            }
-        }
+    if metadata.is_some_and(|m| m.len() <= SMALL_FILE_SIZE) && route_by_extension(path).is_none() {
+        return read_small_file(path, &DiskSource, options);
     }
 
-    // If we got text, return it
-    if !all_text.trim().is_empty() {
-        return Ok(all_text);
+    match route(path, &DiskSource)? {
+        ContentKind::Pdf => extract_pdf_text(path, options, None),
+        ContentKind::Image => extract_image_text(path),
+        ContentKind::Text => read_text_file(path, &DiskSource, options)
+            .map(|(text, encoding)| ExtractedContent::decoded_text(text, encoding)),
     }
+}
 
-    // No text found - try OCR if available (scanned PDF)
-    if ocr::is_ocr_available() {
-        return extract_pdf_with_ocr(&mut doc, path, page_count);
+/// Extracts the text content of a file without touching the clipboard.
+///
+/// This is the library entry point for catboard's extraction pipeline:
+/// plain text is read with binary detection, PDFs have their embedded text
+/// extracted (with OCR fallback, unlike [`read_file_contents`]), and images
+/// are OCR'd on macOS. Routing is the same as [`read_file_contents`],
+/// including `-` for stdin; what to do with the text is left entirely to
+/// the caller.
+///
+/// # Errors
+/// Same as [`read_file_contents`].
+pub fn extract_text<P: AsRef<Path>>(path: P) -> Result<String> {
+    let options = ReadOptions {
+        ocr_fallback: true,
+        ..ReadOptions::default()
+    };
+    extract_content_with(path, &options).map(|content| content.text)
+}
+
+/// Extract text from a PDF, reporting progress as pages are processed.
+///
+/// `progress` is called after each page with `(current, total)`, where
+/// `current` is the 1-based number of the page just processed. Pages that
+/// fail to extract still count towards progress.
+///
+/// # Errors
+/// Same as [`extract_content_with`] for a PDF file.
+pub fn extract_pdf_text_with_progress<P: AsRef<Path>>(
+    path: P,
+    options: &ReadOptions,
+    progress: Option<&mut dyn FnMut(usize, usize)>,
+) -> Result<ExtractedContent> {
+    let path = path.as_ref();
+    if !path.exists() {
+        return Err(CatboardError::FileNotFound(path.to_path_buf()));
     }
+    extract_pdf_text(path, options, progress)
+}
 
-    Err(CatboardError::ExtractionError {
+/// Stub for builds without the `pdf` feature
+#[cfg(not(feature = "pdf"))]
+fn extract_pdf_text(
+    path: &Path,
+    _options: &ReadOptions,
+    _progress: Option<&mut dyn FnMut(usize, usize)>,
+) -> Result<ExtractedContent> {
+    Err(CatboardError::FeatureDisabled {
         path: path.to_path_buf(),
-        message: "PDF contains no extractable text".to_string(),
+        feature: "pdf",
     })
 }
 
-/// Extract text from a scanned PDF using OCR.
+/// Read the values filled in to a PDF form, rather than its text.
+///
+/// Fields are listed in document order under their fully qualified names,
+/// with parent field names joined by `.` (e.g. `address.city`). A path of
+/// `-` reads the PDF from stdin.
+///
+/// # Errors
+/// - `FileNotFound` if the file doesn't exist
+/// - `NoFormFields` if the PDF has no form
+/// - `PasswordRequired` if the PDF needs a password
+/// - `ExtractionError` if the file isn't a readable PDF
+/// - `FeatureDisabled` in builds without the `pdf` feature
+pub fn extract_pdf_fields<P: AsRef<Path>>(path: P) -> Result<Vec<FormField>> {
+    extract_pdf_fields_with(path, &ReadOptions::default())
+}
+
+/// Like [`extract_pdf_fields`], unlocking the PDF with
+/// [`ReadOptions::pdf_password`]
 ///
-/// Uses macOS Vision framework via catboard-ocr helper.
-/// The OCR helper uses PDFKit to iterate through all pages, render each
-/// at 150 DPI, and run Vision OCR on each page sequentially.
-#[cfg(target_os = "macos")]
-fn extract_pdf_with_ocr(_doc: &mut PdfDocument, path: &Path, _page_count: usize) -> Result<String> {
-    // catboard-ocr uses PDFKit for multi-page PDF support.
-    // Each page is rendered and OCR'd sequentially, with page separators
-    // added between pages (e.g., "--- Page 2 ---").
-    let text = ocr::extract_text_from_image(path)?;
-
-    if text.trim().is_empty() {
-        return Err(CatboardError::ExtractionError {
-            path: path.to_path_buf(),
-            message: "PDF contains no recognizable text (OCR found nothing)".to_string(),
+/// # Errors
+/// Same as [`extract_pdf_fields`], plus `WrongPassword` if the password
+/// doesn't unlock the PDF.
+pub fn extract_pdf_fields_with<P: AsRef<Path>>(
+    path: P,
+    options: &ReadOptions,
+) -> Result<Vec<FormField>> {
+    let path = path.as_ref();
+    let password = options.pdf_password.as_deref();
+    if is_stdin_path(path) {
+        return with_staged_bytes(&read_stdin_bytes()?, "pdf", |staged| {
+            extract_form_fields(staged, password)
         });
     }
-
-    Ok(text)
+    if !path.exists() {
+        return Err(CatboardError::FileNotFound(path.to_path_buf()));
+    }
+    extract_form_fields(path, password)
 }
 
-/// Stub for non-macOS platforms - OCR not available
-#[cfg(not(target_os = "macos"))]
-fn extract_pdf_with_ocr(_doc: &mut PdfDocument, path: &Path, _page_count: usize) -> Result<String> {
-    Err(CatboardError::ExtractionError {
+/// Stub for builds without the `pdf` feature
+#[cfg(not(feature = "pdf"))]
+fn extract_form_fields(path: &Path, _password: Option<&str>) -> Result<Vec<FormField>> {
+    Err(CatboardError::FeatureDisabled {
         path: path.to_path_buf(),
-        message: "PDF contains no extractable text (OCR only available on macOS)".to_string(),
+        feature: "pdf",
     })
 }
 
-/// Read a plain text file with binary detection
-fn read_text_file(path: &Path) -> Result<String> {
-    // Try to open the file
-    let mut file = fs::File::open(path).map_err(|e| match e.kind() {
-        io::ErrorKind::PermissionDenied => CatboardError::PermissionDenied(path.to_path_buf()),
-        io::ErrorKind::NotFound => CatboardError::FileNotFound(path.to_path_buf()),
-        _ => CatboardError::IoError {
-            path: path.to_path_buf(),
-            source: e,
-        },
-    })?;
+/// OCR an image file
+#[cfg(feature = "ocr")]
+fn extract_image_text(path: &Path) -> Result<ExtractedContent> {
+    ocr::extract_text_from_image(path)
+        .map(|text| ExtractedContent::extracted(text, SourceKind::ImageOcr, None))
+}
 
-    // Check for binary content by reading first chunk
-    let mut buffer = vec![0u8; BINARY_CHECK_SIZE];
-    let bytes_read = file.read(&mut buffer).map_err(|e| CatboardError::IoError {
+/// Stub for builds without the `ocr` feature
+#[cfg(not(feature = "ocr"))]
+fn extract_image_text(path: &Path) -> Result<ExtractedContent> {
+    Err(CatboardError::FeatureDisabled {
         path: path.to_path_buf(),
-        source: e,
-    })?;
+        feature: "ocr",
+    })
+}
+
+/// Check if a file type is a FIFO or character device rather than a seekable file
+#[cfg(unix)]
+fn is_stream(file_type: &fs::FileType) -> bool {
+    use std::os::unix::fs::FileTypeExt;
+    file_type.is_fifo() || file_type.is_char_device()
+}
+
+/// Non-Unix platforms have no FIFOs reachable by path
+#[cfg(not(unix))]
+fn is_stream(_file_type: &fs::FileType) -> bool {
+    false
+}
 
-    // Check for null bytes which indicate binary content
-    if buffer[..bytes_read].contains(&0) {
+/// Read a FIFO or character device to EOF in a single pass with binary
+/// detection over its first `window` bytes, or all of it for `0`
+fn read_stream(path: &Path, window: usize) -> Result<String> {
+    let mut file = open_file(path)?;
+    let io_err = |e| CatboardError::io(path, e);
+
+    // Check the first chunk before draining the rest, so endless binary
+    // devices like /dev/zero are rejected instead of read forever
+    let mut bytes = Vec::new();
+    if window > 0 {
+        (&mut file)
+            .take(window as u64)
+            .read_to_end(&mut bytes)
+            .map_err(io_err)?;
+        if bytes.contains(&0) {
+            return Err(CatboardError::BinaryFile(path.to_path_buf()));
+        }
+    }
+
+    file.read_to_end(&mut bytes).map_err(io_err)?;
+    if window == 0 && bytes.contains(&0) {
         return Err(CatboardError::BinaryFile(path.to_path_buf()));
     }
 
-    // Re-read the entire file as a string
-    fs::read_to_string(path).map_err(|e| CatboardError::IoError {
+    String::from_utf8(bytes).map_err(|e| encoding_error(path, e.as_bytes(), e.utf8_error()))
+}
+
+/// `EncodingError` locating the first invalid byte of `bytes`
+fn encoding_error(path: &Path, bytes: &[u8], error: std::str::Utf8Error) -> CatboardError {
+    let offset = error.valid_up_to();
+    CatboardError::EncodingError {
         path: path.to_path_buf(),
-        source: e,
-    })
+        offset,
+        byte: bytes[offset],
+    }
+}
+
+/// Read a file no bigger than [`SMALL_FILE_SIZE`] with a single read,
+/// sniffing its kind from the bytes already in memory
+fn read_small_file(
+    path: &Path,
+    source: &dyn FileSource,
+    options: &ReadOptions,
+) -> Result<ExtractedContent> {
+    let bytes = source.read_all(path)?;
+    match content_kind_from_header(&bytes) {
+        ContentKind::Pdf => extract_pdf_text(path, options, None),
+        ContentKind::Image => extract_image_text(path),
+        ContentKind::Text => decode_text(path, bytes, options)
+            .map(|(text, encoding)| ExtractedContent::decoded_text(text, encoding)),
+    }
+}
+
+/// Whether `bytes` start with a UTF-16 byte order mark
+pub(crate) fn is_utf16(bytes: &[u8]) -> bool {
+    bytes.starts_with(UTF16LE_BOM) || bytes.starts_with(UTF16BE_BOM)
+}
+
+/// Whether `sample` looks like binary data rather than text
+///
+/// This is the check catboard runs before decoding a text file: a null
+/// byte within the first [`BINARY_CHECK_SIZE`] bytes means binary, unless
+/// the sample starts with a UTF-16 byte order mark. Bytes past that window
+/// are never inspected, so passing the start of a file is enough, and a
+/// null byte later on does not make it binary. An empty sample is text.
+pub fn is_binary(sample: &[u8]) -> bool {
+    is_binary_within(sample, BINARY_CHECK_SIZE)
+}
+
+/// Like [`is_binary`], searching the first `window` bytes of `sample` for
+/// a null byte, or all of it when `window` is `0`
+pub fn is_binary_within(sample: &[u8], window: usize) -> bool {
+    let checked = match window {
+        0 => sample,
+        _ => &sample[..sample.len().min(window)],
+    };
+    !is_utf16(sample) && checked.contains(&0)
+}
+
+/// Read a plain text file with binary detection and BOM handling
+///
+/// Returns the text along with the encoding it was decoded from
+fn read_text_file(
+    path: &Path,
+    source: &dyn FileSource,
+    options: &ReadOptions,
+) -> Result<(String, &'static str)> {
+    let window = options.binary_check_bytes;
+    if window == 0 || options.force_text {
+        return decode_text(path, source.read_all(path)?, options);
+    }
+    let head = source.read_prefix(path, window)?;
+
+    // A short prefix is the whole file, so there is nothing left to read
+    if head.len() < window {
+        return decode_text(path, head, options);
+    }
+
+    // Reject binary files before reading all of a possibly huge file
+    if is_binary_within(&head, window) {
+        return Err(CatboardError::BinaryFile(path.to_path_buf()));
+    }
+    decode_text(path, source.read_all(path)?, options)
+}
+
+/// Decode a whole file's bytes as UTF-8, or UTF-16 when it has a BOM
+///
+/// Returns the text along with the encoding it was decoded from
+fn decode_text(
+    path: &Path,
+    bytes: Vec<u8>,
+    options: &ReadOptions,
+) -> Result<(String, &'static str)> {
+    let invalid =
+        |e: String| CatboardError::io(path, io::Error::new(io::ErrorKind::InvalidData, e));
+
+    // UTF-16 is full of null bytes, so transcode it before the binary check
+    if is_utf16(&bytes) {
+        let big_endian = bytes.starts_with(UTF16BE_BOM);
+        let text = decode_utf16(&bytes, big_endian).map_err(invalid)?;
+        let text = strip_bom(text, options.keep_bom);
+        return Ok((text, if big_endian { "utf-16be" } else { "utf-16le" }));
+    }
+
+    if !options.force_text && is_binary_within(&bytes, options.binary_check_bytes) {
+        return Err(CatboardError::BinaryFile(path.to_path_buf()));
+    }
+
+    let text = match String::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(e) if options.force_text => String::from_utf8_lossy(e.as_bytes()).into_owned(),
+        Err(e) => return Err(encoding_error(path, e.as_bytes(), e.utf8_error())),
+    };
+    Ok((strip_bom(text, options.keep_bom), "utf-8"))
+}
+
+/// Decode UTF-16 bytes (including any BOM) to a string
+fn decode_utf16(bytes: &[u8], big_endian: bool) -> std::result::Result<String, String> {
+    if bytes.len() % 2 != 0 {
+        return Err("UTF-16 text has an odd number of bytes".to_string());
+    }
+    let units = bytes.chunks_exact(2).map(|pair| {
+        let pair = [pair[0], pair[1]];
+        if big_endian {
+            u16::from_be_bytes(pair)
+        } else {
+            u16::from_le_bytes(pair)
+        }
+    });
+    char::decode_utf16(units)
+        .collect::<std::result::Result<String, _>>()
+        .map_err(|e| e.to_string())
+}
+
+/// Remove a leading U+FEFF unless asked to keep it
+fn strip_bom(mut text: String, keep_bom: bool) -> String {
+    if !keep_bom && text.starts_with('\u{FEFF}') {
+        text.drain(..'\u{FEFF}'.len_utf8());
+    }
+    text
 }
 
 /// Reads content from stdin
@@ -165,18 +558,278 @@ pub fn read_stdin() -> Result<String> {
     let mut buffer = String::new();
     io::stdin()
         .read_to_string(&mut buffer)
-        .map_err(|e| CatboardError::IoError {
-            path: "-".into(),
-            source: e,
-        })?;
+        .with_path(STDIN_PATH)?;
+    Ok(buffer)
+}
+
+/// Waits until stdin has data or reaches EOF.
+///
+/// Whatever arrives stays buffered for the next stdin read.
+///
+/// # Errors
+/// - `StdinTimeout` if nothing arrives within `timeout`
+pub fn wait_for_stdin(timeout: Duration) -> Result<()> {
+    let (tx, rx) = mpsc::channel();
+
+    // The reader thread is left blocked on timeout; the caller is expected
+    // to give up on stdin entirely at that point
+    thread::spawn(move || {
+        let result = io::stdin().lock().fill_buf().map(|_| ());
+        let _ = tx.send(result);
+    });
+
+    match rx.recv_timeout(timeout) {
+        Ok(result) => result.map_err(|e| CatboardError::io(STDIN_PATH, e)),
+        Err(_) => Err(CatboardError::StdinTimeout(timeout)),
+    }
+}
+
+/// Reads raw bytes from stdin without UTF-8 decoding
+pub fn read_stdin_bytes() -> Result<Vec<u8>> {
+    read_bytes_from(io::stdin().lock())
+}
+
+/// Reads the raw bytes of a file, or of stdin for `-`, without binary
+/// detection or text extraction
+pub fn read_file_bytes<P: AsRef<Path>>(path: P) -> Result<Vec<u8>> {
+    let path = path.as_ref();
+
+    if is_stdin_path(path) {
+        return read_stdin_bytes();
+    }
+
+    let mut file = open_file(path)?;
+    let mut buffer = Vec::new();
+    file.read_to_end(&mut buffer)
+        .map_err(|e| CatboardError::io(path, e))?;
+    Ok(buffer)
+}
+
+/// Reads a file, or stdin for `-`, as UTF-8 text exactly as stored.
+///
+/// Nothing is extracted, transcoded or stripped: PDFs and images are read
+/// as text, a byte order mark is kept and UTF-16 is not converted, so the
+/// returned text has the input's bytes.
+///
+/// # Errors
+/// - `BinaryFile` if a null byte appears in the first `binary_check_bytes`
+///   bytes (`0` checks them all)
+/// - `EncodingError` if the input isn't valid UTF-8
+pub fn read_verbatim<P: AsRef<Path>>(path: P, binary_check_bytes: usize) -> Result<String> {
+    let path = path.as_ref();
+    let bytes = read_file_bytes(path)?;
+    let checked = match binary_check_bytes {
+        0 => bytes.len(),
+        window => window.min(bytes.len()),
+    };
+    if bytes[..checked].contains(&0) {
+        return Err(CatboardError::BinaryFile(path.to_path_buf()));
+    }
+    String::from_utf8(bytes).map_err(|e| encoding_error(path, e.as_bytes(), e.utf8_error()))
+}
+
+/// Append `text` to `path`, creating it if needed, followed by a newline
+/// unless `text` is empty or already ends with one.
+///
+/// Existing content is never rewritten: the file is opened for appending
+/// and each entry goes out in a single write, so entries appended at the
+/// same time by other processes don't interleave.
+///
+/// # Errors
+/// - `IoError` if the file cannot be opened or written
+pub fn append_text(path: &Path, text: &str) -> Result<()> {
+    let io_err = |source| CatboardError::io(path, source);
+
+    let mut entry = String::with_capacity(text.len() + 1);
+    entry.push_str(text);
+    if !text.is_empty() && !text.ends_with('\n') {
+        entry.push('\n');
+    }
+
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(io_err)?;
+    file.write_all(entry.as_bytes()).map_err(io_err)
+}
+
+/// Write `bytes` to `path` through a temporary file in the same directory
+/// that is then renamed over it, so an existing `path` is either fully
+/// replaced or left untouched.
+///
+/// A replaced file keeps its permissions; a new one gets `0o644` on Unix.
+/// If `path` is a symlink, the file it points to is replaced and the link
+/// is kept.
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
+    write_atomic_with(path, |file| file.write_all(bytes))
+}
+
+/// Like [`write_atomic`], with the content produced by `write`.
+///
+/// If `write` fails, the temporary file is removed and `path` is untouched.
+pub fn write_atomic_with<F>(path: &Path, write: F) -> Result<()>
+where
+    F: FnOnce(&mut fs::File) -> io::Result<()>,
+{
+    let io_err = |source| CatboardError::io(path, source);
+    let is_symlink = fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_symlink());
+    let resolved;
+    let target = if is_symlink {
+        resolved = fs::canonicalize(path).map_err(io_err)?;
+        resolved.as_path()
+    } else {
+        path
+    };
+    let dir = match target.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    let mut temp = tempfile::Builder::new()
+        .prefix(".catboard-")
+        .suffix(".tmp")
+        .tempfile_in(dir)
+        .map_err(io_err)?;
+    write(temp.as_file_mut())
+        .and_then(|_| temp.as_file().sync_all())
+        .map_err(io_err)?;
+    let permissions = match fs::metadata(target) {
+        Ok(metadata) => Some(metadata.permissions()),
+        Err(_) => default_permissions(),
+    };
+    if let Some(permissions) = permissions {
+        fs::set_permissions(temp.path(), permissions).map_err(io_err)?;
+    }
+
+    match temp.persist(target) {
+        Ok(_) => Ok(()),
+        // The temp file shares the target's directory, but bind mounts and
+        // some network filesystems can still refuse the rename. Copying is
+        // not atomic, but the content is complete before `target` is touched.
+        Err(e) if is_cross_device(&e.error) => {
+            copy_into_place(e.file.path(), target).map_err(io_err)
+        }
+        Err(e) => Err(io_err(e.error)),
+    }
+}
+
+/// Whether a rename failed because source and destination are on
+/// different filesystems
+fn is_cross_device(e: &io::Error) -> bool {
+    #[cfg(unix)]
+    const EXDEV: Option<i32> = Some(18);
+    // ERROR_NOT_SAME_DEVICE
+    #[cfg(windows)]
+    const EXDEV: Option<i32> = Some(17);
+    #[cfg(not(any(unix, windows)))]
+    const EXDEV: Option<i32> = None;
+
+    EXDEV.is_some() && e.raw_os_error() == EXDEV
+}
+
+/// Fallback for a rename that can't cross filesystems: copy `from` over
+/// `to` and flush it to disk
+fn copy_into_place(from: &Path, to: &Path) -> io::Result<()> {
+    fs::copy(from, to)?;
+    fs::OpenOptions::new().write(true).open(to)?.sync_all()
+}
+
+#[cfg(unix)]
+fn default_permissions() -> Option<fs::Permissions> {
+    use std::os::unix::fs::PermissionsExt;
+    Some(fs::Permissions::from_mode(0o644))
+}
+
+#[cfg(not(unix))]
+fn default_permissions() -> Option<fs::Permissions> {
+    None
+}
+
+/// Reads stdin as if it were a file with the given extension.
+///
+/// The bytes are staged in a temporary file so extractors that need a path
+/// (like the PDF one) can run; the file is removed even if extraction fails.
+/// Errors refer to stdin as `-` rather than to the temporary file.
+pub fn read_stdin_as(extension: &str, options: &ReadOptions) -> Result<String> {
+    read_bytes_as(&read_stdin_bytes()?, extension, options)
+}
+
+/// Route in-memory bytes through the extractor for `extension`
+fn read_bytes_as(bytes: &[u8], extension: &str, options: &ReadOptions) -> Result<String> {
+    with_staged_bytes(bytes, extension, |staged| {
+        read_file_contents_with(staged, options)
+    })
+}
+
+/// Run `read` on a temporary file holding stdin's `bytes`, named with
+/// `extension`, reporting its errors against `-`
+fn with_staged_bytes<T>(
+    bytes: &[u8],
+    extension: &str,
+    read: impl FnOnce(&Path) -> Result<T>,
+) -> Result<T> {
+    let stdin_path = Path::new(STDIN_PATH);
+    let suffix = format!(".{}", extension.trim_start_matches('.'));
+
+    let mut temp = tempfile::Builder::new()
+        .prefix("catboard-stdin-")
+        .suffix(&suffix)
+        .tempfile()
+        .with_path(stdin_path)?;
+    temp.write_all(bytes)
+        .and_then(|_| temp.flush())
+        .with_path(stdin_path)?;
+
+    read(temp.path()).map_err(|e| relabel_error(e, stdin_path))
+}
+
+/// Point an error about a staged temp file back at the original input
+fn relabel_error(err: CatboardError, path: &Path) -> CatboardError {
+    let path = path.to_path_buf();
+    match err {
+        CatboardError::FileNotFound(_) => CatboardError::FileNotFound(path),
+        CatboardError::PermissionDenied(_) => CatboardError::PermissionDenied(path),
+        CatboardError::BinaryFile(_) => CatboardError::BinaryFile(path),
+        CatboardError::NoFormFields(_) => CatboardError::NoFormFields(path),
+        CatboardError::PasswordRequired(_) => CatboardError::PasswordRequired(path),
+        CatboardError::WrongPassword(_) => CatboardError::WrongPassword(path),
+        CatboardError::ExtractionError {
+            message, backtrace, ..
+        } => CatboardError::ExtractionError {
+            path,
+            message,
+            backtrace,
+        },
+        CatboardError::IoError {
+            source, backtrace, ..
+        } => CatboardError::IoError {
+            path,
+            source,
+            backtrace,
+        },
+        other => other,
+    }
+}
+
+/// Drain a stdin-like reader into a byte buffer
+fn read_bytes_from<R: Read>(mut reader: R) -> Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    reader
+        .read_to_end(&mut buffer)
+        .map_err(|e| CatboardError::io(STDIN_PATH, e))?;
     Ok(buffer)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::content::SourceKind;
+    use std::cell::Cell;
+    use std::collections::HashMap;
     use std::fs::File;
     use std::io::Write;
+    use std::path::PathBuf;
     use tempfile::TempDir;
 
     #[test]
@@ -193,6 +846,77 @@ mod tests {
         assert_eq!(result.unwrap(), content);
     }
 
+    #[test]
+    fn test_is_stdin_path() {
+        assert!(is_stdin_path(Path::new("-")));
+        assert!(!is_stdin_path(Path::new("./-")));
+        assert!(!is_stdin_path(Path::new("--")));
+        assert!(!is_stdin_path(Path::new("file.txt")));
+    }
+
+    #[test]
+    fn test_extract_text_plain_file() {
+        let dir = TempDir::new().unwrap();
+        let file_path = dir.path().join("notes.md");
+        fs::write(&file_path, "# Notes").unwrap();
+
+        assert_eq!(extract_text(&file_path).unwrap(), "# Notes");
+    }
+
+    #[test]
+    #[cfg(feature = "ocr")]
+    fn test_extract_text_routes_images_to_ocr() {
+        let dir = TempDir::new().unwrap();
+        let file_path = dir.path().join("scan.png");
+        fs::write(&file_path, b"fake png").unwrap();
+
+        // Routed to OCR, which fails on fake data, rather than text or binary
+        let result = extract_text(&file_path);
+        assert!(matches!(result, Err(CatboardError::ExtractionError { .. })));
+    }
+
+    #[test]
+    #[cfg(feature = "pdf")]
+    fn test_extract_text_ocrs_scanned_pdfs() {
+        // Tries OCR, which only succeeds on macOS with the helper installed
+        if let Err(err) = extract_text("tests/blank-page.pdf") {
+            assert!(
+                !err.to_string().contains("OCR fallback disabled"),
+                "{}",
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn test_extract_content_plain_text_metadata() {
+        let dir = TempDir::new().unwrap();
+        let file_path = dir.path().join("notes.txt");
+        fs::write(&file_path, "hello").unwrap();
+
+        let content = extract_content(&file_path).unwrap();
+        assert_eq!(content.text, "hello");
+        assert_eq!(content.source, SourceKind::PlainText);
+        assert_eq!(content.pages, None);
+        assert_eq!(content.detected_encoding.as_deref(), Some("utf-8"));
+    }
+
+    #[test]
+    #[cfg(feature = "pdf")]
+    fn test_extract_content_pdf_metadata() {
+        let pdf_path = Path::new("tests/2025-12-12_12-11-14.pdf");
+        // Scanned PDF: only succeeds where OCR is available
+        let options = ReadOptions {
+            ocr_fallback: true,
+            ..ReadOptions::default()
+        };
+        if let Ok(content) = extract_content_with(pdf_path, &options) {
+            assert_eq!(content.source, SourceKind::PdfOcr);
+            assert!(content.pages.is_some());
+            assert_eq!(content.detected_encoding, None);
+        }
+    }
+
     #[test]
     fn test_read_empty_file() {
         let dir = TempDir::new().unwrap();
@@ -239,17 +963,52 @@ mod tests {
     }
 
     #[test]
-    fn test_read_large_text_file() {
-        let dir = TempDir::new().unwrap();
-        let file_path = dir.path().join("large.txt");
+    fn test_is_binary_null_bytes() {
+        assert!(is_binary(&[0x48, 0x65, 0x6c, 0x00, 0x6f]));
+        assert!(is_binary(&[0x00]));
 
-        // Create a file larger than BINARY_CHECK_SIZE
-        let content = "A".repeat(10000);
-        let mut file = File::create(&file_path).unwrap();
-        file.write_all(content.as_bytes()).unwrap();
+        let mut late = vec![b'A'; 5000];
+        late[4000] = 0;
+        assert!(is_binary(&late));
+    }
 
-        let result = read_file_contents(&file_path);
-        assert!(result.is_ok());
+    #[test]
+    fn test_is_binary_text() {
+        assert!(!is_binary(b""));
+        assert!(!is_binary(b"plain text\n"));
+        assert!(!is_binary("caf\u{e9} \u{1F600}".as_bytes()));
+        assert!(!is_binary(b"\xEF\xBB\xBFwith bom"));
+    }
+
+    #[test]
+    fn test_is_binary_allows_utf16_with_bom() {
+        assert!(!is_binary(b"\xFF\xFEh\x00i\x00"));
+        assert!(!is_binary(b"\xFE\xFF\x00h\x00i"));
+        // Without a BOM, UTF-16 is indistinguishable from binary
+        assert!(is_binary(b"h\x00i\x00"));
+    }
+
+    #[test]
+    fn test_is_binary_only_samples_check_window() {
+        let mut bytes = vec![b'a'; BINARY_CHECK_SIZE];
+        bytes.push(0);
+        assert!(!is_binary(&bytes));
+        bytes[BINARY_CHECK_SIZE - 1] = 0;
+        assert!(is_binary(&bytes));
+    }
+
+    #[test]
+    fn test_read_large_text_file() {
+        let dir = TempDir::new().unwrap();
+        let file_path = dir.path().join("large.txt");
+
+        // Create a file larger than BINARY_CHECK_SIZE
+        let content = "A".repeat(10000);
+        let mut file = File::create(&file_path).unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+
+        let result = read_file_contents(&file_path);
+        assert!(result.is_ok());
         assert_eq!(result.unwrap().len(), 10000);
     }
 
@@ -270,6 +1029,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "pdf")]
     fn test_pdf_extension_detected() {
         let dir = TempDir::new().unwrap();
         let file_path = dir.path().join("test.pdf");
@@ -284,6 +1044,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "pdf")]
     fn test_rotated_pdf_detected_as_pdf() {
         // Test that a rotated PDF is properly recognized as a PDF
         // (not rejected as binary)
@@ -307,4 +1068,1015 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_content_kind_from_header() {
+        assert_eq!(content_kind_from_header(b"%PDF-1.7\n"), ContentKind::Pdf);
+        assert_eq!(
+            content_kind_from_header(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]),
+            ContentKind::Image
+        );
+        assert_eq!(content_kind_from_header(b"plain text"), ContentKind::Text);
+        assert_eq!(content_kind_from_header(b""), ContentKind::Text);
+    }
+
+    #[test]
+    #[cfg(feature = "pdf")]
+    fn test_extensionless_pdf_sniffed() {
+        let dir = TempDir::new().unwrap();
+        let file_path = dir.path().join("report");
+
+        fs::copy("tests/three-pages.pdf", &file_path).unwrap();
+
+        // Routed to the PDF extractor rather than rejected as binary
+        let content = extract_content(&file_path).unwrap();
+        assert_eq!(content.source, SourceKind::PdfText);
+        assert_eq!(content.pages, Some(3));
+    }
+
+    #[test]
+    #[cfg(feature = "pdf")]
+    fn test_mislabeled_txt_pdf_sniffed() {
+        let dir = TempDir::new().unwrap();
+        let file_path = dir.path().join("notes.txt");
+
+        // Looks like a PDF but isn't valid - must fail extraction, not be read as text
+        let mut file = File::create(&file_path).unwrap();
+        file.write_all(b"%PDF-1.4 truncated").unwrap();
+
+        let result = read_file_contents(&file_path);
+        assert!(matches!(result, Err(CatboardError::ExtractionError { .. })));
+    }
+
+    #[test]
+    #[cfg(feature = "ocr")]
+    fn test_extensionless_image_sniffed() {
+        let dir = TempDir::new().unwrap();
+        let file_path = dir.path().join("screenshot");
+
+        let mut file = File::create(&file_path).unwrap();
+        file.write_all(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0x00])
+            .unwrap();
+
+        let result = read_file_contents(&file_path);
+        // Routed to OCR rather than rejected as binary
+        assert!(matches!(result, Err(CatboardError::ExtractionError { .. })));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_read_named_pipe() {
+        let dir = TempDir::new().unwrap();
+        let fifo_path = dir.path().join("pipe");
+
+        let status = std::process::Command::new("mkfifo")
+            .arg(&fifo_path)
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let writer_path = fifo_path.clone();
+        let writer = std::thread::spawn(move || {
+            let mut fifo = fs::OpenOptions::new()
+                .write(true)
+                .open(writer_path)
+                .unwrap();
+            fifo.write_all(b"from a pipe").unwrap();
+        });
+
+        let result = read_file_contents(&fifo_path);
+        writer.join().unwrap();
+        assert_eq!(result.unwrap(), "from a pipe");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_read_character_device() {
+        let result = read_file_contents("/dev/null");
+        assert_eq!(result.unwrap(), "");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_endless_binary_device_rejected() {
+        let result = read_file_contents("/dev/zero");
+        assert!(matches!(result, Err(CatboardError::BinaryFile(_))));
+    }
+
+    #[test]
+    fn test_invalid_utf8_reports_offset_and_byte() {
+        let dir = TempDir::new().unwrap();
+        let file_path = dir.path().join("mixed.txt");
+        // "héllo " is 7 bytes; 0xC3 then starts a sequence that '(' breaks
+        fs::write(&file_path, b"h\xC3\xA9llo \xC3( world").unwrap();
+
+        match read_file_contents(&file_path) {
+            Err(CatboardError::EncodingError { path, offset, byte }) => {
+                assert_eq!(path, file_path);
+                assert_eq!(offset, 7);
+                assert_eq!(byte, 0xC3);
+            }
+            other => panic!("expected an encoding error, got {:?}", other),
+        }
+
+        // A sequence cut off at the end of the file
+        fs::write(&file_path, b"ok\xE2\x82").unwrap();
+        let err = read_file_contents(&file_path).unwrap_err();
+        assert!(
+            err.to_string().contains("at byte offset 2 (0xE2)"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_read_verbatim_changes_nothing() {
+        let dir = TempDir::new().unwrap();
+
+        let bom = dir.path().join("bom.txt");
+        fs::write(&bom, b"\xEF\xBB\xBFa\r\nb").unwrap();
+        assert_eq!(
+            read_verbatim(&bom, BINARY_CHECK_SIZE).unwrap(),
+            "\u{FEFF}a\r\nb"
+        );
+
+        // Read as text rather than routed to the PDF extractor
+        let pdf = dir.path().join("notes.pdf");
+        fs::write(&pdf, "not really a pdf").unwrap();
+        assert_eq!(
+            read_verbatim(&pdf, BINARY_CHECK_SIZE).unwrap(),
+            "not really a pdf"
+        );
+
+        // UTF-16 is not transcoded, so it isn't UTF-8
+        let utf16 = dir.path().join("utf16.txt");
+        fs::write(&utf16, b"\xFF\xFEh\x00i\x00").unwrap();
+        assert!(matches!(
+            read_verbatim(&utf16, BINARY_CHECK_SIZE),
+            Err(CatboardError::BinaryFile(_))
+        ));
+        assert!(matches!(
+            read_verbatim(&utf16, 1),
+            Err(CatboardError::EncodingError { offset: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_read_bytes_from_non_utf8() {
+        let input: &[u8] = &[0xFF, 0xFE, 0x00, 0x80, b'a'];
+        let result = read_bytes_from(input).unwrap();
+        assert_eq!(result, input);
+    }
+
+    #[test]
+    fn test_read_file_bytes_binary() {
+        let dir = TempDir::new().unwrap();
+        let file_path = dir.path().join("binary.bin");
+
+        let mut file = File::create(&file_path).unwrap();
+        file.write_all(&[0x00, 0xFF, 0x10]).unwrap();
+
+        // Raw byte reads skip binary detection
+        let result = read_file_bytes(&file_path).unwrap();
+        assert_eq!(result, vec![0x00, 0xFF, 0x10]);
+    }
+
+    #[test]
+    fn test_read_file_bytes_not_found() {
+        let result = read_file_bytes("/nonexistent/path/file.bin");
+        assert!(matches!(result, Err(CatboardError::FileNotFound(_))));
+    }
+
+    #[test]
+    fn test_read_bytes_as_text_extension() {
+        let result = read_bytes_as(b"# Heading\n", "md", &ReadOptions::default()).unwrap();
+        assert_eq!(result, "# Heading\n");
+    }
+
+    #[test]
+    #[cfg(feature = "pdf")]
+    fn test_read_bytes_as_pdf_routes_to_extractor() {
+        let result = read_bytes_as(b"definitely not a pdf", ".pdf", &ReadOptions::default());
+        match result {
+            Err(CatboardError::ExtractionError { path, .. }) => {
+                assert_eq!(path, Path::new("-"));
+            }
+            other => panic!("Expected ExtractionError for stdin, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_read_bytes_as_binary_reports_stdin() {
+        let result = read_bytes_as(&[0x00, 0x01], "txt", &ReadOptions::default());
+        assert!(matches!(result, Err(CatboardError::BinaryFile(p)) if p == Path::new("-")));
+    }
+
+    #[test]
+    #[cfg(feature = "pdf")]
+    fn test_multi_page_pdf_default_separator() {
+        let content = extract_content("tests/three-pages.pdf").unwrap();
+        assert_eq!(content.source, SourceKind::PdfText);
+        assert_eq!(content.pages, Some(3));
+        assert!(content.text.contains("First page text"));
+        assert!(content.text.contains("Third page text"));
+    }
+
+    #[test]
+    #[cfg(feature = "pdf")]
+    fn test_multi_page_pdf_custom_separators() {
+        let options = ReadOptions {
+            page_separator: "\x0c".to_string(),
+            ..ReadOptions::default()
+        };
+        let text = read_file_contents_with("tests/three-pages.pdf", &options).unwrap();
+        assert_eq!(text.matches('\x0c').count(), 2);
+
+        let options = ReadOptions {
+            page_separator: "\n--- page {page} ---\n".to_string(),
+            ..ReadOptions::default()
+        };
+        let text = read_file_contents_with("tests/three-pages.pdf", &options).unwrap();
+        assert_eq!(text.matches("--- page ").count(), 2);
+        let second = text.find("--- page 2 ---").unwrap();
+        let third = text.find("--- page 3 ---").unwrap();
+        assert!(second < text.find("Second page text").unwrap());
+        assert!(third > text.find("Second page text").unwrap());
+        assert!(!text.contains("--- page 1 ---"));
+    }
+
+    #[test]
+    #[cfg(feature = "pdf")]
+    fn test_pdf_bad_page_is_skipped() {
+        let content = extract_content("tests/bad-page.pdf").unwrap();
+        assert_eq!(content.source, SourceKind::PdfText);
+        assert_eq!(content.pages, Some(3));
+        assert!(content.text.contains("First page text"));
+        assert!(content.text.contains("Third page text"));
+        assert_eq!(content.failed_pages.len(), 1);
+        assert_eq!(content.failed_pages[0].page, 2);
+    }
+
+    #[test]
+    #[cfg(feature = "pdf")]
+    fn test_pdf_bad_page_strict_fails() {
+        let options = ReadOptions {
+            strict_pages: true,
+            ..ReadOptions::default()
+        };
+        let err = extract_content_with("tests/bad-page.pdf", &options).unwrap_err();
+        assert!(err.to_string().contains("Failed to extract page 2"));
+    }
+
+    #[test]
+    #[cfg(feature = "pdf")]
+    fn test_pdf_backend_reported() {
+        let content = extract_content("tests/three-pages.pdf").unwrap();
+        assert_eq!(content.pdf_backend, Some(PdfBackend::Oxide));
+
+        let options = ReadOptions {
+            pdf_backend: PdfBackend::Basic,
+            ..ReadOptions::default()
+        };
+        let content = extract_content_with("tests/three-pages.pdf", &options).unwrap();
+        assert_eq!(content.pdf_backend, Some(PdfBackend::Basic));
+        assert_eq!(content.pages, Some(3));
+        assert_eq!(
+            content.text,
+            "First page text\nSecond page text\nThird page text"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "pdf")]
+    fn test_pdf_falls_back_to_next_backend() {
+        // The trailer has no /Root, which pdf_oxide can't open without
+        let content = extract_content("tests/no-root.pdf").unwrap();
+        assert_eq!(content.pdf_backend, Some(PdfBackend::Basic));
+        assert_eq!(content.source, SourceKind::PdfText);
+        assert!(content.text.contains("Second page text"));
+    }
+
+    #[test]
+    #[cfg(feature = "pdf")]
+    fn test_pdf_reports_first_backend_error() {
+        // Both backends fail page 2; the error is the one tried first
+        let options = ReadOptions {
+            strict_pages: true,
+            pdf_backend: PdfBackend::Basic,
+            ..ReadOptions::default()
+        };
+        let err = extract_content_with("tests/bad-page.pdf", &options).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Failed to extract page 2: could not inflate"));
+    }
+
+    #[test]
+    #[cfg(feature = "pdf")]
+    fn test_pdf_layout_keeps_columns() {
+        // tests/table.pdf draws a two-column table a column at a time
+        let flow = extract_content("tests/table.pdf").unwrap();
+        assert!(!flow.layout);
+        assert!(flow.text.starts_with("Fruit\nApples\nBananas\nCherries"));
+
+        let options = ReadOptions {
+            pdf_layout: true,
+            ..ReadOptions::default()
+        };
+        let laid_out = extract_content_with("tests/table.pdf", &options).unwrap();
+        assert!(laid_out.layout);
+        let lines: Vec<&str> = laid_out.text.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("Fruit ") && lines[0].ends_with(" Qty"));
+        assert!(lines[1].starts_with("Apples ") && lines[1].ends_with(" 12"));
+        assert!(lines[3].starts_with("Cherries ") && lines[3].ends_with(" 30"));
+        // The quantities line up in one column
+        let column = lines[0].find("Qty").unwrap();
+        assert!(lines[1..]
+            .iter()
+            .all(|line| line.find(char::is_numeric) == Some(column)));
+
+        // The basic backend can't place text, so it falls back to flow
+        let options = ReadOptions {
+            pdf_layout: true,
+            pdf_backend: PdfBackend::Basic,
+            ..ReadOptions::default()
+        };
+        let fallback = extract_content_with("tests/table.pdf", &options).unwrap();
+        assert!(!fallback.layout);
+        assert_eq!(fallback.text.lines().count(), 8);
+    }
+
+    #[test]
+    #[cfg(feature = "pdf")]
+    fn test_extract_pdf_fields() {
+        use crate::content::FieldValue;
+
+        let fields = extract_pdf_fields("tests/form.pdf").unwrap();
+        let text = |s: &str| FieldValue::Text(s.to_string());
+        let pairs: Vec<(&str, &FieldValue)> =
+            fields.iter().map(|f| (f.name.as_str(), &f.value)).collect();
+        assert_eq!(
+            pairs,
+            [
+                ("name", &text("Ada Lovelace")),
+                ("subscribe", &FieldValue::Checked(true)),
+                ("colour", &text("Blue")),
+                ("address.city", &text("London")),
+                ("notes", &FieldValue::Empty),
+            ]
+        );
+
+        let err = extract_pdf_fields("tests/three-pages.pdf").unwrap_err();
+        assert!(matches!(err, CatboardError::NoFormFields(_)), "{}", err);
+        assert!(matches!(
+            extract_pdf_fields("tests/missing.pdf"),
+            Err(CatboardError::FileNotFound(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "pdf")]
+    fn test_extract_encrypted_pdf_fields() {
+        use crate::content::FieldValue;
+
+        let path = "tests/encrypted-form.pdf";
+        assert!(matches!(
+            extract_pdf_fields(path),
+            Err(CatboardError::PasswordRequired(_))
+        ));
+        let options = |password: &str| ReadOptions {
+            pdf_password: Some(password.to_string()),
+            ..ReadOptions::default()
+        };
+        assert!(matches!(
+            extract_pdf_fields_with(path, &options("x")),
+            Err(CatboardError::WrongPassword(_))
+        ));
+
+        let fields = extract_pdf_fields_with(path, &options("secret")).unwrap();
+        let pairs: Vec<(&str, &FieldValue)> =
+            fields.iter().map(|f| (f.name.as_str(), &f.value)).collect();
+        assert_eq!(
+            pairs,
+            [
+                ("account", &FieldValue::Text("12-3456".to_string())),
+                ("agree", &FieldValue::Checked(false)),
+                (
+                    "plans",
+                    &FieldValue::Choices(vec!["Gold".to_string(), "Silver".to_string()])
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "pdf")]
+    fn test_encrypted_pdf_password() {
+        let with_password = |password: Option<&str>| {
+            let options = ReadOptions {
+                pdf_password: password.map(str::to_string),
+                ..ReadOptions::default()
+            };
+            extract_content_with("tests/encrypted.pdf", &options)
+        };
+
+        let content = with_password(Some("secret")).unwrap();
+        assert_eq!(content.text, "First secret page\nSecond secret page");
+        assert_eq!(content.pdf_backend, Some(PdfBackend::Basic));
+
+        let err = with_password(Some("guess")).unwrap_err();
+        assert!(matches!(err, CatboardError::WrongPassword(_)), "{}", err);
+        let err = with_password(None).unwrap_err();
+        assert!(matches!(err, CatboardError::PasswordRequired(_)), "{}", err);
+
+        // A damaged file is still an extraction error, password or not
+        let options = ReadOptions {
+            pdf_password: Some("secret".to_string()),
+            ..ReadOptions::default()
+        };
+        let err = read_bytes_as(b"%PDF-1.4 truncated", ".pdf", &options).unwrap_err();
+        assert!(
+            matches!(err, CatboardError::ExtractionError { .. }),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "pdf")]
+    fn test_pdf_without_text_ocr_fallback_disabled() {
+        // Off unless asked for
+        assert!(!ReadOptions::default().ocr_fallback);
+        let err = extract_content("tests/blank-page.pdf").unwrap_err();
+        assert!(err.to_string().contains("OCR fallback disabled"));
+    }
+
+    #[test]
+    #[cfg(feature = "pdf")]
+    #[cfg(not(target_os = "macos"))]
+    fn test_pdf_without_text_no_ocr_helper() {
+        let options = ReadOptions {
+            ocr_fallback: true,
+            ..ReadOptions::default()
+        };
+        let err = extract_content_with("tests/blank-page.pdf", &options).unwrap_err();
+        assert!(err.to_string().contains("no extractable text"));
+        assert!(!err.to_string().contains("disabled"));
+    }
+
+    #[test]
+    #[cfg(feature = "pdf")]
+    fn test_pdf_progress_fires_per_page() {
+        let mut calls = Vec::new();
+        let mut record = |current, total| calls.push((current, total));
+        let content = extract_pdf_text_with_progress(
+            "tests/three-pages.pdf",
+            &ReadOptions::default(),
+            Some(&mut record),
+        )
+        .unwrap();
+        assert_eq!(content.pages, Some(3));
+        assert_eq!(calls, vec![(1, 3), (2, 3), (3, 3)]);
+    }
+
+    #[test]
+    #[cfg(feature = "pdf")]
+    fn test_pdf_cancelled_between_pages() {
+        let token = CancellationToken::new();
+        let options = ReadOptions {
+            cancel: Some(token.clone()),
+            ..ReadOptions::default()
+        };
+        let mut pages_done = 0;
+        let mut cancel_after_first = |current, _total| {
+            pages_done = current;
+            token.cancel();
+        };
+        let err = extract_pdf_text_with_progress(
+            "tests/three-pages.pdf",
+            &options,
+            Some(&mut cancel_after_first),
+        )
+        .unwrap_err();
+        assert_eq!(pages_done, 1);
+        assert!(matches!(
+            &err,
+            CatboardError::Cancelled(msg) if msg.contains("before page 2 of 3")
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "pdf")]
+    fn test_pdf_cancelled_before_start() {
+        let token = CancellationToken::new();
+        token.cancel();
+        let options = ReadOptions {
+            cancel: Some(token),
+            ..ReadOptions::default()
+        };
+        let result = read_file_contents_with("tests/three-pages.pdf", &options);
+        assert!(matches!(result, Err(CatboardError::Cancelled(_))));
+    }
+
+    #[test]
+    #[cfg(feature = "pdf")]
+    fn test_pdf_progress_counts_failed_pages() {
+        let mut count = 0;
+        let mut record = |_, _| count += 1;
+        extract_pdf_text_with_progress(
+            "tests/bad-page.pdf",
+            &ReadOptions::default(),
+            Some(&mut record),
+        )
+        .unwrap();
+        assert_eq!(count, 3);
+    }
+
+    #[test]
+    fn test_pdf_progress_missing_file() {
+        let err = extract_pdf_text_with_progress("/nonexistent.pdf", &ReadOptions::default(), None)
+            .unwrap_err();
+        assert!(matches!(err, CatboardError::FileNotFound(_)));
+    }
+
+    #[test]
+    #[cfg(not(feature = "pdf"))]
+    fn test_pdf_without_feature_reports_disabled() {
+        let result = read_file_contents("tests/three-pages.pdf");
+        assert!(matches!(
+            result,
+            Err(CatboardError::FeatureDisabled { feature: "pdf", .. })
+        ));
+    }
+
+    #[test]
+    #[cfg(not(feature = "ocr"))]
+    fn test_image_without_feature_reports_disabled() {
+        let dir = TempDir::new().unwrap();
+        let file_path = dir.path().join("screenshot");
+        fs::write(&file_path, [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]).unwrap();
+
+        let result = read_file_contents(&file_path);
+        assert!(matches!(
+            result,
+            Err(CatboardError::FeatureDisabled { feature: "ocr", .. })
+        ));
+    }
+
+    /// In-memory [`FileSource`] for routing tests
+    #[derive(Default)]
+    struct MemorySource {
+        files: HashMap<PathBuf, Vec<u8>>,
+        /// How many reads of either kind were made
+        reads: Cell<usize>,
+    }
+
+    impl MemorySource {
+        fn with(mut self, path: &str, bytes: &[u8]) -> Self {
+            self.files.insert(PathBuf::from(path), bytes.to_vec());
+            self
+        }
+    }
+
+    impl FileSource for MemorySource {
+        fn read_prefix(&self, path: &Path, len: usize) -> Result<Vec<u8>> {
+            let bytes = self.read_all(path)?;
+            Ok(bytes[..len.min(bytes.len())].to_vec())
+        }
+
+        fn read_all(&self, path: &Path) -> Result<Vec<u8>> {
+            self.reads.set(self.reads.get() + 1);
+            self.files
+                .get(path)
+                .cloned()
+                .ok_or_else(|| CatboardError::FileNotFound(path.to_path_buf()))
+        }
+    }
+
+    #[test]
+    fn test_route_by_extension_skips_reading() {
+        // Nothing in the source: extension hits must not need the bytes
+        let source = MemorySource::default();
+        assert_eq!(
+            route(Path::new("a.pdf"), &source).unwrap(),
+            ContentKind::Pdf
+        );
+        assert_eq!(
+            route(Path::new("a.PNG"), &source).unwrap(),
+            ContentKind::Image
+        );
+    }
+
+    #[test]
+    fn test_route_sniffs_unknown_extensions() {
+        let source = MemorySource::default()
+            .with("report", b"%PDF-1.7\n")
+            .with("notes.txt", b"%PDF-1.4 truncated")
+            .with("shot", &[0xFF, 0xD8, 0xFF, 0xE0])
+            .with("README", b"# Hello");
+
+        assert_eq!(
+            route(Path::new("report"), &source).unwrap(),
+            ContentKind::Pdf
+        );
+        assert_eq!(
+            route(Path::new("notes.txt"), &source).unwrap(),
+            ContentKind::Pdf
+        );
+        assert_eq!(
+            route(Path::new("shot"), &source).unwrap(),
+            ContentKind::Image
+        );
+        assert_eq!(
+            route(Path::new("README"), &source).unwrap(),
+            ContentKind::Text
+        );
+    }
+
+    #[test]
+    fn test_route_missing_file() {
+        let result = route(Path::new("gone"), &MemorySource::default());
+        assert!(matches!(result, Err(CatboardError::FileNotFound(_))));
+    }
+
+    fn read_memory_text(bytes: &[u8], keep_bom: bool) -> Result<(String, &'static str)> {
+        let source = MemorySource::default().with("bom.txt", bytes);
+        let options = ReadOptions {
+            keep_bom,
+            ..ReadOptions::default()
+        };
+        read_text_file(Path::new("bom.txt"), &source, &options)
+    }
+
+    #[test]
+    fn test_utf8_bom_stripped_by_default() {
+        let (text, encoding) = read_memory_text(b"\xEF\xBB\xBF#!/bin/sh\n", false).unwrap();
+        assert_eq!(text, "#!/bin/sh\n");
+        assert_eq!(encoding, "utf-8");
+        let (text, _) = read_memory_text("\u{FEFF} only at start".as_bytes(), false).unwrap();
+        assert_eq!(text, " only at start");
+    }
+
+    #[test]
+    fn test_utf8_bom_kept_on_request() {
+        let (text, _) = read_memory_text(b"\xEF\xBB\xBFhi", true).unwrap();
+        assert_eq!(text, "\u{FEFF}hi");
+    }
+
+    #[test]
+    fn test_utf8_without_bom_unchanged() {
+        let (text, encoding) = read_memory_text(b"plain", false).unwrap();
+        assert_eq!(text, "plain");
+        assert_eq!(encoding, "utf-8");
+        // U+FEFF past the start is a character, not a byte order mark
+        let (text, _) = read_memory_text("mid\u{FEFF}dle".as_bytes(), false).unwrap();
+        assert_eq!(text, "mid\u{FEFF}dle");
+    }
+
+    #[test]
+    fn test_utf16le_bom_transcoded() {
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend("héllo 😀".encode_utf16().flat_map(u16::to_le_bytes));
+        let (text, encoding) = read_memory_text(&bytes, false).unwrap();
+        assert_eq!(text, "héllo 😀");
+        assert_eq!(encoding, "utf-16le");
+    }
+
+    #[test]
+    fn test_utf16be_bom_transcoded() {
+        let mut bytes = vec![0xFE, 0xFF];
+        bytes.extend("hi".encode_utf16().flat_map(u16::to_be_bytes));
+        let (text, encoding) = read_memory_text(&bytes, false).unwrap();
+        assert_eq!(text, "hi");
+        assert_eq!(encoding, "utf-16be");
+
+        let (text, _) = read_memory_text(&bytes, true).unwrap();
+        assert_eq!(text, "\u{FEFF}hi");
+    }
+
+    #[test]
+    fn test_malformed_utf16_is_io_error() {
+        // Odd length
+        assert!(matches!(
+            read_memory_text(&[0xFF, 0xFE, b'a'], false),
+            Err(CatboardError::IoError { .. })
+        ));
+        // Unpaired high surrogate
+        assert!(matches!(
+            read_memory_text(&[0xFF, 0xFE, 0x00, 0xD8], false),
+            Err(CatboardError::IoError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_extract_content_reports_utf16_encoding() {
+        let dir = TempDir::new().unwrap();
+        let file_path = dir.path().join("windows.txt");
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend("dir".encode_utf16().flat_map(u16::to_le_bytes));
+        fs::write(&file_path, bytes).unwrap();
+
+        let content = extract_content(&file_path).unwrap();
+        assert_eq!(content.text, "dir");
+        assert_eq!(content.detected_encoding.as_deref(), Some("utf-16le"));
+    }
+
+    #[test]
+    fn test_read_text_file_from_memory() {
+        let source = MemorySource::default()
+            .with("a.txt", "héllo".as_bytes())
+            .with("b.bin", b"ab\0cd")
+            .with("c.txt", &[0xC3, 0x28]);
+
+        let options = ReadOptions::default();
+        assert_eq!(
+            read_text_file(Path::new("a.txt"), &source, &options).unwrap(),
+            ("héllo".to_string(), "utf-8")
+        );
+        assert!(matches!(
+            read_text_file(Path::new("b.bin"), &source, &options),
+            Err(CatboardError::BinaryFile(_))
+        ));
+        assert!(matches!(
+            read_text_file(Path::new("c.txt"), &source, &options),
+            Err(CatboardError::EncodingError {
+                offset: 0,
+                byte: 0xC3,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_read_small_file_reads_once() {
+        let source = MemorySource::default()
+            .with("notes", b"short text\n")
+            .with("blob", b"\x01\0\x02")
+            .with("wide", b"\xFF\xFEh\0i\0");
+        let options = ReadOptions::default();
+
+        let content = read_small_file(Path::new("notes"), &source, &options).unwrap();
+        assert_eq!(content.text, "short text\n");
+        assert_eq!(source.reads.get(), 1);
+
+        assert!(matches!(
+            read_small_file(Path::new("blob"), &source, &options),
+            Err(CatboardError::BinaryFile(_))
+        ));
+        let wide = read_small_file(Path::new("wide"), &source, &options).unwrap();
+        assert_eq!(wide.text, "hi");
+        assert_eq!(wide.detected_encoding.as_deref(), Some("utf-16le"));
+    }
+
+    #[test]
+    fn test_read_small_file_still_sniffs_pdfs() {
+        let source = MemorySource::default().with("report", b"%PDF-1.7 not really");
+        let result = read_small_file(Path::new("report"), &source, &ReadOptions::default());
+        // Routed to the PDF extractor, which rejects it, rather than read as text
+        assert!(matches!(
+            result,
+            Err(CatboardError::ExtractionError { .. } | CatboardError::FeatureDisabled { .. })
+        ));
+    }
+
+    #[test]
+    fn test_read_text_file_short_prefix_is_whole_file() {
+        let source = MemorySource::default().with("a.txt", b"abc");
+        read_text_file(Path::new("a.txt"), &source, &ReadOptions::default()).unwrap();
+        assert_eq!(source.reads.get(), 1);
+
+        let big = vec![b'x'; BINARY_CHECK_SIZE + 1];
+        let source = MemorySource::default().with("big.txt", &big);
+        let (text, _) =
+            read_text_file(Path::new("big.txt"), &source, &ReadOptions::default()).unwrap();
+        assert_eq!(text.len(), BINARY_CHECK_SIZE + 1);
+        assert_eq!(source.reads.get(), 2);
+    }
+
+    #[test]
+    fn test_small_files_match_large_file_behavior() {
+        let dir = TempDir::new().unwrap();
+        for (name, bytes) in [
+            ("small.txt", b"\xEF\xBB\xBFsmall\n".to_vec()),
+            ("large.txt", {
+                let mut bytes = b"\xEF\xBB\xBF".to_vec();
+                bytes.extend(vec![b'z'; SMALL_FILE_SIZE as usize * 2]);
+                bytes
+            }),
+        ] {
+            let path = dir.path().join(name);
+            fs::write(&path, &bytes).unwrap();
+            let content = extract_content(&path).unwrap();
+            assert!(!content.text.starts_with('\u{FEFF}'), "{}", name);
+            assert_eq!(content.detected_encoding.as_deref(), Some("utf-8"));
+        }
+    }
+
+    #[test]
+    fn test_read_text_file_binary_past_check_window_is_text() {
+        let mut bytes = vec![b'a'; BINARY_CHECK_SIZE];
+        bytes.push(0);
+        let source = MemorySource::default().with("late.txt", &bytes);
+        // Only the first BINARY_CHECK_SIZE bytes are checked
+        assert!(read_text_file(Path::new("late.txt"), &source, &ReadOptions::default()).is_ok());
+    }
+
+    #[test]
+    fn test_read_text_file_configured_check_window() {
+        let mut bytes = vec![b'a'; 64];
+        bytes[40] = 0;
+        let source = MemorySource::default().with("mixed.txt", &bytes);
+        let read = |binary_check_bytes| {
+            let options = ReadOptions {
+                binary_check_bytes,
+                ..ReadOptions::default()
+            };
+            read_text_file(Path::new("mixed.txt"), &source, &options)
+        };
+
+        // Just outside the window, then just inside it
+        assert!(read(40).is_ok());
+        assert!(matches!(read(41), Err(CatboardError::BinaryFile(_))));
+    }
+
+    #[test]
+    fn test_read_text_file_zero_window_scans_whole_file() {
+        let mut bytes = vec![b'a'; BINARY_CHECK_SIZE * 4];
+        bytes.push(0);
+        let source = MemorySource::default().with("late.txt", &bytes);
+        let options = ReadOptions {
+            binary_check_bytes: 0,
+            ..ReadOptions::default()
+        };
+        assert!(matches!(
+            read_text_file(Path::new("late.txt"), &source, &options),
+            Err(CatboardError::BinaryFile(_))
+        ));
+        assert_eq!(source.reads.get(), 1);
+    }
+
+    #[test]
+    fn test_force_text_reads_false_positive_binary() {
+        // A text log with a stray NUL and a Latin-1 byte
+        let bytes = b"started\n\0\nuser=jos\xe9\n";
+        let source = MemorySource::default().with("app.log", bytes);
+        assert!(matches!(
+            read_text_file(Path::new("app.log"), &source, &ReadOptions::default()),
+            Err(CatboardError::BinaryFile(_))
+        ));
+
+        let options = ReadOptions {
+            force_text: true,
+            ..ReadOptions::default()
+        };
+        let (text, encoding) = read_text_file(Path::new("app.log"), &source, &options).unwrap();
+        assert_eq!(text, "started\n\0\nuser=jos\u{FFFD}\n");
+        assert_eq!(encoding, "utf-8");
+    }
+
+    #[test]
+    fn test_force_text_skips_content_routing() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("notes.pdf");
+        fs::write(&path, "%PDF- is how these files start").unwrap();
+        let options = ReadOptions {
+            force_text: true,
+            ..ReadOptions::default()
+        };
+        let content = extract_content_with(&path, &options).unwrap();
+        assert_eq!(content.text, "%PDF- is how these files start");
+    }
+
+    #[test]
+    fn test_is_binary_within_window() {
+        let mut bytes = vec![b'a'; 10];
+        bytes.push(0);
+        assert!(!is_binary_within(&bytes, 10));
+        assert!(is_binary_within(&bytes, 11));
+        assert!(is_binary_within(&bytes, 0));
+        assert!(!is_binary_within(b"\xFF\xFEh\x00", 0));
+    }
+
+    #[test]
+    fn test_append_text_creates_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("notes.md");
+        append_text(&path, "first").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "first\n");
+    }
+
+    #[test]
+    fn test_append_text_keeps_existing_content() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("notes.md");
+        fs::write(&path, "# Notes\n").unwrap();
+        append_text(&path, "one").unwrap();
+        append_text(&path, "two\n").unwrap();
+        append_text(&path, "").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "# Notes\none\ntwo\n");
+    }
+
+    #[test]
+    fn test_append_text_missing_directory() {
+        let result = append_text(Path::new("/nonexistent/dir/notes.md"), "x");
+        assert!(matches!(result, Err(CatboardError::IoError { .. })));
+    }
+
+    #[test]
+    fn test_write_atomic_creates_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("out.txt");
+        write_atomic(&path, b"hello").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"hello");
+        // The temporary file was renamed, not left behind
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_write_atomic_replaces_existing_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("out.txt");
+        fs::write(&path, "a much longer original").unwrap();
+        write_atomic(&path, b"short").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "short");
+    }
+
+    #[test]
+    fn test_write_atomic_missing_directory() {
+        let result = write_atomic(Path::new("/nonexistent/dir/out.txt"), b"x");
+        assert!(matches!(result, Err(CatboardError::IoError { .. })));
+    }
+
+    #[test]
+    fn test_write_atomic_failed_write_leaves_target_untouched() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("out.txt");
+        fs::write(&path, "original").unwrap();
+
+        let result = write_atomic_with(&path, |file| {
+            file.write_all(b"half of the new")?;
+            Err(io::Error::new(io::ErrorKind::Other, "simulated crash"))
+        });
+        assert!(matches!(result, Err(CatboardError::IoError { .. })));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "original");
+        // The partial temporary file was cleaned up
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_write_atomic_failed_write_creates_nothing() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("new.txt");
+        let result = write_atomic_with(&path, |_| {
+            Err(io::Error::new(io::ErrorKind::Other, "simulated crash"))
+        });
+        assert!(result.is_err());
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_write_atomic_keeps_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("script.sh");
+        fs::write(&path, "old").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o750)).unwrap();
+
+        write_atomic(&path, b"new").unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o750);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_write_atomic_through_symlink() {
+        let dir = TempDir::new().unwrap();
+        let target = dir.path().join("real.txt");
+        let link = dir.path().join("link.txt");
+        fs::write(&target, "old").unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        write_atomic(&link, b"new").unwrap();
+        assert!(fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(fs::read_to_string(&target).unwrap(), "new");
+    }
+
+    #[test]
+    fn test_cross_device_fallback() {
+        #[cfg(unix)]
+        assert!(is_cross_device(&io::Error::from_raw_os_error(18)));
+        assert!(!is_cross_device(&io::Error::from(io::ErrorKind::NotFound)));
+
+        let dir = TempDir::new().unwrap();
+        let (from, to) = (dir.path().join("from"), dir.path().join("to"));
+        fs::write(&from, "complete").unwrap();
+        fs::write(&to, "stale and longer").unwrap();
+        copy_into_place(&from, &to).unwrap();
+        assert_eq!(fs::read_to_string(&to).unwrap(), "complete");
+    }
 }
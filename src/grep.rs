@@ -0,0 +1,140 @@
+//! Line matching for `--count-matches` and `--grep`.
+
+use crate::error::Result;
+use crate::transform::Transform;
+use regex::Regex;
+
+/// Number of lines in `text` that contain a match for `pattern`
+pub fn count_matching_lines(text: &str, pattern: &Regex) -> usize {
+    text.lines().filter(|line| pattern.is_match(line)).count()
+}
+
+/// Keep the lines of `text` that match `pattern`, or those that don't when
+/// `invert` is set, returning them with the number kept.
+///
+/// Kept lines keep their original endings (`\n`, `\r\n` or none for a
+/// final unterminated line); the ending itself is never matched against.
+pub fn filter_lines(text: &str, pattern: &Regex, invert: bool) -> (String, usize) {
+    let mut kept = String::new();
+    let mut count = 0;
+    for line in text.split_inclusive('\n') {
+        let content = line
+            .strip_suffix('\n')
+            .map(|l| l.strip_suffix('\r').unwrap_or(l))
+            .unwrap_or(line);
+        if pattern.is_match(content) != invert {
+            kept.push_str(line);
+            count += 1;
+        }
+    }
+    (kept, count)
+}
+
+/// `--grep` as a transform
+#[derive(Debug, Clone)]
+pub struct Grep {
+    pub pattern: Regex,
+    pub invert: bool,
+}
+
+impl Grep {
+    /// The kept lines and how many there are
+    pub fn filter(&self, text: &str) -> (String, usize) {
+        filter_lines(text, &self.pattern, self.invert)
+    }
+}
+
+impl Transform for Grep {
+    fn apply(&self, input: &str) -> Result<String> {
+        Ok(self.filter(input).0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_lines_not_matches() {
+        let pattern = Regex::new("ERROR").unwrap();
+        let log = "ERROR one ERROR two\nINFO ok\nERROR three\n";
+        assert_eq!(count_matching_lines(log, &pattern), 2);
+    }
+
+    #[test]
+    fn test_count_edge_cases() {
+        let any = Regex::new("").unwrap();
+        assert_eq!(count_matching_lines("", &any), 0);
+        assert_eq!(count_matching_lines("a\r\nb", &any), 2);
+        // Anchors apply per line
+        let start = Regex::new("^b").unwrap();
+        assert_eq!(count_matching_lines("ab\nba\nbb", &start), 2);
+        let none = Regex::new("zzz").unwrap();
+        assert_eq!(count_matching_lines("a\nb\n", &none), 0);
+    }
+
+    #[test]
+    fn test_count_crlf_does_not_leak_into_match() {
+        let end = Regex::new("ok$").unwrap();
+        assert_eq!(count_matching_lines("ok\r\nnot ok?\r\nok", &end), 2);
+    }
+
+    #[test]
+    fn test_filter_keeps_matching_lines() {
+        let pattern = Regex::new("ERROR").unwrap();
+        let log = "ERROR a\nINFO b\nERROR c\nDEBUG d\n";
+        assert_eq!(
+            filter_lines(log, &pattern, false),
+            ("ERROR a\nERROR c\n".to_string(), 2)
+        );
+        assert_eq!(
+            filter_lines(log, &pattern, true),
+            ("INFO b\nDEBUG d\n".to_string(), 2)
+        );
+    }
+
+    #[test]
+    fn test_filter_preserves_line_endings() {
+        let pattern = Regex::new("keep").unwrap();
+        let text = "keep 1\r\ndrop\nkeep 2\nkeep 3";
+        assert_eq!(
+            filter_lines(text, &pattern, false),
+            ("keep 1\r\nkeep 2\nkeep 3".to_string(), 3)
+        );
+        let end = Regex::new("1$").unwrap();
+        assert_eq!(filter_lines(text, &end, false).0, "keep 1\r\n");
+    }
+
+    #[test]
+    fn test_filter_edge_cases() {
+        let pattern = Regex::new("x").unwrap();
+        assert_eq!(filter_lines("", &pattern, false), (String::new(), 0));
+        assert_eq!(filter_lines("", &pattern, true), (String::new(), 0));
+        assert_eq!(filter_lines("a\nb\n", &pattern, false), (String::new(), 0));
+        // Blank lines are lines too
+        assert_eq!(
+            filter_lines("x\n\nx\n", &pattern, true),
+            ("\n".to_string(), 1)
+        );
+    }
+
+    #[test]
+    fn test_count_agrees_with_filter() {
+        let pattern = Regex::new("[0-9]").unwrap();
+        let text = "a1\nb\r\nc2\n\nd3";
+        let (_, kept) = filter_lines(text, &pattern, false);
+        assert_eq!(kept, count_matching_lines(text, &pattern));
+    }
+
+    #[test]
+    fn test_grep_transform() {
+        let grep = Grep {
+            pattern: Regex::new("(?i)warn").unwrap(),
+            invert: false,
+        };
+        assert_eq!(
+            grep.apply("WARN a\nok\nwarn b\n").unwrap(),
+            "WARN a\nwarn b\n"
+        );
+    }
+}
@@ -0,0 +1,166 @@
+//! Append-only log of copies for `--history`.
+//!
+//! Each successful copy appends one JSON object per line:
+//!
+//! ```json
+//! {"timestamp":"2025-01-31T14:05:09Z","sources":["notes.md"],"bytes":42,"sha256":"..."}
+//! ```
+//!
+//! The copied text itself is only stored when explicitly requested, so the
+//! log can be kept without leaking clipboard contents.
+
+use crate::error::{CatboardError, Result};
+use crate::template::utc_date;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Metadata about one copy, written as a single JSON line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HistoryRecord {
+    /// Copy time as RFC 3339 UTC, e.g. `2025-01-31T14:05:09Z`
+    pub timestamp: String,
+    /// Inputs that made it into the copied payload, as displayed
+    pub sources: Vec<String>,
+    pub bytes: usize,
+    /// Lowercase hex SHA-256 of the copied payload
+    pub sha256: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+}
+
+impl HistoryRecord {
+    /// Build a record for `payload`, keeping the text only if `include_content`
+    pub fn new(
+        time: SystemTime,
+        sources: Vec<String>,
+        payload: &str,
+        include_content: bool,
+    ) -> Self {
+        Self {
+            timestamp: utc_datetime(time),
+            sources,
+            bytes: payload.len(),
+            sha256: sha256_hex(payload.as_bytes()),
+            content: include_content.then(|| payload.to_string()),
+        }
+    }
+}
+
+/// Append `record` to the JSON-lines log at `path`, creating it if needed.
+///
+/// The log is appended to in place rather than rewritten with
+/// [`write_atomic`](crate::file::write_atomic): a rewrite would drop
+/// records that another catboard appended in the meantime.
+///
+/// # Errors
+/// - `IoError` if the log cannot be opened or written
+pub fn append_history(path: &Path, record: &HistoryRecord) -> Result<()> {
+    let io_err = |e| CatboardError::io(path, e);
+
+    let mut line = serde_json::to_string(record).map_err(|e| io_err(e.into()))?;
+    line.push('\n');
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(io_err)?;
+    // One write per record keeps concurrent appends from interleaving
+    file.write_all(line.as_bytes()).map_err(io_err)
+}
+
+/// Format a time as `YYYY-MM-DDTHH:MM:SSZ`
+fn utc_datetime(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    format!(
+        "{}T{:02}:{:02}:{:02}Z",
+        utc_date(time),
+        secs / 3600 % 24,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn test_utc_datetime() {
+        assert_eq!(utc_datetime(at(0)), "1970-01-01T00:00:00Z");
+        assert_eq!(utc_datetime(at(1_738_332_309)), "2025-01-31T14:05:09Z");
+    }
+
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_record_omits_content_by_default() {
+        let record = HistoryRecord::new(at(0), vec!["a.txt".to_string()], "abc", false);
+        let json: serde_json::Value = serde_json::to_value(&record).unwrap();
+        assert_eq!(json["timestamp"], "1970-01-01T00:00:00Z");
+        assert_eq!(json["sources"], serde_json::json!(["a.txt"]));
+        assert_eq!(json["bytes"], 3);
+        assert_eq!(json["sha256"], sha256_hex(b"abc"));
+        assert!(json.get("content").is_none());
+    }
+
+    #[test]
+    fn test_record_includes_content_when_requested() {
+        let record = HistoryRecord::new(at(0), vec!["-".to_string()], "secret", true);
+        let json: serde_json::Value = serde_json::to_value(&record).unwrap();
+        assert_eq!(json["content"], "secret");
+    }
+
+    #[test]
+    fn test_append_history_writes_json_lines() {
+        let dir = TempDir::new().unwrap();
+        let log = dir.path().join("history.jsonl");
+
+        for name in ["a.txt", "b.txt"] {
+            let record = HistoryRecord::new(at(0), vec![name.to_string()], "x\ny", false);
+            append_history(&log, &record).unwrap();
+        }
+
+        let text = std::fs::read_to_string(&log).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        for (line, name) in lines.iter().zip(["a.txt", "b.txt"]) {
+            let json: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(json["sources"][0], name);
+            assert_eq!(json["bytes"], 3);
+        }
+    }
+
+    #[test]
+    fn test_append_history_missing_dir() {
+        let record = HistoryRecord::new(at(0), Vec::new(), "", false);
+        let result = append_history(Path::new("/nonexistent/dir/history.jsonl"), &record);
+        assert!(matches!(result, Err(CatboardError::IoError { .. })));
+    }
+}
@@ -0,0 +1,78 @@
+//! Ctrl-C handling for long-running modes such as `--watch` and
+//! `transform --monitor`.
+//!
+//! [`interrupt_token`] installs a handler the first time it's called, via
+//! `ctrlc`: SIGINT, SIGTERM and SIGHUP on Unix, and Ctrl-C, Ctrl-Break and
+//! closing the console on Windows. The handler only sets a shutdown flag;
+//! loops holding the token notice it on their next check and return
+//! normally, so the process can finish up and exit successfully. A second
+//! signal ends the process straight away, in case a loop is stuck.
+//!
+//! If the handler can't be installed, for example because something else in
+//! the process already owns it, the token is never cancelled and Ctrl-C ends
+//! the process as before.
+
+use crate::cancel::CancellationToken;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Once, OnceLock};
+
+static SHUTDOWN: OnceLock<Arc<AtomicBool>> = OnceLock::new();
+static INSTALL: Once = Once::new();
+
+/// A token cancelled once the process is interrupted.
+///
+/// Every call returns a token sharing the same flag.
+pub fn interrupt_token() -> CancellationToken {
+    // The flag exists before the handler that sets it
+    let token = shutdown_token();
+    INSTALL.call_once(|| {
+        let _ = ctrlc::set_handler(on_signal);
+    });
+    token
+}
+
+/// Exit status for a process ended by a second signal, as a shell reports
+/// for SIGINT
+const FORCED_EXIT_STATUS: i32 = 130;
+
+/// Ask loops to stop, or end the process if they were already asked
+fn on_signal() {
+    if !request_shutdown() {
+        std::process::exit(FORCED_EXIT_STATUS);
+    }
+}
+
+/// A token sharing the shutdown flag, without installing the handler
+fn shutdown_token() -> CancellationToken {
+    let flag = SHUTDOWN.get_or_init(|| Arc::new(AtomicBool::new(false)));
+    CancellationToken::from_flag(Arc::clone(flag))
+}
+
+/// Set the shutdown flag, returning whether this is the first request
+fn request_shutdown() -> bool {
+    SHUTDOWN
+        .get()
+        .is_some_and(|flag| !flag.swap(true, Ordering::Relaxed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One test, since the flag is process-wide. The handler itself isn't
+    /// installed here, where a signal would reach the test harness; the
+    /// integration tests send real signals to the binary.
+    #[test]
+    fn test_shutdown_flag_plumbing() {
+        let first = shutdown_token();
+        let second = shutdown_token();
+        assert!(!first.is_cancelled());
+
+        assert!(request_shutdown());
+        assert!(first.is_cancelled());
+        assert!(second.is_cancelled());
+
+        // A second request is what ends the process
+        assert!(!request_shutdown());
+    }
+}
@@ -0,0 +1,154 @@
+//! JSON reformatting for `--json-pretty` and `--json-minify`.
+//!
+//! Object keys keep their original order unless sorting is requested, so
+//! reformatting alone never reorders a document.
+
+use crate::error::{CatboardError, Result};
+use crate::transform::Transform;
+use serde_json::{Map, Value};
+
+/// How to lay out re-serialized JSON
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonStyle {
+    /// Indented with two spaces
+    Pretty,
+    /// No whitespace at all
+    Minified,
+}
+
+/// Parse `text` as JSON and serialize it again in `style`.
+///
+/// Parse errors name the line and column of the problem.
+pub fn format_json(text: &str, style: JsonStyle, sort_keys: bool) -> Result<String> {
+    let mut value: Value =
+        serde_json::from_str(text).map_err(|e| CatboardError::TransformError {
+            name: "json",
+            message: format!("invalid JSON: {}", e),
+        })?;
+    if sort_keys {
+        sort_object_keys(&mut value);
+    }
+
+    let formatted = match style {
+        JsonStyle::Pretty => serde_json::to_string_pretty(&value),
+        JsonStyle::Minified => serde_json::to_string(&value),
+    };
+    // Serializing a Value we just parsed cannot fail
+    Ok(formatted.expect("JSON value serializes"))
+}
+
+/// Recursively sort every object's keys
+fn sort_object_keys(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> = std::mem::take(map).into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            *map = entries
+                .into_iter()
+                .map(|(key, mut value)| {
+                    sort_object_keys(&mut value);
+                    (key, value)
+                })
+                .collect::<Map<String, Value>>();
+        }
+        Value::Array(items) => items.iter_mut().for_each(sort_object_keys),
+        _ => {}
+    }
+}
+
+/// `--json-pretty` / `--json-minify` as a transform
+#[derive(Debug, Clone, Copy)]
+pub struct JsonFormat {
+    pub style: JsonStyle,
+    pub sort_keys: bool,
+}
+
+impl Transform for JsonFormat {
+    fn apply(&self, input: &str) -> Result<String> {
+        format_json(input, self.style, self.sort_keys)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NESTED: &str = r#"{"b": [1, {"z": true, "a": null}], "a": {"y": "x", "c": 1.5}}"#;
+
+    #[test]
+    fn test_minify_keeps_key_order() {
+        assert_eq!(
+            format_json(NESTED, JsonStyle::Minified, false).unwrap(),
+            r#"{"b":[1,{"z":true,"a":null}],"a":{"y":"x","c":1.5}}"#
+        );
+    }
+
+    #[test]
+    fn test_minify_sorts_nested_keys() {
+        assert_eq!(
+            format_json(NESTED, JsonStyle::Minified, true).unwrap(),
+            r#"{"a":{"c":1.5,"y":"x"},"b":[1,{"a":null,"z":true}]}"#
+        );
+    }
+
+    #[test]
+    fn test_pretty_nested() {
+        let pretty = format_json(r#"{"a":[1,{"b":2}],"c":{}}"#, JsonStyle::Pretty, false).unwrap();
+        assert_eq!(
+            pretty,
+            "{\n  \"a\": [\n    1,\n    {\n      \"b\": 2\n    }\n  ],\n  \"c\": {}\n}"
+        );
+    }
+
+    #[test]
+    fn test_pretty_then_minify_round_trips() {
+        let pretty = format_json(NESTED, JsonStyle::Pretty, false).unwrap();
+        assert_eq!(
+            format_json(&pretty, JsonStyle::Minified, false).unwrap(),
+            format_json(NESTED, JsonStyle::Minified, false).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_scalars_and_unicode() {
+        assert_eq!(
+            format_json(" 42 \n", JsonStyle::Minified, true).unwrap(),
+            "42"
+        );
+        assert_eq!(
+            format_json(r#""café""#, JsonStyle::Pretty, false).unwrap(),
+            "\"caf\u{E9}\""
+        );
+    }
+
+    #[test]
+    fn test_invalid_json_reports_location() {
+        let err =
+            format_json("{\n  \"a\": 1,\n  \"b\": }\n", JsonStyle::Pretty, false).unwrap_err();
+        let message = err.to_string();
+        assert!(
+            message.starts_with("json failed: invalid JSON: "),
+            "{}",
+            message
+        );
+        assert!(message.contains("line 3 column 8"), "{}", message);
+    }
+
+    #[test]
+    fn test_trailing_garbage_is_invalid() {
+        assert!(format_json("{} {}", JsonStyle::Minified, false).is_err());
+        assert!(format_json("", JsonStyle::Minified, false).is_err());
+    }
+
+    #[test]
+    fn test_as_transform() {
+        let transform = JsonFormat {
+            style: JsonStyle::Minified,
+            sort_keys: true,
+        };
+        assert_eq!(
+            transform.apply(r#"{"b": 1, "a": 2}"#).unwrap(),
+            r#"{"a":2,"b":1}"#
+        );
+    }
+}
@@ -10,6 +10,32 @@
 //! - Binary file detection to prevent clipboard corruption
 //! - PDF text extraction
 //! - Image OCR on macOS via Vision framework
+//! - Recursive directory copying with a symlink policy
+//! - Cancelling long PDF extractions and directory walks from another thread
+//! - Composing a snippet in `$EDITOR` and copying it
+//! - Watch mode to re-copy files when they change
+//! - Monitoring the clipboard to transform whatever is copied
+//! - Stopping watch and monitor loops cleanly on Ctrl-C
+//! - Unified diffs between two files
+//! - Templates to wrap copied content with its filename, date and size
+//! - An opt-in JSON-lines history of copies
+//! - Expanding `${VAR}` environment references in copied text
+//! - Regex redaction of secrets before copying
+//! - Counting or keeping only the lines that match a regex
+//! - Copying only the first or last N lines
+//! - Sharing several files as one base64 tar or zip archive
+//! - Streaming inputs too large to hold in memory to a file
+//! - Opt-in warnings for content that looks like a secret
+//! - Confirmation prompts before large or sensitive copies
+//! - Unicode normalization (NFC, NFD, NFKC, NFKD) of copied text
+//! - Grapheme and display-width statistics, and truncation by columns
+//! - An OSC 52 terminal clipboard for SSH sessions
+//! - Copying to several clipboards at once (CLIPBOARD, PRIMARY, OSC 52)
+//! - URL percent-encoding and decoding of copied text
+//! - JSON pretty-printing and minifying, with optional key sorting
+//! - Quoting copied text as a shell word or JSON string
+//! - `data:` URIs for images and other files
+//! - Optional async wrappers for use from a Tokio runtime (`async` feature)
 //!
 //! ## Example
 //!
@@ -19,20 +45,80 @@
 //! let content = read_file_contents("file.txt").unwrap();
 //! copy_to_clipboard(&content).unwrap();
 //! ```
+//!
+//! Extraction can also be used on its own, without any clipboard access:
+//!
+//! ```no_run
+//! let text = catboard::extract_text("scan.pdf").unwrap();
+//! println!("{}", text);
+//! ```
 
+pub mod ansi;
+pub mod archive;
+pub mod broadcast;
+pub mod cancel;
 pub mod clipboard;
+pub mod comment;
+pub mod confirm;
+pub mod content;
+pub mod diff;
+pub mod editor;
+pub mod envsubst;
 pub mod error;
 pub mod file;
+pub mod grep;
+pub mod history;
+pub mod interrupt;
+pub mod json;
+pub mod mime;
+pub mod monitor;
+#[cfg(feature = "async")]
+pub mod nonblocking;
+pub mod normalize;
+pub mod numbers;
 pub mod ocr;
+pub mod osc52;
+pub mod paths;
+#[cfg(feature = "pdf")]
+mod pdf;
+pub mod pick;
+pub mod quote;
+pub mod redact;
+pub mod secrets;
+pub mod select;
+pub mod stream;
+pub mod template;
+pub mod transform;
+pub mod urlencode;
+pub mod walk;
+pub mod watch;
+pub mod width;
 
-pub use clipboard::{copy_to_clipboard, Clipboard, SystemClipboard};
+pub use cancel::CancellationToken;
+pub use clipboard::{
+    clear_clipboard, clipboard_available, clipboard_formats, copy_or_print, copy_to_clipboard,
+    display_detected, is_ssh_session, paste_from_clipboard, paste_html_from_clipboard,
+    paste_image_to_file, Clipboard, ClipboardFormat, ClipboardImage, CopyTarget, PrimarySelection,
+    Session, SystemClipboard, VerifiedClipboard,
+};
+pub use content::{ExtractedContent, FieldValue, FormField, PageError, PdfBackend, SourceKind};
 pub use error::{CatboardError, Result};
-pub use file::{read_file_contents, read_stdin};
+pub use file::{
+    append_text, extract_content, extract_content_with, extract_pdf_fields,
+    extract_pdf_fields_with, extract_pdf_text_with_progress, extract_text, is_binary,
+    is_binary_within, is_stdin_path, read_file_bytes, read_file_contents, read_file_contents_with,
+    read_stdin, read_stdin_as, read_stdin_bytes, read_verbatim, wait_for_stdin, write_atomic,
+    write_atomic_with, ReadOptions,
+};
+#[cfg(feature = "async")]
+pub use nonblocking::{
+    copy_file_to_clipboard_async, copy_to_clipboard_async, read_file_contents_async,
+};
 
 /// Copy contents of a file to the clipboard
 ///
 /// This is the main high-level function that combines file reading
-/// and clipboard operations.
+/// and clipboard operations. A path of `-` copies stdin.
 pub fn copy_file_to_clipboard<P: AsRef<std::path::Path>>(path: P) -> Result<usize> {
     let content = read_file_contents(path)?;
     let len = content.len();
@@ -0,0 +1,153 @@
+//! MIME type sniffing and `data:` URIs for `--data-uri`.
+
+use base64::prelude::*;
+
+/// Guess a MIME type from leading bytes (magic numbers).
+///
+/// Unrecognized content is `text/plain` when it is NUL-free UTF-8 and
+/// `application/octet-stream` otherwise.
+pub fn sniff_mime(bytes: &[u8]) -> &'static str {
+    if let Some(mime) = image_mime(bytes) {
+        return mime;
+    }
+    if bytes.starts_with(b"%PDF-") {
+        return "application/pdf";
+    }
+
+    match std::str::from_utf8(bytes) {
+        Ok(text) if !text.contains('\0') => "text/plain;charset=utf-8",
+        _ => "application/octet-stream",
+    }
+}
+
+/// The MIME type of an image format recognized from its leading bytes
+pub fn image_mime(bytes: &[u8]) -> Option<&'static str> {
+    const SIGNATURES: &[(&[u8], &str)] = &[
+        (
+            &[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A],
+            "image/png",
+        ),
+        (&[0xFF, 0xD8, 0xFF], "image/jpeg"),
+        (b"GIF87a", "image/gif"),
+        (b"GIF89a", "image/gif"),
+        (b"II*\0", "image/tiff"),
+        (b"MM\0*", "image/tiff"),
+    ];
+
+    if let Some((_, mime)) = SIGNATURES
+        .iter()
+        .find(|(magic, _)| bytes.starts_with(magic))
+    {
+        return Some(mime);
+    }
+    if bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(b"WEBP") {
+        return Some("image/webp");
+    }
+    // BMP: "BM" followed by file size and four reserved zero bytes
+    if bytes.starts_with(b"BM") && bytes.get(6..10) == Some(&[0, 0, 0, 0]) {
+        return Some("image/bmp");
+    }
+    if bytes.get(4..8) == Some(b"ftyp") {
+        match bytes.get(8..12) {
+            Some(b"heic" | b"heix" | b"hevc") => return Some("image/heic"),
+            Some(b"heif" | b"mif1" | b"msf1") => return Some("image/heif"),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Whether `mime` is a bare `type/subtype` MIME type such as
+/// `image/svg+xml`, without parameters
+pub fn is_mime_type(mime: &str) -> bool {
+    // RFC 6838 restricted-name: alphanumerics and !#$&-^_.+
+    let is_name = |part: &str| {
+        !part.is_empty()
+            && part.len() <= 127
+            && part.starts_with(|c: char| c.is_ascii_alphanumeric())
+            && part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "!#$&-^_.+".contains(c))
+    };
+    matches!(mime.split_once('/'), Some((kind, subtype)) if is_name(kind) && is_name(subtype))
+}
+
+/// Encode `bytes` as a base64 `data:` URI with a sniffed MIME type
+pub fn data_uri(bytes: &[u8]) -> String {
+    format!(
+        "data:{};base64,{}",
+        sniff_mime(bytes),
+        BASE64_STANDARD.encode(bytes)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A complete 1x1 transparent PNG
+    const TINY_PNG: &[u8] = &[
+        0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0x00, 0x00, 0x0D, 0x49, 0x48, 0x44,
+        0x52, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00, 0x00, 0x1F,
+        0x15, 0xC4, 0x89, 0x00, 0x00, 0x00, 0x0D, 0x49, 0x44, 0x41, 0x54, 0x78, 0x9C, 0x63, 0x00,
+        0x01, 0x00, 0x00, 0x05, 0x00, 0x01, 0x0D, 0x0A, 0x2D, 0xB4, 0x00, 0x00, 0x00, 0x00, 0x49,
+        0x45, 0x4E, 0x44, 0xAE, 0x42, 0x60, 0x82,
+    ];
+
+    #[test]
+    fn test_png_data_uri() {
+        let uri = data_uri(TINY_PNG);
+        assert!(
+            uri.starts_with("data:image/png;base64,iVBORw0KGgo"),
+            "{}",
+            uri
+        );
+        let payload = uri.split_once(',').unwrap().1;
+        assert_eq!(BASE64_STANDARD.decode(payload).unwrap(), TINY_PNG);
+    }
+
+    #[test]
+    fn test_sniff_images() {
+        assert_eq!(sniff_mime(&[0xFF, 0xD8, 0xFF, 0xE0]), "image/jpeg");
+        assert_eq!(sniff_mime(b"GIF89a..."), "image/gif");
+        assert_eq!(sniff_mime(b"MM\0*...."), "image/tiff");
+        assert_eq!(sniff_mime(b"RIFF\0\0\0\0WEBPVP8 "), "image/webp");
+        assert_eq!(sniff_mime(b"BM\x36\0\0\0\0\0\0\0"), "image/bmp");
+        assert_eq!(sniff_mime(b"\0\0\0\x18ftypheic"), "image/heic");
+        assert_eq!(sniff_mime(b"\0\0\0\x18ftypmif1"), "image/heif");
+    }
+
+    #[test]
+    fn test_sniff_non_images() {
+        assert_eq!(sniff_mime(b"%PDF-1.7\n"), "application/pdf");
+        assert_eq!(
+            sniff_mime("h\u{E9}llo".as_bytes()),
+            "text/plain;charset=utf-8"
+        );
+        assert_eq!(sniff_mime(b""), "text/plain;charset=utf-8");
+        assert_eq!(sniff_mime(&[0x00, 0x01, 0x02]), "application/octet-stream");
+        assert_eq!(sniff_mime(&[0xC3, 0x28]), "application/octet-stream");
+        // MP4 also uses ftyp, with a brand that isn't an image
+        assert_eq!(
+            sniff_mime(b"\0\0\0\x18ftypisom"),
+            "application/octet-stream"
+        );
+    }
+
+    #[test]
+    fn test_text_data_uri() {
+        assert_eq!(data_uri(b"hi"), "data:text/plain;charset=utf-8;base64,aGk=");
+    }
+
+    #[test]
+    fn test_is_mime_type() {
+        assert!(is_mime_type("image/svg+xml"));
+        assert!(is_mime_type("application/x-figma"));
+        assert!(is_mime_type("application/vnd.oasis.opendocument.text"));
+        assert!(!is_mime_type("image"));
+        assert!(!is_mime_type("image/"));
+        assert!(!is_mime_type("/svg"));
+        assert!(!is_mime_type("text/plain; charset=utf-8"));
+        assert!(!is_mime_type("text/plain/extra"));
+    }
+}
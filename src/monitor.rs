@@ -0,0 +1,366 @@
+//! Clipboard monitoring for `catboard transform --monitor`.
+//!
+//! Clipboards have no portable change notification, so the text is polled.
+//! A change is handled once it has stayed the same for a short settle time,
+//! so an application that writes the clipboard in several steps is seen
+//! once. Text the monitor writes back itself is remembered and never
+//! handled again, which keeps a rewrite from retriggering the monitor.
+//! A clipboard that briefly can't be read, as when another application
+//! holds it, is reported and polled again rather than ending the monitor.
+
+use crate::cancel::CancellationToken;
+use crate::clipboard::Clipboard;
+use crate::error::{CatboardError, Result};
+use std::ops::ControlFlow;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Default time between clipboard reads
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Default time new text must stay on the clipboard before it's handled
+pub const DEFAULT_SETTLE: Duration = Duration::from_millis(200);
+
+/// Decides which polled clipboard texts are new, settled and not the
+/// monitor's own writes
+#[derive(Debug)]
+pub struct ChangeDetector {
+    settle: Duration,
+    handled: Option<String>,
+    pending: Option<(String, Instant)>,
+}
+
+impl ChangeDetector {
+    /// Start from the clipboard's current text, which counts as handled
+    pub fn new(initial: Option<String>, settle: Duration) -> Self {
+        Self {
+            settle,
+            handled: initial,
+            pending: None,
+        }
+    }
+
+    /// Feed one poll of the clipboard (`None` when it holds no text).
+    ///
+    /// Returns the text once it differs from the last handled text and has
+    /// been seen unchanged for the settle time.
+    pub fn observe(&mut self, current: Option<String>, now: Instant) -> Option<String> {
+        let Some(current) = current else {
+            self.pending = None;
+            return None;
+        };
+        if self.handled.as_deref() == Some(current.as_str()) {
+            self.pending = None;
+            return None;
+        }
+
+        let since = match &self.pending {
+            Some((pending, since)) if *pending == current => *since,
+            _ => {
+                self.pending = Some((current.clone(), now));
+                now
+            }
+        };
+        if now.duration_since(since) < self.settle {
+            return None;
+        }
+        self.pending = None;
+        self.handled = Some(current.clone());
+        Some(current)
+    }
+
+    /// Note text the monitor put on the clipboard itself
+    pub fn wrote(&mut self, text: &str) {
+        self.handled = Some(text.to_string());
+        self.pending = None;
+    }
+}
+
+/// How [`monitor_clipboard`] polls
+#[derive(Debug, Clone)]
+pub struct MonitorOptions {
+    pub interval: Duration,
+    pub settle: Duration,
+    /// Checked before each poll; once cancelled, monitoring stops cleanly
+    pub cancel: Option<CancellationToken>,
+}
+
+impl Default for MonitorOptions {
+    fn default() -> Self {
+        Self {
+            interval: DEFAULT_POLL_INTERVAL,
+            settle: DEFAULT_SETTLE,
+            cancel: None,
+        }
+    }
+}
+
+/// The clipboard's text, or `None` when it holds something else
+fn read_text(clipboard: &mut dyn Clipboard) -> Result<Option<String>> {
+    match clipboard.get_text() {
+        Ok(text) => Ok(Some(text)),
+        Err(CatboardError::ClipboardFormatUnavailable(_)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Poll `clipboard` and call `on_change` with each new text.
+///
+/// `on_change` returns `Continue(Some(text))` to replace the clipboard's
+/// text, `Continue(None)` to leave it, or `Break` to stop. The text already
+/// on the clipboard when monitoring starts is not handled. Blocks until
+/// `on_change` breaks or `options.cancel` is cancelled.
+///
+/// Once monitoring has started, a failed read or write is passed to
+/// `on_error` and polling carries on. A run of failed reads is reported
+/// once, when it starts.
+///
+/// # Errors
+/// - `ClipboardError` if the clipboard can't be read when monitoring starts
+pub fn monitor_clipboard<F, E>(
+    clipboard: &mut dyn Clipboard,
+    options: &MonitorOptions,
+    mut on_change: F,
+    mut on_error: E,
+) -> Result<()>
+where
+    F: FnMut(&str) -> ControlFlow<(), Option<String>>,
+    E: FnMut(&CatboardError),
+{
+    let cancelled = || {
+        options
+            .cancel
+            .as_ref()
+            .is_some_and(|token| token.is_cancelled())
+    };
+    let mut detector = ChangeDetector::new(read_text(clipboard)?, options.settle);
+    let mut failing = false;
+
+    while !cancelled() {
+        thread::sleep(options.interval);
+        if cancelled() {
+            break;
+        }
+        let current = match read_text(clipboard) {
+            Ok(current) => {
+                failing = false;
+                current
+            }
+            Err(e) => {
+                if !failing {
+                    on_error(&e);
+                }
+                failing = true;
+                continue;
+            }
+        };
+        let Some(text) = detector.observe(current, Instant::now()) else {
+            continue;
+        };
+        match on_change(&text) {
+            ControlFlow::Break(()) => break,
+            ControlFlow::Continue(Some(replacement)) if replacement != text => {
+                match clipboard.set_text(&replacement) {
+                    Ok(()) => detector.wrote(&replacement),
+                    Err(e) => on_error(&e),
+                }
+            }
+            ControlFlow::Continue(_) => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clipboard::ClipboardFormat;
+    use std::collections::VecDeque;
+
+    const SETTLE: Duration = Duration::from_millis(200);
+
+    fn text(s: &str) -> Option<String> {
+        Some(s.to_string())
+    }
+
+    #[test]
+    fn test_initial_text_is_not_handled() {
+        let start = Instant::now();
+        let mut detector = ChangeDetector::new(text("old"), Duration::ZERO);
+        assert_eq!(detector.observe(text("old"), start), None);
+        assert_eq!(detector.observe(text("new"), start), text("new"));
+    }
+
+    #[test]
+    fn test_change_waits_to_settle() {
+        let start = Instant::now();
+        let mut detector = ChangeDetector::new(None, SETTLE);
+        assert_eq!(detector.observe(text("a"), start), None);
+        assert_eq!(detector.observe(text("a"), start + SETTLE / 2), None);
+        // A further change restarts the wait
+        assert_eq!(detector.observe(text("ab"), start + SETTLE), None);
+        assert_eq!(detector.observe(text("ab"), start + SETTLE * 3 / 2), None);
+        assert_eq!(detector.observe(text("ab"), start + SETTLE * 2), text("ab"));
+        // Handled once only
+        assert_eq!(detector.observe(text("ab"), start + SETTLE * 3), None);
+    }
+
+    #[test]
+    fn test_own_writes_are_ignored() {
+        let start = Instant::now();
+        let mut detector = ChangeDetector::new(None, Duration::ZERO);
+        assert_eq!(detector.observe(text(" padded "), start), text(" padded "));
+        detector.wrote("padded");
+        assert_eq!(detector.observe(text("padded"), start), None);
+        assert_eq!(detector.observe(text("padded"), start + SETTLE), None);
+        // The user copying the original again is a new change
+        assert_eq!(detector.observe(text(" padded "), start), text(" padded "));
+    }
+
+    #[test]
+    fn test_non_text_content_is_skipped() {
+        let start = Instant::now();
+        let mut detector = ChangeDetector::new(text("a"), Duration::ZERO);
+        assert_eq!(detector.observe(None, start), None);
+        assert_eq!(detector.observe(text("a"), start), None);
+    }
+
+    /// What one read of a [`ScriptedClipboard`] sees
+    enum Read {
+        /// New text copied by the user
+        Text(&'static str),
+        /// Whatever was last read or written
+        Same,
+        /// A failed read
+        Fail,
+    }
+
+    /// Returns scripted reads in turn and records writes. Running out of
+    /// the script reads the last text or write.
+    struct ScriptedClipboard {
+        reads: VecDeque<Read>,
+        current: Option<String>,
+        writes: Vec<String>,
+    }
+
+    impl ScriptedClipboard {
+        fn new(reads: impl IntoIterator<Item = Read>) -> Self {
+            Self {
+                reads: reads.into_iter().collect(),
+                current: None,
+                writes: Vec::new(),
+            }
+        }
+    }
+
+    impl Clipboard for ScriptedClipboard {
+        fn set_text(&mut self, text: &str) -> Result<()> {
+            self.writes.push(text.to_string());
+            self.current = Some(text.to_string());
+            Ok(())
+        }
+
+        fn get_text(&mut self) -> Result<String> {
+            match self.reads.pop_front() {
+                Some(Read::Text(next)) => self.current = Some(next.to_string()),
+                Some(Read::Fail) => return Err(CatboardError::ClipboardError("busy".to_string())),
+                Some(Read::Same) | None => {}
+            }
+            self.current
+                .clone()
+                .ok_or(CatboardError::ClipboardFormatUnavailable(
+                    ClipboardFormat::Text,
+                ))
+        }
+    }
+
+    const NO_WAIT: MonitorOptions = MonitorOptions {
+        interval: Duration::ZERO,
+        settle: Duration::ZERO,
+        cancel: None,
+    };
+
+    #[test]
+    fn test_monitor_rewrites_changes_without_retriggering() {
+        let mut clipboard = ScriptedClipboard::new([
+            Read::Text("start"),
+            Read::Text("start"),
+            Read::Text("  one  "),
+            Read::Same,
+            Read::Same,
+            Read::Text("two"),
+        ]);
+        let mut seen = Vec::new();
+        monitor_clipboard(
+            &mut clipboard,
+            &NO_WAIT,
+            |text| {
+                seen.push(text.to_string());
+                match text {
+                    "  one  " => ControlFlow::Continue(Some(text.trim().to_string())),
+                    _ => ControlFlow::Break(()),
+                }
+            },
+            |e| panic!("unexpected error: {}", e),
+        )
+        .unwrap();
+        // The trimmed write stayed on the clipboard for two polls without
+        // being handed back
+        assert_eq!(clipboard.writes, vec!["one"]);
+        assert_eq!(seen, vec!["  one  ", "two"]);
+    }
+
+    #[test]
+    fn test_monitor_stops_when_cancelled() {
+        let mut clipboard = ScriptedClipboard::new([Read::Text("same")]);
+        let token = CancellationToken::new();
+        token.cancel();
+        let options = MonitorOptions {
+            cancel: Some(token),
+            ..NO_WAIT
+        };
+        monitor_clipboard(
+            &mut clipboard,
+            &options,
+            |_| unreachable!(),
+            |_| unreachable!(),
+        )
+        .unwrap();
+        assert!(clipboard.writes.is_empty());
+    }
+
+    #[test]
+    fn test_monitor_survives_failed_reads() {
+        let mut clipboard = ScriptedClipboard::new([
+            Read::Text("start"),
+            Read::Fail,
+            Read::Fail,
+            Read::Text("new"),
+            Read::Fail,
+            Read::Text("newer"),
+        ]);
+        let (mut seen, mut errors) = (Vec::new(), Vec::new());
+        monitor_clipboard(
+            &mut clipboard,
+            &NO_WAIT,
+            |text| {
+                seen.push(text.to_string());
+                match text {
+                    "newer" => ControlFlow::Break(()),
+                    _ => ControlFlow::Continue(None),
+                }
+            },
+            |e| errors.push(e.to_string()),
+        )
+        .unwrap();
+        assert_eq!(seen, ["new", "newer"]);
+        // Each run of failures is reported once
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].contains("busy"), "{}", errors[0]);
+
+        // The clipboard must be readable to start with
+        let mut clipboard = ScriptedClipboard::new([Read::Fail]);
+        let result = monitor_clipboard(&mut clipboard, &NO_WAIT, |_| unreachable!(), |_| {});
+        assert!(matches!(result, Err(CatboardError::ClipboardError(_))));
+    }
+}
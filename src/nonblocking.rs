@@ -0,0 +1,110 @@
+//! Async wrappers around the blocking API, behind the `async` feature.
+//!
+//! Each call runs its blocking work with [`tokio::task::spawn_blocking`], so
+//! the runtime's worker threads are never stalled on file or clipboard I/O.
+//! The futures must be awaited inside a Tokio runtime.
+//!
+//! The work itself is unchanged. Clipboard access in particular still
+//! blocks a thread for as long as the system clipboard takes, and on Linux
+//! that can include waiting for the display server; it just isn't one of
+//! the runtime's worker threads. Dropping a future does not cancel its work.
+//!
+//! ```no_run
+//! # async fn example() -> catboard::Result<()> {
+//! let bytes = catboard::copy_file_to_clipboard_async("notes.txt").await?;
+//! println!("copied {} bytes", bytes);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{CatboardError, Result};
+use std::panic;
+use std::path::Path;
+
+/// Run `work` on Tokio's blocking thread pool and wait for its result.
+///
+/// If `work` panics, the panic resumes in the caller. If the runtime shuts
+/// down before `work` starts, this fails with [`CatboardError::Cancelled`].
+async fn run_blocking<F, T>(work: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    match tokio::task::spawn_blocking(work).await {
+        Ok(result) => result,
+        Err(err) if err.is_panic() => panic::resume_unwind(err.into_panic()),
+        Err(err) => Err(CatboardError::Cancelled(err.to_string())),
+    }
+}
+
+/// Async [`read_file_contents`](crate::read_file_contents)
+pub async fn read_file_contents_async<P: AsRef<Path>>(path: P) -> Result<String> {
+    let path = path.as_ref().to_path_buf();
+    run_blocking(move || crate::read_file_contents(path)).await
+}
+
+/// Async [`copy_to_clipboard`](crate::copy_to_clipboard)
+pub async fn copy_to_clipboard_async(text: String) -> Result<()> {
+    run_blocking(move || crate::copy_to_clipboard(&text)).await
+}
+
+/// Async [`copy_file_to_clipboard`](crate::copy_file_to_clipboard)
+pub async fn copy_file_to_clipboard_async<P: AsRef<Path>>(path: P) -> Result<usize> {
+    let path = path.as_ref().to_path_buf();
+    run_blocking(move || crate::copy_file_to_clipboard(path)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_read_file_contents_async() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "read off the runtime").unwrap();
+
+        let text = read_file_contents_async(&path).await.unwrap();
+        assert_eq!(text, "read off the runtime");
+    }
+
+    #[tokio::test]
+    async fn test_async_errors_match_sync_errors() {
+        let result = read_file_contents_async("/nonexistent/file.txt").await;
+        assert!(matches!(result, Err(CatboardError::FileNotFound(_))));
+
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("binary.bin");
+        std::fs::write(&path, [0x00, 0x01, 0x02]).unwrap();
+        let result = copy_file_to_clipboard_async(&path).await;
+        assert!(matches!(result, Err(CatboardError::BinaryFile(_))));
+    }
+
+    #[tokio::test]
+    #[ignore = "Requires display server"]
+    async fn test_copy_file_to_clipboard_async() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "copied off the runtime").unwrap();
+
+        let bytes = copy_file_to_clipboard_async(&path).await.unwrap();
+        assert_eq!(bytes, "copied off the runtime".len());
+    }
+
+    #[tokio::test]
+    async fn test_work_runs_off_the_calling_thread() {
+        let caller = thread::current().id();
+        let worker = run_blocking(|| Ok(thread::current().id())).await.unwrap();
+        assert_ne!(worker, caller);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "worker failed")]
+    async fn test_panics_resume_in_the_caller() {
+        run_blocking::<_, ()>(|| panic!("worker failed"))
+            .await
+            .unwrap();
+    }
+}
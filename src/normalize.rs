@@ -0,0 +1,134 @@
+//! Unicode normalization for `--normalize`.
+//!
+//! Text from macOS (filenames especially) is often in decomposed NFD form,
+//! which renders the same as NFC but compares and pastes differently.
+
+use crate::error::Result;
+use crate::transform::Transform;
+use std::fmt;
+use std::str::FromStr;
+use unicode_normalization::UnicodeNormalization;
+
+/// A Unicode normalization form
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NormalizationForm {
+    /// Canonical composition
+    Nfc,
+    /// Canonical decomposition
+    Nfd,
+    /// Compatibility composition
+    Nfkc,
+    /// Compatibility decomposition
+    Nfkd,
+}
+
+impl fmt::Display for NormalizationForm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            NormalizationForm::Nfc => "nfc",
+            NormalizationForm::Nfd => "nfd",
+            NormalizationForm::Nfkc => "nfkc",
+            NormalizationForm::Nfkd => "nfkd",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for NormalizationForm {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "nfc" => Ok(NormalizationForm::Nfc),
+            "nfd" => Ok(NormalizationForm::Nfd),
+            "nfkc" => Ok(NormalizationForm::Nfkc),
+            "nfkd" => Ok(NormalizationForm::Nfkd),
+            _ => Err(format!(
+                "unknown normalization form '{}' (expected nfc, nfd, nfkc or nfkd)",
+                s
+            )),
+        }
+    }
+}
+
+/// Normalize `text` to the given form
+pub fn normalize(text: &str, form: NormalizationForm) -> String {
+    match form {
+        NormalizationForm::Nfc => text.nfc().collect(),
+        NormalizationForm::Nfd => text.nfd().collect(),
+        NormalizationForm::Nfkc => text.nfkc().collect(),
+        NormalizationForm::Nfkd => text.nfkd().collect(),
+    }
+}
+
+impl Transform for NormalizationForm {
+    fn apply(&self, input: &str) -> Result<String> {
+        Ok(normalize(input, *self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // "é" precomposed (U+00E9) and as "e" + combining acute (U+0301)
+    const COMPOSED: &str = "caf\u{E9}";
+    const DECOMPOSED: &str = "cafe\u{301}";
+
+    #[test]
+    fn test_nfc_composes() {
+        assert_eq!(normalize(DECOMPOSED, NormalizationForm::Nfc), COMPOSED);
+        assert_eq!(normalize(COMPOSED, NormalizationForm::Nfc), COMPOSED);
+    }
+
+    #[test]
+    fn test_nfd_decomposes() {
+        assert_eq!(normalize(COMPOSED, NormalizationForm::Nfd), DECOMPOSED);
+    }
+
+    #[test]
+    fn test_compatibility_forms_fold_ligatures() {
+        // U+FB01 LATIN SMALL LIGATURE FI has only a compatibility mapping
+        assert_eq!(
+            normalize("\u{FB01}le", NormalizationForm::Nfc),
+            "\u{FB01}le"
+        );
+        assert_eq!(normalize("\u{FB01}le", NormalizationForm::Nfkc), "file");
+        assert_eq!(
+            normalize("\u{FB01}l\u{E9}", NormalizationForm::Nfkd),
+            "file\u{301}"
+        );
+    }
+
+    #[test]
+    fn test_ascii_unchanged() {
+        for form in [
+            NormalizationForm::Nfc,
+            NormalizationForm::Nfd,
+            NormalizationForm::Nfkc,
+            NormalizationForm::Nfkd,
+        ] {
+            assert_eq!(normalize("plain text\n", form), "plain text\n");
+        }
+    }
+
+    #[test]
+    fn test_parse_form() {
+        assert_eq!("nfc".parse(), Ok(NormalizationForm::Nfc));
+        assert_eq!("NFKD".parse(), Ok(NormalizationForm::Nfkd));
+        let err = "nfx".parse::<NormalizationForm>().unwrap_err();
+        assert!(err.contains("unknown normalization form 'nfx'"));
+    }
+
+    #[test]
+    fn test_display_round_trips() {
+        let form = NormalizationForm::Nfkc;
+        assert_eq!(form.to_string().parse(), Ok(form));
+    }
+
+    #[test]
+    fn test_as_transform() {
+        let transform: &dyn Transform = &NormalizationForm::Nfc;
+        assert_eq!(transform.apply(DECOMPOSED).unwrap(), COMPOSED);
+    }
+}
@@ -0,0 +1,258 @@
+//! Locale number formatting for `--reformat-numbers`.
+//!
+//! Numbers written with English thousands separators (`1,234,567.89`) are
+//! rewritten with a locale's thousands separator and decimal mark, so `de`
+//! gives `1.234.567,89`. Only whole words that read as a number once
+//! surrounding brackets, quotes, currency symbols and punctuation are set
+//! aside are touched. A number without a thousands separator is left
+//! alone, since it's as likely to be an ID, a postcode or a value in code
+//! (`sleep 0.5`) as a quantity. So are versions (`1.2.3`), addresses
+//! (`192.168.0.1`), dates, times, hex literals and identifiers like
+//! `item_1,000`.
+//!
+//! The locale table is built in and covers common languages. It doesn't
+//! follow every regional convention, such as Indian lakh grouping.
+
+use crate::error::Result;
+use crate::transform::Transform;
+use regex::Regex;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// How a locale writes numbers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumberLocale {
+    tag: String,
+    group: &'static str,
+    decimal: char,
+    /// Fewest digits that must precede the first separator, so `es` writes
+    /// `1234` but `12.345`
+    min_grouping: usize,
+}
+
+/// Narrow no-break space, used by French
+const NNBSP: &str = "\u{202F}";
+/// No-break space
+const NBSP: &str = "\u{A0}";
+
+/// `(tag, group, decimal, min_grouping)`, matched on the full tag first and
+/// then on the language alone
+const LOCALES: &[(&str, &str, char, usize)] = &[
+    ("en", ",", '.', 1),
+    ("ja", ",", '.', 1),
+    ("ko", ",", '.', 1),
+    ("zh", ",", '.', 1),
+    ("he", ",", '.', 1),
+    ("th", ",", '.', 1),
+    ("de", ".", ',', 1),
+    ("da", ".", ',', 1),
+    ("el", ".", ',', 1),
+    ("id", ".", ',', 1),
+    ("it", ".", ',', 1),
+    ("nl", ".", ',', 1),
+    ("pt", ".", ',', 1),
+    ("tr", ".", ',', 1),
+    ("es", ".", ',', 2),
+    ("pt-pt", NBSP, ',', 2),
+    ("pl", NBSP, ',', 2),
+    ("fr", NNBSP, ',', 1),
+    ("cs", NBSP, ',', 1),
+    ("fi", NBSP, ',', 1),
+    ("hu", NBSP, ',', 1),
+    ("nb", NBSP, ',', 1),
+    ("no", NBSP, ',', 1),
+    ("ru", NBSP, ',', 1),
+    ("sk", NBSP, ',', 1),
+    ("sv", NBSP, ',', 1),
+    ("uk", NBSP, ',', 1),
+    ("de-ch", "\u{2019}", '.', 1),
+    ("de-li", "\u{2019}", '.', 1),
+    ("it-ch", "\u{2019}", '.', 1),
+];
+
+impl fmt::Display for NumberLocale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.tag)
+    }
+}
+
+impl FromStr for NumberLocale {
+    type Err = String;
+
+    /// Accepts tags like `de`, `de-DE`, `de_DE` and `de_DE.UTF-8`
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let tag = s
+            .split(['.', '@'])
+            .next()
+            .unwrap_or_default()
+            .replace('_', "-")
+            .to_ascii_lowercase();
+        let language = tag.split('-').next().unwrap_or_default();
+        let found = LOCALES
+            .iter()
+            .find(|(name, ..)| *name == tag)
+            .or_else(|| LOCALES.iter().find(|(name, ..)| *name == language));
+        match found {
+            Some(&(_, group, decimal, min_grouping)) => Ok(NumberLocale {
+                tag: s.to_string(),
+                group,
+                decimal,
+                min_grouping,
+            }),
+            None => Err(format!(
+                "unknown locale '{}' (expected a tag like en-US, de-DE or fr-FR)",
+                s
+            )),
+        }
+    }
+}
+
+/// Characters set aside before a word is read as a number
+const LEADING: &[char] = &[
+    '(', '[', '{', '"', '\'', '\u{201C}', '\u{2018}', '$', '€', '£', '¥',
+];
+const TRAILING: &[char] = &[
+    ')', ']', '}', '"', '\'', '\u{201D}', '\u{2019}', '.', ',', ';', ':', '!', '?', '%',
+];
+
+fn number_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"^(-?)(\d{1,3}(?:,\d{3})+)(?:\.(\d+))?$").expect("valid number pattern")
+    })
+}
+
+fn word_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"\S+").expect("valid word pattern"))
+}
+
+impl NumberLocale {
+    /// Rewrite a bare number with thousands separators, or `None` if it
+    /// isn't one
+    fn format(&self, number: &str) -> Option<String> {
+        let captures = number_pattern().captures(number)?;
+        let sign = &captures[1];
+        let integer = captures[2].replace(',', "");
+        let fraction = captures.get(3).map(|m| m.as_str());
+
+        if integer.starts_with('0') {
+            return None;
+        }
+
+        let mut formatted = sign.to_string();
+        if integer.len() >= 3 + self.min_grouping {
+            let first = integer.len() % 3;
+            let groups = (first..integer.len())
+                .step_by(3)
+                .map(|start| &integer[start..start + 3]);
+            let parts: Vec<&str> = std::iter::once(&integer[..first])
+                .filter(|head| !head.is_empty())
+                .chain(groups)
+                .collect();
+            formatted.push_str(&parts.join(self.group));
+        } else {
+            formatted.push_str(&integer);
+        }
+        if let Some(fraction) = fraction {
+            formatted.push(self.decimal);
+            formatted.push_str(fraction);
+        }
+        Some(formatted)
+    }
+
+    /// Reformat the numbers in `text`, leaving everything else, including
+    /// whitespace, untouched
+    pub fn reformat(&self, text: &str) -> String {
+        word_pattern()
+            .replace_all(text, |captures: &regex::Captures| {
+                let word = &captures[0];
+                let core = word.trim_start_matches(LEADING);
+                let prefix = &word[..word.len() - core.len()];
+                let core = core.trim_end_matches(TRAILING);
+                let suffix = &word[prefix.len() + core.len()..];
+                match self.format(core) {
+                    Some(number) => format!("{}{}{}", prefix, number, suffix),
+                    None => word.to_string(),
+                }
+            })
+            .into_owned()
+    }
+}
+
+impl Transform for NumberLocale {
+    fn apply(&self, input: &str) -> Result<String> {
+        Ok(self.reformat(input))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reformat(locale: &str, text: &str) -> String {
+        locale.parse::<NumberLocale>().unwrap().reformat(text)
+    }
+
+    #[test]
+    fn test_german_swaps_marks() {
+        assert_eq!(
+            reformat("de-DE", "Total: 1,234,567.89 EUR\n-1,042.5\t12,345"),
+            "Total: 1.234.567,89 EUR\n-1.042,5\t12.345"
+        );
+    }
+
+    #[test]
+    fn test_french_uses_narrow_spaces() {
+        assert_eq!(
+            reformat("fr_FR.UTF-8", "(1,234,567.5)"),
+            "(1\u{202F}234\u{202F}567,5)"
+        );
+    }
+
+    #[test]
+    fn test_regional_variants() {
+        assert_eq!(reformat("de-CH", "1,234.5"), "1\u{2019}234.5");
+        assert_eq!(reformat("en-US", "1,234,567.25"), "1,234,567.25");
+        // Spanish doesn't group four-digit numbers
+        assert_eq!(reformat("es", "1,234.5 12,345.5"), "1234,5 12.345,5");
+    }
+
+    #[test]
+    fn test_punctuation_around_numbers_is_kept() {
+        assert_eq!(
+            reformat("de", "Costs $1,200.50, up (2,500). \"3,750\""),
+            "Costs $1.200,50, up (2.500). \"3.750\""
+        );
+    }
+
+    #[test]
+    fn test_code_like_tokens_are_untouched() {
+        let text = "v1.2.3 1.2.3 192.168.0.1 0x1F 2024-01-05 12:30 item_1000 \
+                    abc12345 1e10 id=123456 #12,345 0,012,345 1,23 1,2345 2024 1.2.3.";
+        assert_eq!(reformat("de", text), text);
+    }
+
+    #[test]
+    fn test_ungrouped_numbers_are_untouched() {
+        let text = "Beverly Hills 90210, order 123456, sleep 0.5; ratio 1234567.25 -42.5";
+        assert_eq!(reformat("de", text), text);
+        assert_eq!(reformat("fr", text), text);
+    }
+
+    #[test]
+    fn test_parse_locale() {
+        let locale: NumberLocale = "pt_BR".parse().unwrap();
+        assert_eq!(locale.to_string(), "pt_BR");
+        assert_eq!(locale.reformat("12,345.6"), "12.345,6");
+        let err = "xx-YY".parse::<NumberLocale>().unwrap_err();
+        assert!(err.contains("unknown locale 'xx-YY'"), "{}", err);
+    }
+
+    #[test]
+    fn test_as_transform() {
+        let transform: &dyn Transform = &"de".parse::<NumberLocale>().unwrap();
+        assert_eq!(transform.apply("1,000.5").unwrap(), "1.000,5");
+    }
+}
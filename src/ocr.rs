@@ -20,6 +20,11 @@ pub fn is_image_file(path: &Path) -> bool {
         .unwrap_or(false)
 }
 
+/// Check if leading file bytes match a known image signature
+pub fn is_image_header(header: &[u8]) -> bool {
+    crate::mime::image_mime(header).is_some()
+}
+
 /// Trait for OCR operations, allowing for mocking in tests
 pub trait OcrEngine: Send + Sync {
     /// Extract text from an image file
@@ -62,24 +67,22 @@ impl Default for SystemOcrEngine {
 impl OcrEngine for SystemOcrEngine {
     #[cfg(target_os = "macos")]
     fn extract_text(&self, path: &Path) -> Result<String> {
-        let helper = self
-            .helper_path
-            .as_ref()
-            .ok_or_else(|| CatboardError::ExtractionError {
-                path: path.to_path_buf(),
-                message: "OCR helper 'catboard-ocr' not found. Install it alongside catboard."
-                    .to_string(),
-            })?;
+        let helper = self.helper_path.as_ref().ok_or_else(|| {
+            CatboardError::extraction(
+                path,
+                "OCR helper 'catboard-ocr' not found. Install it alongside catboard.",
+            )
+        })?;
 
         run_ocr_helper(helper, path)
     }
 
     #[cfg(not(target_os = "macos"))]
     fn extract_text(&self, path: &Path) -> Result<String> {
-        Err(CatboardError::ExtractionError {
-            path: path.to_path_buf(),
-            message: "OCR is only supported on macOS".to_string(),
-        })
+        Err(CatboardError::extraction(
+            path,
+            "OCR is only supported on macOS",
+        ))
     }
 
     fn is_available(&self) -> bool {
@@ -98,27 +101,24 @@ impl OcrEngine for SystemOcrEngine {
 #[cfg(target_os = "macos")]
 fn run_ocr_helper(helper: &Path, image_path: &Path) -> Result<String> {
     let output = Command::new(helper).arg(image_path).output().map_err(|e| {
-        CatboardError::ExtractionError {
-            path: image_path.to_path_buf(),
-            message: format!("Failed to run OCR helper: {}", e),
-        }
+        CatboardError::extraction(image_path, format!("Failed to run OCR helper: {}", e))
     })?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(CatboardError::ExtractionError {
-            path: image_path.to_path_buf(),
-            message: format!("OCR failed: {}", stderr.trim()),
-        });
+        return Err(CatboardError::extraction(
+            image_path,
+            format!("OCR failed: {}", stderr.trim()),
+        ));
     }
 
     let text = String::from_utf8_lossy(&output.stdout).to_string();
 
     if text.trim().is_empty() {
-        return Err(CatboardError::ExtractionError {
-            path: image_path.to_path_buf(),
-            message: "Image contains no recognizable text".to_string(),
-        });
+        return Err(CatboardError::extraction(
+            image_path,
+            "Image contains no recognizable text",
+        ));
     }
 
     Ok(text)
@@ -234,14 +234,11 @@ pub mod mock {
             let responses = self.responses.lock().unwrap();
             match responses.get(path) {
                 Some(MockResponse::Text(text)) => Ok(text.clone()),
-                Some(MockResponse::Error(msg)) => Err(CatboardError::ExtractionError {
-                    path: path.to_path_buf(),
-                    message: msg.clone(),
-                }),
-                None => Err(CatboardError::ExtractionError {
-                    path: path.to_path_buf(),
-                    message: "No mock response configured for this path".to_string(),
-                }),
+                Some(MockResponse::Error(msg)) => Err(CatboardError::extraction(path, msg.clone())),
+                None => Err(CatboardError::extraction(
+                    path,
+                    "No mock response configured for this path",
+                )),
             }
         }
 
@@ -254,8 +251,6 @@ pub mod mock {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs::File;
-    use std::io::Write;
     use tempfile::TempDir;
 
     #[test]
@@ -302,6 +297,21 @@ mod tests {
         assert!(is_image_file(Path::new("../parent/photo.tiff")));
     }
 
+    #[test]
+    fn test_is_image_header() {
+        assert!(is_image_header(&[
+            0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A
+        ]));
+        assert!(is_image_header(&[0xFF, 0xD8, 0xFF, 0xE0]));
+        assert!(is_image_header(b"GIF89a"));
+        assert!(is_image_header(b"II*\0"));
+        assert!(is_image_header(b"RIFF\0\0\0\0WEBP"));
+        assert!(is_image_header(b"\0\0\0\x18ftypheic"));
+        assert!(!is_image_header(b"BMW owners manual"));
+        assert!(!is_image_header(b"%PDF-1.7"));
+        assert!(!is_image_header(b""));
+    }
+
     #[test]
     fn test_mock_ocr_engine_success() {
         let engine = mock::MockOcrEngine::new(true);
@@ -349,13 +359,13 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "ocr")]
     fn test_image_file_detection_in_read_file_contents() {
         let dir = TempDir::new().unwrap();
 
         // Create a fake image file (will fail OCR but tests routing)
         let png_path = dir.path().join("test.png");
-        let mut file = File::create(&png_path).unwrap();
-        file.write_all(b"fake png data").unwrap();
+        std::fs::write(&png_path, b"fake png data").unwrap();
 
         // On non-macOS, should get "OCR only supported on macOS"
         // On macOS without helper, should get "OCR helper not found"
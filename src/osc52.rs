@@ -0,0 +1,178 @@
+//! Terminal clipboard via the OSC 52 escape sequence.
+//!
+//! Over SSH the remote machine has no clipboard of its own, but most modern
+//! terminals accept `ESC ] 52 ; c ; <base64> BEL` and place the decoded text
+//! on the local clipboard. The sequence is write-only: the terminal is never
+//! asked to report the clipboard back.
+//!
+//! Inside tmux the sequence is wrapped in a DCS passthrough so it reaches
+//! the outer terminal (tmux needs `set -g allow-passthrough on`).
+
+use crate::clipboard::{Clipboard, Session};
+use crate::error::{CatboardError, Result};
+use base64::prelude::*;
+use std::io::Write;
+
+/// Largest encoded payload many terminals accept; longer sequences may be
+/// truncated or silently dropped
+pub const OSC52_MAX_ENCODED: usize = 100_000;
+
+/// The OSC 52 sequence that sets the clipboard to `text`
+pub fn osc52_sequence(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", BASE64_STANDARD.encode(text))
+}
+
+/// Wrap `sequence` so tmux forwards it to the outer terminal, doubling
+/// every ESC inside as the passthrough requires
+pub fn tmux_passthrough(sequence: &str) -> String {
+    format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+}
+
+/// Whether `text` is too large to copy reliably via OSC 52
+pub fn exceeds_osc52_limit(text: &str) -> bool {
+    // Base64 turns every 3 bytes (rounded up) into 4
+    (text.len() + 2) / 3 * 4 > OSC52_MAX_ENCODED
+}
+
+/// A write-only clipboard that emits OSC 52 sequences to a terminal
+pub struct Osc52Clipboard<W: Write> {
+    out: W,
+    tmux: bool,
+}
+
+impl<W: Write> Osc52Clipboard<W> {
+    pub fn new(out: W) -> Self {
+        Self { out, tmux: false }
+    }
+
+    /// Wrap sequences for tmux passthrough
+    pub fn with_tmux(mut self, tmux: bool) -> Self {
+        self.tmux = tmux;
+        self
+    }
+
+    /// The writer the sequences were sent to
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+
+    fn emit(&mut self, sequence: &str) -> Result<()> {
+        self.out
+            .write_all(sequence.as_bytes())
+            .and_then(|()| self.out.flush())
+            .map_err(|e| CatboardError::ClipboardError(format!("OSC 52 write failed: {}", e)))
+    }
+}
+
+impl Osc52Clipboard<Box<dyn Write>> {
+    /// Write to the controlling terminal, so redirected stdout and stderr
+    /// don't swallow the sequence. Uses tmux passthrough inside tmux.
+    pub fn terminal() -> Result<Self> {
+        let tmux = Session::detect().tmux;
+        Self::open_terminal().map(|clipboard| clipboard.with_tmux(tmux))
+    }
+
+    fn open_terminal() -> Result<Self> {
+        #[cfg(unix)]
+        {
+            let tty = std::fs::OpenOptions::new()
+                .write(true)
+                .open("/dev/tty")
+                .map_err(|e| {
+                    CatboardError::ClipboardError(format!("no terminal for OSC 52: {}", e))
+                })?;
+            Ok(Self::new(Box::new(tty)))
+        }
+        #[cfg(not(unix))]
+        {
+            Ok(Self::new(Box::new(std::io::stderr())))
+        }
+    }
+}
+
+impl<W: Write> Clipboard for Osc52Clipboard<W> {
+    fn set_text(&mut self, text: &str) -> Result<()> {
+        let sequence = osc52_sequence(text);
+        if self.tmux {
+            self.emit(&tmux_passthrough(&sequence))
+        } else {
+            self.emit(&sequence)
+        }
+    }
+
+    fn get_text(&mut self) -> Result<String> {
+        Err(CatboardError::ClipboardError(
+            "the OSC 52 clipboard cannot be read".to_string(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sequence_bytes() {
+        // "hello" is aGVsbG8= in base64
+        assert_eq!(osc52_sequence("hello"), "\x1b]52;c;aGVsbG8=\x07");
+        assert_eq!(
+            osc52_sequence("hello").as_bytes(),
+            b"\x1b]52;c;aGVsbG8=\x07"
+        );
+    }
+
+    #[test]
+    fn test_sequence_encodes_unicode_as_utf8() {
+        // U+00E9 is C3 A9 in UTF-8
+        assert_eq!(osc52_sequence("\u{E9}"), "\x1b]52;c;w6k=\x07");
+    }
+
+    #[test]
+    fn test_set_text_writes_sequence() {
+        let mut clipboard = Osc52Clipboard::new(Vec::new());
+        clipboard.set_text("hi\n").unwrap();
+        assert_eq!(clipboard.into_inner(), b"\x1b]52;c;aGkK\x07");
+    }
+
+    #[test]
+    fn test_clear_sends_empty_payload() {
+        let mut clipboard = Osc52Clipboard::new(Vec::new());
+        clipboard.clear().unwrap();
+        assert_eq!(clipboard.into_inner(), b"\x1b]52;c;\x07");
+    }
+
+    #[test]
+    fn test_get_text_is_unsupported() {
+        let mut clipboard = Osc52Clipboard::new(Vec::new());
+        assert!(matches!(
+            clipboard.get_text(),
+            Err(CatboardError::ClipboardError(_))
+        ));
+    }
+
+    #[test]
+    fn test_tmux_passthrough_bytes() {
+        assert_eq!(
+            tmux_passthrough(&osc52_sequence("hello")).as_bytes(),
+            b"\x1bPtmux;\x1b\x1b]52;c;aGVsbG8=\x07\x1b\\"
+        );
+    }
+
+    #[test]
+    fn test_set_text_in_tmux() {
+        let mut clipboard = Osc52Clipboard::new(Vec::new()).with_tmux(true);
+        clipboard.set_text("hi\n").unwrap();
+        assert_eq!(
+            clipboard.into_inner(),
+            b"\x1bPtmux;\x1b\x1b]52;c;aGkK\x07\x1b\\"
+        );
+    }
+
+    #[test]
+    fn test_size_limit() {
+        // 75,000 bytes encode to exactly 100,000
+        assert!(!exceeds_osc52_limit(&"a".repeat(75_000)));
+        assert!(exceeds_osc52_limit(&"a".repeat(75_001)));
+        assert!(!exceeds_osc52_limit(""));
+    }
+}
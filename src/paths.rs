@@ -0,0 +1,167 @@
+//! Path helpers for copying file paths or names instead of file contents,
+//! and for backing up files before they are overwritten.
+
+use crate::error::{CatboardError, Result, ResultExt};
+use std::path::{Component, Path, PathBuf};
+
+/// Resolve `path` to an absolute path with symlinks and `..` removed
+pub fn canonical_path(path: &Path) -> Result<PathBuf> {
+    path.canonicalize().with_path(path)
+}
+
+/// Express absolute `path` relative to absolute `base`, walking up with `..`
+/// where needed. Both should already be canonical.
+pub fn relative_path(path: &Path, base: &Path) -> PathBuf {
+    let path_components: Vec<Component> = path.components().collect();
+    let base_components: Vec<Component> = base.components().collect();
+    let common = path_components
+        .iter()
+        .zip(&base_components)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let relative: PathBuf = std::iter::repeat(Component::ParentDir)
+        .take(base_components.len() - common)
+        .chain(path_components[common..].iter().copied())
+        .collect();
+
+    if relative.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        relative
+    }
+}
+
+/// The final component of `path`, or its stem (name without the last
+/// extension) when `stem` is set.
+///
+/// `path` must exist. Paths without a final component, such as `.` or
+/// `..`, are named after the directory they resolve to.
+pub fn file_name(path: &Path, stem: bool) -> Result<String> {
+    if !path.exists() {
+        return Err(CatboardError::FileNotFound(path.to_path_buf()));
+    }
+    let resolved;
+    let named = if path.file_name().is_some() {
+        path
+    } else {
+        resolved = canonical_path(path)?;
+        &resolved
+    };
+    let name = if stem {
+        named.file_stem()
+    } else {
+        named.file_name()
+    };
+    // Only the filesystem root has no name at all
+    Ok(name
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| named.display().to_string()))
+}
+
+/// Where `--backup` moves an existing file: its name with `.bak` appended
+pub fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".bak");
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_canonical_path_resolves_dot_dot() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+
+        let indirect = dir.path().join("sub").join("..").join("a.txt");
+        let canonical = canonical_path(&indirect).unwrap();
+        assert!(canonical.is_absolute());
+        assert_eq!(canonical, dir.path().canonicalize().unwrap().join("a.txt"));
+    }
+
+    #[test]
+    fn test_canonical_path_missing_file() {
+        let result = canonical_path(Path::new("/nonexistent/file.txt"));
+        assert!(matches!(result, Err(CatboardError::FileNotFound(_))));
+    }
+
+    #[test]
+    fn test_relative_path_below_base() {
+        assert_eq!(
+            relative_path(Path::new("/home/me/src/main.rs"), Path::new("/home/me")),
+            PathBuf::from("src/main.rs")
+        );
+    }
+
+    #[test]
+    fn test_relative_path_outside_base() {
+        assert_eq!(
+            relative_path(
+                Path::new("/home/me/notes.txt"),
+                Path::new("/home/me/src/app")
+            ),
+            PathBuf::from("../../notes.txt")
+        );
+        assert_eq!(
+            relative_path(Path::new("/etc/hosts"), Path::new("/home/me")),
+            PathBuf::from("../../etc/hosts")
+        );
+    }
+
+    #[test]
+    fn test_relative_path_same_as_base() {
+        assert_eq!(
+            relative_path(Path::new("/home/me"), Path::new("/home/me")),
+            PathBuf::from(".")
+        );
+    }
+
+    #[test]
+    fn test_file_name_and_stem() {
+        let dir = TempDir::new().unwrap();
+        for name in ["archive.tar.gz", "Makefile", ".bashrc", "notes.txt"] {
+            std::fs::write(dir.path().join(name), "").unwrap();
+        }
+        let name = |n: &str, stem| file_name(&dir.path().join(n), stem).unwrap();
+
+        assert_eq!(name("archive.tar.gz", false), "archive.tar.gz");
+        assert_eq!(name("archive.tar.gz", true), "archive.tar");
+        assert_eq!(name("Makefile", false), "Makefile");
+        assert_eq!(name("Makefile", true), "Makefile");
+        assert_eq!(name(".bashrc", true), ".bashrc");
+        assert_eq!(name("notes.txt", true), "notes");
+    }
+
+    #[test]
+    fn test_file_name_of_dot_uses_directory_name() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("project.d")).unwrap();
+        let dot = dir.path().join("project.d").join(".");
+        assert_eq!(file_name(&dot, false).unwrap(), "project.d");
+        assert_eq!(file_name(&dot, true).unwrap(), "project");
+    }
+
+    #[test]
+    fn test_backup_path() {
+        assert_eq!(
+            backup_path(Path::new("shots/out.png")),
+            PathBuf::from("shots/out.png.bak")
+        );
+        assert_eq!(
+            backup_path(Path::new("Makefile")),
+            PathBuf::from("Makefile.bak")
+        );
+    }
+
+    #[test]
+    fn test_file_name_missing_file() {
+        assert!(matches!(
+            file_name(Path::new("/nonexistent/file.txt"), false),
+            Err(CatboardError::FileNotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,520 @@
+//! PDF text extraction, compiled with the `pdf` feature.
+//!
+//! Each backend opens a PDF behind [`PdfPages`]. The backend chosen in
+//! [`ReadOptions::pdf_backend`] is tried first; if it can't open the file,
+//! fails every page or finds no text, the next one is tried.
+
+mod basic;
+mod decrypt;
+mod images;
+mod layout;
+
+use crate::cancel;
+use crate::content::{ExtractedContent, FieldValue, FormField, PageError, PdfBackend, SourceKind};
+use crate::error::{CatboardError, Result};
+use crate::file::ReadOptions;
+use crate::ocr;
+use pdf_oxide::extractors::forms;
+use pdf_oxide::PdfDocument;
+use std::path::{Path, PathBuf};
+
+/// Page-by-page text of an open PDF
+trait PdfPages {
+    fn page_count(&self) -> usize;
+
+    /// Text of the page at 0-based `index`, or why it couldn't be read
+    fn page_text(&mut self, index: usize) -> std::result::Result<String, String>;
+
+    /// Positioned text of the page at `index` for `--pdf-layout`, or `None`
+    /// if the backend can't place text
+    fn page_spans(
+        &mut self,
+        _index: usize,
+    ) -> Option<std::result::Result<Vec<layout::Span>, String>> {
+        None
+    }
+}
+
+struct OxidePages {
+    doc: PdfDocument,
+    page_count: usize,
+}
+
+impl OxidePages {
+    fn open(path: &Path) -> std::result::Result<Self, String> {
+        let mut doc = PdfDocument::open(path).map_err(|e| e.to_string())?;
+        let page_count = doc.page_count().map_err(|e| e.to_string())?;
+        Ok(Self { doc, page_count })
+    }
+}
+
+impl PdfPages for OxidePages {
+    fn page_count(&self) -> usize {
+        self.page_count
+    }
+
+    fn page_text(&mut self, index: usize) -> std::result::Result<String, String> {
+        self.doc.extract_text(index).map_err(|e| e.to_string())
+    }
+
+    fn page_spans(
+        &mut self,
+        index: usize,
+    ) -> Option<std::result::Result<Vec<layout::Span>, String>> {
+        let spans = self.doc.extract_spans(index).map_err(|e| e.to_string());
+        Some(spans.map(|spans| {
+            spans
+                .into_iter()
+                .map(|span| layout::Span {
+                    text: span.text,
+                    x: span.bbox.x,
+                    y: span.bbox.y,
+                    width: span.bbox.width,
+                    height: span.bbox.height,
+                })
+                .collect()
+        }))
+    }
+}
+
+/// Text of the page at `index`, laid out if `layout` is set and the backend
+/// can do it. The flag returned says whether it was laid out.
+fn read_page(
+    doc: &mut dyn PdfPages,
+    index: usize,
+    layout: bool,
+) -> std::result::Result<(String, bool), String> {
+    if layout {
+        if let Some(spans) = doc.page_spans(index) {
+            return spans.map(|spans| (layout::render(&spans), true));
+        }
+    }
+    doc.page_text(index).map(|text| (text, false))
+}
+
+fn open_pdf(backend: PdfBackend, path: &Path, options: &ReadOptions) -> Result<Box<dyn PdfPages>> {
+    Ok(match backend {
+        PdfBackend::Oxide => {
+            Box::new(OxidePages::open(path).map_err(|e| CatboardError::extraction(path, e))?)
+        }
+        PdfBackend::Basic => Box::new(basic::BasicPages::open(
+            path,
+            options.pdf_password.as_deref(),
+        )?),
+    })
+}
+
+/// What one backend made of a PDF
+enum Extraction {
+    Text(ExtractedContent),
+    /// Opened, but no page held any text
+    NoText {
+        page_count: usize,
+    },
+}
+
+/// Extract text from a PDF file.
+///
+/// First attempts to extract embedded text, trying each backend in turn.
+/// If the PDF appears to be scanned (no backend finds text), falls back to
+/// OCR on macOS. When every backend fails, the first one's error is
+/// returned.
+pub(crate) fn extract_pdf_text(
+    path: &Path,
+    options: &ReadOptions,
+    mut progress: Option<&mut dyn FnMut(usize, usize)>,
+) -> Result<ExtractedContent> {
+    let mut first_error = None;
+    let mut blank_pages = None;
+    for backend in options.pdf_backend.fallback_order() {
+        match extract_with(backend, path, options, &mut progress) {
+            Ok(Extraction::Text(mut content)) => {
+                content.pdf_backend = Some(backend);
+                return Ok(content);
+            }
+            Ok(Extraction::NoText { page_count }) => {
+                blank_pages.get_or_insert(page_count);
+            }
+            // Only the basic backend can decrypt, so its verdict on the
+            // password is final
+            Err(
+                e @ (CatboardError::Cancelled(_)
+                | CatboardError::PasswordRequired(_)
+                | CatboardError::WrongPassword(_)),
+            ) => return Err(e),
+            Err(e) => {
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+
+    let Some(page_count) = blank_pages else {
+        return Err(first_error.expect("at least one backend is tried"));
+    };
+
+    if !options.ocr_fallback {
+        return Err(CatboardError::extraction(
+            path,
+            "PDF contains no extractable text (OCR fallback disabled)",
+        ));
+    }
+
+    // No text found - try OCR if available (scanned PDF)
+    if cfg!(feature = "ocr") && ocr::is_ocr_available() {
+        cancel::check(options.cancel.as_ref(), || {
+            format!("extracting text from '{}' before OCR", path.display())
+        })?;
+        return extract_pdf_with_ocr(path, options, page_count);
+    }
+
+    Err(CatboardError::extraction(
+        path,
+        "PDF contains no extractable text",
+    ))
+}
+
+/// Extract the embedded text of a PDF with one backend
+fn extract_with(
+    backend: PdfBackend,
+    path: &Path,
+    options: &ReadOptions,
+    progress: &mut Option<&mut dyn FnMut(usize, usize)>,
+) -> Result<Extraction> {
+    let mut doc = open_pdf(backend, path, options)?;
+
+    let page_count = doc.page_count();
+    let mut all_text = String::new();
+    let mut failed_pages = Vec::new();
+    let mut extracted_any = false;
+    let mut laid_out = options.pdf_layout;
+
+    for page_num in 0..page_count {
+        cancel::check(options.cancel.as_ref(), || {
+            format!(
+                "extracting text from '{}' before page {} of {}",
+                path.display(),
+                page_num + 1,
+                page_count
+            )
+        })?;
+        match read_page(doc.as_mut(), page_num, options.pdf_layout) {
+            Ok((text, page_laid_out)) => {
+                laid_out &= page_laid_out;
+                if extracted_any {
+                    all_text.push_str(&page_separator(&options.page_separator, page_num + 1));
+                }
+                all_text.push_str(&text);
+                extracted_any = true;
+            }
+            Err(e) if options.strict_pages => {
+                return Err(CatboardError::extraction(
+                    path,
+                    format!("Failed to extract page {}: {}", page_num + 1, e),
+                ));
+            }
+            Err(e) => failed_pages.push(PageError {
+                page: page_num + 1,
+                message: e,
+            }),
+        }
+        if let Some(report) = progress.as_mut() {
+            report(page_num + 1, page_count);
+        }
+    }
+
+    // If we got text, return it along with any pages that were skipped
+    if !all_text.trim().is_empty() {
+        let mut content =
+            ExtractedContent::extracted(all_text, SourceKind::PdfText, Some(page_count));
+        content.failed_pages = failed_pages;
+        content.layout = laid_out;
+        return Ok(Extraction::Text(content));
+    }
+
+    // Every page failed, so there is nothing worth returning or OCRing
+    if !extracted_any && !failed_pages.is_empty() {
+        let details: Vec<String> = failed_pages.iter().map(ToString::to_string).collect();
+        return Err(CatboardError::extraction(
+            path,
+            format!("No pages could be extracted ({})", details.join("; ")),
+        ));
+    }
+
+    Ok(Extraction::NoText { page_count })
+}
+
+/// Read the values filled in to a PDF's form fields, in document order.
+///
+/// pdf_oxide reads the form whatever the chosen backend, unless the file
+/// needs a password; only the basic backend can decrypt those.
+pub(crate) fn extract_pdf_fields(path: &Path, password: Option<&str>) -> Result<Vec<FormField>> {
+    let fields = match basic::BasicPages::open(path, None) {
+        Err(CatboardError::PasswordRequired(_)) => {
+            basic::BasicPages::open(path, password)?.form_fields()
+        }
+        _ => oxide_form_fields(path)?,
+    };
+    if fields.is_empty() {
+        return Err(CatboardError::NoFormFields(path.to_path_buf()));
+    }
+    Ok(fields)
+}
+
+fn oxide_form_fields(path: &Path) -> Result<Vec<FormField>> {
+    let mut doc =
+        PdfDocument::open(path).map_err(|e| CatboardError::extraction(path, e.to_string()))?;
+    let fields = forms::FormExtractor::extract_fields(&mut doc)
+        .map_err(|e| CatboardError::extraction(path, e.to_string()))?;
+
+    Ok(fields
+        .into_iter()
+        .map(|field| FormField {
+            name: field.full_name,
+            value: match field.value {
+                forms::FieldValue::Text(text) | forms::FieldValue::Name(text) => {
+                    FieldValue::Text(form_text(&text))
+                }
+                forms::FieldValue::Boolean(checked) => FieldValue::Checked(checked),
+                forms::FieldValue::Array(choices) => FieldValue::Choices(choices),
+                forms::FieldValue::None => FieldValue::Empty,
+            },
+        })
+        .collect())
+}
+
+/// A form field's text with line breaks as `\n`; multi-line text fields end
+/// lines with CR
+fn form_text(text: &str) -> String {
+    text.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+/// Expand a page separator template for the page that follows it
+fn page_separator(template: &str, page_number: usize) -> String {
+    template.replace("{page}", &page_number.to_string())
+}
+
+/// Extract text from a scanned PDF using OCR.
+///
+/// The images embedded in each page are OCR'd directly when there are any,
+/// since a scanner usually stores one image per page. Otherwise, or if
+/// they hold no text, whole pages are rendered and OCR'd instead.
+fn extract_pdf_with_ocr(
+    path: &Path,
+    options: &ReadOptions,
+    page_count: usize,
+) -> Result<ExtractedContent> {
+    let engine = ocr::SystemOcrEngine::new();
+    match ocr_embedded_images(path, options, &engine) {
+        Ok(Some(content)) => Ok(content),
+        Err(e @ CatboardError::Cancelled(_)) => Err(e),
+        // Rendering may still work where pdf_oxide can't reach the images
+        Ok(None) | Err(_) => render_pdf_with_ocr(path)
+            .map(|text| ExtractedContent::extracted(text, SourceKind::PdfOcr, Some(page_count))),
+    }
+}
+
+/// OCR the image XObjects embedded in each page of a PDF.
+///
+/// Returns `Ok(None)` when there are no images or none of them hold
+/// recognizable text.
+fn ocr_embedded_images(
+    path: &Path,
+    options: &ReadOptions,
+    engine: &dyn ocr::OcrEngine,
+) -> Result<Option<ExtractedContent>> {
+    let dir = tempfile::Builder::new()
+        .prefix("catboard-ocr-")
+        .tempdir()
+        .map_err(|e| CatboardError::io(std::env::temp_dir(), e))?;
+    let pages = images::save_page_images(path, dir.path())?;
+
+    let (text, failed_pages) = ocr_page_images(path, &pages, options, engine)?;
+    if text.is_empty() {
+        return Ok(None);
+    }
+    let mut content = ExtractedContent::extracted(text, SourceKind::PdfOcr, Some(pages.len()));
+    content.failed_pages = failed_pages;
+    Ok(Some(content))
+}
+
+/// OCR the images saved from each page, joining pages with the same
+/// `--- Page N ---` markers as the catboard-ocr helper. Every page with
+/// text after the first gets its marker, so blank pages before it don't
+/// shift the numbering. Images that fail are returned as page errors for
+/// `--verbose` rather than failing the page.
+fn ocr_page_images(
+    path: &Path,
+    pages: &[Vec<PathBuf>],
+    options: &ReadOptions,
+    engine: &dyn ocr::OcrEngine,
+) -> Result<(String, Vec<PageError>)> {
+    let mut text = String::new();
+    let mut failed_pages = Vec::new();
+    for (index, images) in pages.iter().enumerate() {
+        cancel::check(options.cancel.as_ref(), || {
+            format!(
+                "OCRing '{}' before page {} of {}",
+                path.display(),
+                index + 1,
+                pages.len()
+            )
+        })?;
+        // An image without text, such as a logo, shouldn't sink the page
+        let mut page_text = Vec::new();
+        for (number, image) in images.iter().enumerate() {
+            match engine.extract_text(image) {
+                Ok(found) if !found.trim().is_empty() => page_text.push(found.trim().to_string()),
+                Ok(_) => {}
+                Err(e) => failed_pages.push(PageError {
+                    page: index + 1,
+                    message: format!("OCR of image {} failed: {}", number + 1, e),
+                }),
+            }
+        }
+        if page_text.is_empty() {
+            continue;
+        }
+        if index > 0 {
+            if !text.is_empty() {
+                text.push_str("\n\n");
+            }
+            text.push_str(&format!("--- Page {} ---\n\n", index + 1));
+        }
+        text.push_str(&page_text.join("\n"));
+    }
+    Ok((text, failed_pages))
+}
+
+/// Render each page of a scanned PDF and OCR it.
+///
+/// Uses macOS Vision framework via catboard-ocr helper.
+/// The OCR helper uses PDFKit to iterate through all pages, render each
+/// at 150 DPI, and run Vision OCR on each page sequentially.
+#[cfg(target_os = "macos")]
+fn render_pdf_with_ocr(path: &Path) -> Result<String> {
+    // catboard-ocr uses PDFKit for multi-page PDF support.
+    // Each page is rendered and OCR'd sequentially, with page separators
+    // added between pages (e.g., "--- Page 2 ---").
+    let text = ocr::extract_text_from_image(path)?;
+
+    if text.trim().is_empty() {
+        return Err(CatboardError::extraction(
+            path,
+            "PDF contains no recognizable text (OCR found nothing)",
+        ));
+    }
+
+    Ok(text)
+}
+
+/// Stub for non-macOS platforms - OCR not available
+#[cfg(not(target_os = "macos"))]
+fn render_pdf_with_ocr(path: &Path) -> Result<String> {
+    Err(CatboardError::extraction(
+        path,
+        "PDF contains no extractable text (OCR only available on macOS)",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_separator_expands_page_number() {
+        assert_eq!(page_separator("\n", 2), "\n");
+        assert_eq!(
+            page_separator("\n--- page {page} ---\n", 3),
+            "\n--- page 3 ---\n"
+        );
+    }
+
+    #[test]
+    fn test_backends_differ_on_damaged_trailer() {
+        let path = Path::new("tests/no-root.pdf");
+        assert!(open_pdf(PdfBackend::Oxide, path, &ReadOptions::default()).is_err());
+        let options = ReadOptions::default();
+        let Extraction::Text(content) =
+            extract_with(PdfBackend::Basic, path, &options, &mut None).unwrap()
+        else {
+            panic!("expected text");
+        };
+        assert_eq!(content.pages, Some(3));
+        assert!(content.text.starts_with("First page text"));
+    }
+
+    /// Reads back the name of each image it's given
+    struct FileNameOcr;
+
+    impl ocr::OcrEngine for FileNameOcr {
+        fn extract_text(&self, path: &Path) -> Result<String> {
+            Ok(format!(
+                "text of {}",
+                path.file_name().unwrap().to_string_lossy()
+            ))
+        }
+
+        fn is_available(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn test_ocr_embedded_images() {
+        let options = ReadOptions::default();
+        let text = ocr_embedded_images(
+            Path::new("tests/2025-12-12_12-11-14.pdf"),
+            &options,
+            &FileNameOcr,
+        )
+        .unwrap()
+        .map(|content| content.text);
+        assert_eq!(text.as_deref(), Some("text of page1_001.png"));
+
+        // No images, so the caller falls back to rendering pages
+        let text = ocr_embedded_images(Path::new("tests/three-pages.pdf"), &options, &FileNameOcr)
+            .unwrap();
+        assert_eq!(text, None);
+    }
+
+    #[test]
+    fn test_ocr_embedded_images_skips_images_without_text() {
+        let engine = ocr::mock::MockOcrEngine::new(true);
+        let text = ocr_embedded_images(
+            Path::new("tests/2025-12-12_12-11-14.pdf"),
+            &ReadOptions::default(),
+            &engine,
+        )
+        .unwrap();
+        assert_eq!(text, None);
+    }
+
+    #[test]
+    fn test_ocr_page_markers_and_failures() {
+        let engine = ocr::mock::MockOcrEngine::new(true);
+        engine.set_text(PathBuf::from("p2a.png"), "second");
+        engine.set_error(PathBuf::from("p2b.png"), "no text found");
+        engine.set_text(PathBuf::from("p4.png"), "fourth");
+        let pages = vec![
+            vec![PathBuf::from("p1.png")],
+            vec![PathBuf::from("p2a.png"), PathBuf::from("p2b.png")],
+            vec![],
+            vec![PathBuf::from("p4.png")],
+        ];
+
+        let (text, failed) = ocr_page_images(
+            Path::new("scan.pdf"),
+            &pages,
+            &ReadOptions::default(),
+            &engine,
+        )
+        .unwrap();
+        // Page 1 held no text, but page 2 is still marked as page 2
+        assert_eq!(text, "--- Page 2 ---\n\nsecond\n\n--- Page 4 ---\n\nfourth");
+        let failed: Vec<(usize, bool)> = failed
+            .iter()
+            .map(|f| (f.page, f.message.starts_with("OCR of image")))
+            .collect();
+        assert_eq!(failed, [(1, true), (2, true)]);
+    }
+}
@@ -0,0 +1,978 @@
+//! The `basic` PDF backend: a small scanner with no dependencies beyond
+//! flate2, plus pdf_oxide's security handler for encrypted files.
+//!
+//! Objects are found by scanning the file for `N G obj` headers rather than
+//! through the cross-reference table and trailer, so files whose xref or
+//! trailer is damaged can still be read. Only uncompressed and FlateDecode
+//! content streams are supported, objects inside object streams are not
+//! seen, text comes from the `Tj`, `TJ`, `'` and `"` operators, and strings
+//! are decoded as Latin-1 (or UTF-16 with a byte order mark). It suits
+//! simple documents as a fallback rather than replacing a full parser.
+//!
+//! Most PDF 1.5 and later files keep their page objects in object streams,
+//! so this backend can't open them. Decoded streams are capped at
+//! [`MAX_STREAM_LEN`] and the page tree at [`MAX_TREE_DEPTH`] levels, so a
+//! hostile file can't exhaust memory or the stack.
+//!
+//! Unlike pdf_oxide, it can open files encrypted with a user password; see
+//! the `decrypt` module. For the same reason it also reads the form fields
+//! of such files for `--pdf-fields`.
+
+use super::decrypt::{Decryptor, UnlockError};
+use super::{form_text, PdfPages};
+use crate::content::{FieldValue, FormField};
+use crate::error::{CatboardError, Result};
+use flate2::read::ZlibDecoder;
+use regex::bytes::Regex;
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Kerning in a `TJ` array, in thousandths of an em, wide enough to count
+/// as a space between words
+const TJ_SPACE: f64 = -200.0;
+
+/// Largest stream inflated, in bytes
+const MAX_STREAM_LEN: usize = 64 * 1024 * 1024;
+
+/// Deepest page tree walked
+const MAX_TREE_DEPTH: usize = 32;
+
+fn regex(cell: &'static OnceLock<Regex>, pattern: &str) -> &'static Regex {
+    cell.get_or_init(|| Regex::new(pattern).expect("valid regex"))
+}
+
+fn object_header() -> &'static Regex {
+    static CELL: OnceLock<Regex> = OnceLock::new();
+    regex(&CELL, r"(?-u)(?:^|[^0-9])(\d+)\s+(\d+)\s+obj\b")
+}
+
+fn reference() -> &'static Regex {
+    static CELL: OnceLock<Regex> = OnceLock::new();
+    regex(&CELL, r"(?-u)(\d+)\s+\d+\s+R\b")
+}
+
+fn encrypt_entry() -> &'static Regex {
+    static CELL: OnceLock<Regex> = OnceLock::new();
+    regex(&CELL, r"(?-u)/Encrypt\s+(\d+)\s+\d+\s+R\b")
+}
+
+fn page_type() -> &'static Regex {
+    static CELL: OnceLock<Regex> = OnceLock::new();
+    regex(&CELL, r"(?-u)/Type\s*/Page\b")
+}
+
+fn pages_type() -> &'static Regex {
+    static CELL: OnceLock<Regex> = OnceLock::new();
+    regex(&CELL, r"(?-u)/Type\s*/Pages\b")
+}
+
+fn catalog_type() -> &'static Regex {
+    static CELL: OnceLock<Regex> = OnceLock::new();
+    regex(&CELL, r"(?-u)/Type\s*/Catalog\b")
+}
+
+/// The refs in `dict` under `key`, whether a single ref or an array of them
+fn refs_under(dict: &[u8], key: &str) -> Vec<u32> {
+    let Some(start) = find(dict, key.as_bytes()).map(|at| at + key.len()) else {
+        return Vec::new();
+    };
+    let rest = &dict[start..];
+    let rest = trim_start(rest);
+    let value = if rest.first() == Some(&b'[') {
+        let end = rest.iter().position(|&b| b == b']').unwrap_or(rest.len());
+        &rest[..end]
+    } else {
+        // A single `N G R`
+        let end = rest
+            .iter()
+            .position(|&b| b == b'R')
+            .map_or(rest.len(), |at| at + 1);
+        &rest[..end]
+    };
+    reference()
+        .captures_iter(value)
+        .filter_map(|c| parse_u32(&c[1]))
+        .collect()
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+fn rfind(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .rposition(|window| window == needle)
+}
+
+fn trim_start(bytes: &[u8]) -> &[u8] {
+    let start = bytes
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(bytes.len());
+    &bytes[start..]
+}
+
+fn parse_u32(bytes: &[u8]) -> Option<u32> {
+    std::str::from_utf8(bytes).ok()?.parse().ok()
+}
+
+/// Where an object's body is in the file
+struct ObjectSpan {
+    generation: u32,
+    body: Range<usize>,
+}
+
+/// A PDF's objects, found by scanning, and its pages in reading order
+pub(super) struct BasicPages {
+    data: Vec<u8>,
+    /// Object number to its span; later definitions win, as in an
+    /// incremental update
+    objects: HashMap<u32, ObjectSpan>,
+    pages: Vec<u32>,
+    /// Set for an encrypted file once it's unlocked
+    decryptor: Option<Decryptor>,
+}
+
+impl BasicPages {
+    /// Open the PDF at `path`, unlocking it with `password` if it's
+    /// encrypted
+    ///
+    /// # Errors
+    /// - `PasswordRequired` or `WrongPassword` if the file is encrypted and
+    ///   the password is missing or wrong
+    /// - `ExtractionError` if the file can't be read or holds no pages
+    pub(super) fn open(path: &Path, password: Option<&str>) -> Result<Self> {
+        let data =
+            std::fs::read(path).map_err(|e| CatboardError::extraction(path, e.to_string()))?;
+        let mut pages = Self::parse(data).map_err(|e| CatboardError::extraction(path, e))?;
+        pages.decryptor = pages.unlock(password).map_err(|e| e.into_error(path))?;
+        Ok(pages)
+    }
+
+    fn parse(data: Vec<u8>) -> std::result::Result<Self, String> {
+        if find(&data[..data.len().min(1024)], b"%PDF-").is_none() {
+            return Err("not a PDF file".to_string());
+        }
+
+        // Object number, generation and where the body starts
+        let headers: Vec<(u32, u32, usize)> = object_header()
+            .captures_iter(&data)
+            .filter_map(|c| {
+                let number = parse_u32(&c[1])?;
+                let generation = parse_u32(&c[2])?;
+                Some((number, generation, c.get(0)?.end()))
+            })
+            .collect();
+        let mut objects = HashMap::new();
+        for (i, &(number, generation, start)) in headers.iter().enumerate() {
+            let next = headers.get(i + 1).map_or(data.len(), |&(_, _, at)| at);
+            let end = rfind(&data[start..next], b"endobj").map_or(next, |at| start + at);
+            objects.insert(
+                number,
+                ObjectSpan {
+                    generation,
+                    body: start..end,
+                },
+            );
+        }
+
+        let mut pages = Self {
+            data,
+            objects,
+            pages: Vec::new(),
+            decryptor: None,
+        };
+        pages.pages = pages.page_order();
+        if pages.pages.is_empty() {
+            return Err("no pages found".to_string());
+        }
+        Ok(pages)
+    }
+
+    /// The dictionary part of object `number`, before any stream data
+    fn dict(&self, number: u32) -> Option<&[u8]> {
+        let body = &self.data[self.objects.get(&number)?.body.clone()];
+        Some(find(body, b"stream").map_or(body, |at| &body[..at]))
+    }
+
+    /// A decryptor for the file if the trailer names an `/Encrypt`
+    /// dictionary, or `None` if it isn't encrypted
+    fn unlock(
+        &self,
+        password: Option<&str>,
+    ) -> std::result::Result<Option<Decryptor>, UnlockError> {
+        // The last trailer (or cross-reference stream) is the current one
+        let Some(entry) = encrypt_entry().captures_iter(&self.data).last() else {
+            return Ok(None);
+        };
+        let dict = parse_u32(&entry[1])
+            .and_then(|number| self.dict(number))
+            .ok_or_else(|| {
+                UnlockError::Unsupported("the /Encrypt dictionary is missing".to_string())
+            })?;
+        Decryptor::unlock(dict, self.file_id(), password).map(Some)
+    }
+
+    /// The first string of the trailer's `/ID`, which keys encryption
+    fn file_id(&self) -> Vec<u8> {
+        let Some(at) = rfind(&self.data, b"/ID") else {
+            return Vec::new();
+        };
+        let rest = trim_start(&self.data[at + b"/ID".len()..]);
+        let Some(array) = rest.strip_prefix(b"[") else {
+            return Vec::new();
+        };
+        let mut lexer = Lexer {
+            data: array,
+            pos: 0,
+        };
+        match lexer.next_token() {
+            Some(Token::Operand(Operand::Text(id))) => id,
+            _ => Vec::new(),
+        }
+    }
+
+    /// Pages in the order of the page tree, or in file order if the tree
+    /// can't be followed
+    fn page_order(&self) -> Vec<u32> {
+        let is_page = |number: u32| self.dict(number).is_some_and(|d| page_type().is_match(d));
+
+        let root = self.objects.keys().copied().find(|&number| {
+            self.dict(number)
+                .is_some_and(|d| pages_type().is_match(d) && find(d, b"/Parent").is_none())
+        });
+        let mut ordered = Vec::new();
+        if let Some(root) = root {
+            let mut seen = HashSet::new();
+            self.collect_pages(root, 0, &mut ordered, &mut seen);
+        }
+        if !ordered.is_empty() {
+            return ordered;
+        }
+
+        let mut by_position: Vec<u32> = self
+            .objects
+            .keys()
+            .copied()
+            .filter(|&number| is_page(number))
+            .collect();
+        by_position.sort_by_key(|number| self.objects[number].body.start);
+        by_position
+    }
+
+    fn collect_pages(
+        &self,
+        node: u32,
+        depth: usize,
+        pages: &mut Vec<u32>,
+        seen: &mut HashSet<u32>,
+    ) {
+        if depth > MAX_TREE_DEPTH || !seen.insert(node) {
+            return;
+        }
+        let Some(dict) = self.dict(node) else {
+            return;
+        };
+        if pages_type().is_match(dict) {
+            for kid in refs_under(dict, "/Kids") {
+                self.collect_pages(kid, depth + 1, pages, seen);
+            }
+        } else if page_type().is_match(dict) {
+            pages.push(node);
+        }
+    }
+
+    /// The decoded data of stream object `number`
+    fn stream(&self, number: u32) -> std::result::Result<Vec<u8>, String> {
+        let span = self
+            .objects
+            .get(&number)
+            .ok_or_else(|| format!("content object {} is missing", number))?;
+        let body = &self.data[span.body.clone()];
+        let start =
+            find(body, b"stream").ok_or_else(|| format!("object {} has no stream", number))?;
+        let dict = &body[..start];
+        let mut data = &body[start + b"stream".len()..];
+        data = data.strip_prefix(b"\r").unwrap_or(data);
+        data = data.strip_prefix(b"\n").unwrap_or(data);
+        let end = rfind(data, b"endstream").unwrap_or(data.len());
+        let decrypted;
+        let mut data = &data[..end];
+        if let Some(decryptor) = &self.decryptor {
+            decrypted = decryptor.decrypt(data, number, span.generation)?;
+            data = &decrypted;
+        }
+
+        if find(dict, b"/FlateDecode").is_some() {
+            let mut decoded = Vec::new();
+            ZlibDecoder::new(data)
+                .take(MAX_STREAM_LEN as u64 + 1)
+                .read_to_end(&mut decoded)
+                .map_err(|e| format!("could not inflate object {}: {}", number, e))?;
+            if decoded.len() > MAX_STREAM_LEN {
+                return Err(format!(
+                    "object {} inflates past {} MiB",
+                    number,
+                    MAX_STREAM_LEN >> 20
+                ));
+            }
+            Ok(decoded)
+        } else if find(dict, b"/Filter").is_some() {
+            Err(format!("object {} uses an unsupported filter", number))
+        } else {
+            Ok(data.to_vec())
+        }
+    }
+
+    /// The fields of the document's form in document order, with parent
+    /// names joined by `.`; empty if it has no form
+    pub(super) fn form_fields(&self) -> Vec<FormField> {
+        let catalog = self.objects.keys().copied().find(|&number| {
+            self.dict(number)
+                .is_some_and(|d| catalog_type().is_match(d))
+        });
+        let Some(catalog) = catalog.and_then(|number| self.dict(number)) else {
+            return Vec::new();
+        };
+        // /AcroForm is either a reference or written inline
+        let form = match find(catalog, b"/AcroForm").map(|at| trim_start(&catalog[at + 9..])) {
+            Some(rest) if rest.starts_with(b"<<") => rest,
+            Some(_) => refs_under(catalog, "/AcroForm")
+                .first()
+                .and_then(|&number| self.dict(number))
+                .unwrap_or_default(),
+            None => return Vec::new(),
+        };
+
+        let mut fields = Vec::new();
+        let mut seen = HashSet::new();
+        for field in refs_under(form, "/Fields") {
+            self.collect_field(field, "", 0, &mut fields, &mut seen);
+        }
+        fields
+    }
+
+    fn collect_field(
+        &self,
+        number: u32,
+        parent: &str,
+        depth: usize,
+        fields: &mut Vec<FormField>,
+        seen: &mut HashSet<u32>,
+    ) {
+        if depth > MAX_TREE_DEPTH || !seen.insert(number) {
+            return;
+        }
+        let Some(dict) = self.dict(number) else {
+            return;
+        };
+        let partial = match dict_entry(dict, b"T") {
+            Some(Value::Text(name)) => self.string(&name, number),
+            _ => String::new(),
+        };
+        let name = match (parent.is_empty(), partial.is_empty()) {
+            (true, _) => partial,
+            (false, true) => parent.to_string(),
+            (false, false) => format!("{}.{}", parent, partial),
+        };
+        for kid in refs_under(dict, "/Kids") {
+            self.collect_field(kid, &name, depth + 1, fields, seen);
+        }
+
+        // Fields without a type only group their kids
+        let Some(Value::Name(field_type)) = dict_entry(dict, b"FT") else {
+            return;
+        };
+        let value = match dict_entry(dict, b"V") {
+            Some(Value::Text(text)) => FieldValue::Text(form_text(&self.string(&text, number))),
+            Some(Value::Name(name)) if field_type == b"Btn" => match name.as_slice() {
+                b"Yes" | b"On" => FieldValue::Checked(true),
+                b"No" | b"Off" => FieldValue::Checked(false),
+                _ => FieldValue::Text(decode_string(&name)),
+            },
+            Some(Value::Name(name)) => FieldValue::Text(decode_string(&name)),
+            Some(Value::Array(items)) => FieldValue::Choices(
+                items
+                    .iter()
+                    .filter_map(|item| match item {
+                        Value::Text(text) => Some(self.string(text, number)),
+                        _ => None,
+                    })
+                    .collect(),
+            ),
+            _ => FieldValue::Empty,
+        };
+        fields.push(FormField { name, value });
+    }
+
+    /// A string from object `number`, decrypted if the file is encrypted
+    fn string(&self, bytes: &[u8], number: u32) -> String {
+        let generation = self.objects.get(&number).map_or(0, |span| span.generation);
+        match &self.decryptor {
+            Some(decryptor) => decryptor
+                .decrypt_string(bytes, number, generation)
+                .map_or_else(|_| String::new(), |bytes| decode_string(&bytes)),
+            None => decode_string(bytes),
+        }
+    }
+}
+
+impl PdfPages for BasicPages {
+    fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    fn page_text(&mut self, index: usize) -> std::result::Result<String, String> {
+        let page = self.pages[index];
+        let dict = self.dict(page).unwrap_or_default();
+        let mut content = Vec::new();
+        for number in refs_under(dict, "/Contents") {
+            content.extend(self.stream(number)?);
+            content.push(b'\n');
+        }
+        Ok(content_text(&content))
+    }
+}
+
+/// An operand in a content stream
+enum Operand {
+    Number(f64),
+    Text(Vec<u8>),
+    /// A name, without its `/`
+    Name(Vec<u8>),
+    Array(Vec<Operand>),
+    Other,
+}
+
+/// A value in a dictionary, as far as form fields need one
+enum Value {
+    Text(Vec<u8>),
+    Name(Vec<u8>),
+    Array(Vec<Value>),
+    Other,
+}
+
+/// The value under `key` (without its `/`) in the outermost dictionary of
+/// `object`
+fn dict_entry(object: &[u8], key: &[u8]) -> Option<Value> {
+    let mut lexer = Lexer {
+        data: object,
+        pos: 0,
+    };
+    while !matches!(lexer.next_token()?, Token::DictStart) {}
+    loop {
+        match lexer.next_token()? {
+            Token::Operand(Operand::Name(name)) => {
+                let value = lexer.value()?;
+                if name == key {
+                    return Some(value);
+                }
+            }
+            Token::DictEnd => return None,
+            _ => {}
+        }
+    }
+}
+
+/// Reads the operands and operators of a content stream
+struct Lexer<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+enum Token<'a> {
+    Operand(Operand),
+    ArrayStart,
+    ArrayEnd,
+    DictStart,
+    DictEnd,
+    Operator(&'a [u8]),
+}
+
+fn is_delimiter(b: u8) -> bool {
+    b.is_ascii_whitespace() || b"()<>[]{}/%".contains(&b)
+}
+
+impl<'a> Lexer<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.data.get(self.pos).copied()
+    }
+
+    fn next_token(&mut self) -> Option<Token<'a>> {
+        loop {
+            match self.peek()? {
+                b if b.is_ascii_whitespace() => self.pos += 1,
+                b'%' => {
+                    while self.peek().is_some_and(|b| b != b'\n' && b != b'\r') {
+                        self.pos += 1;
+                    }
+                }
+                _ => break,
+            }
+        }
+
+        let start = self.pos;
+        let token = match self.data[start] {
+            b'(' => Token::Operand(Operand::Text(self.literal_string())),
+            b'<' if self.data.get(start + 1) == Some(&b'<') => {
+                self.pos += 2;
+                Token::DictStart
+            }
+            b'>' if self.data.get(start + 1) == Some(&b'>') => {
+                self.pos += 2;
+                Token::DictEnd
+            }
+            b'<' => Token::Operand(Operand::Text(self.hex_string())),
+            b'[' => {
+                self.pos += 1;
+                Token::ArrayStart
+            }
+            b']' => {
+                self.pos += 1;
+                Token::ArrayEnd
+            }
+            b'/' => {
+                self.pos += 1;
+                self.skip_regular();
+                Token::Operand(Operand::Name(self.data[start + 1..self.pos].to_vec()))
+            }
+            _ => {
+                self.pos += 1;
+                self.skip_regular();
+                let word = &self.data[start..self.pos];
+                match std::str::from_utf8(word).ok().and_then(|s| s.parse().ok()) {
+                    Some(number) => Token::Operand(Operand::Number(number)),
+                    None => Token::Operator(word),
+                }
+            }
+        };
+        Some(token)
+    }
+
+    /// The next value: a string, name or array of them, with anything else
+    /// (a number, reference, dictionary or keyword) read past as `Other`
+    fn value(&mut self) -> Option<Value> {
+        Some(match self.next_token()? {
+            Token::Operand(Operand::Text(text)) => Value::Text(text),
+            Token::Operand(Operand::Name(name)) => Value::Name(name),
+            Token::Operand(Operand::Number(_)) => {
+                // `N G R` is one value
+                let after = self.pos;
+                let reference = matches!(
+                    (self.next_token(), self.next_token()),
+                    (
+                        Some(Token::Operand(Operand::Number(_))),
+                        Some(Token::Operator(b"R"))
+                    )
+                );
+                if !reference {
+                    self.pos = after;
+                }
+                Value::Other
+            }
+            Token::ArrayStart => {
+                let mut items = Vec::new();
+                loop {
+                    let before = self.pos;
+                    match self.next_token()? {
+                        Token::ArrayEnd => break,
+                        _ => self.pos = before,
+                    }
+                    items.push(self.value()?);
+                }
+                Value::Array(items)
+            }
+            Token::DictStart => {
+                let mut depth = 1;
+                while depth > 0 {
+                    match self.next_token()? {
+                        Token::DictStart => depth += 1,
+                        Token::DictEnd => depth -= 1,
+                        _ => {}
+                    }
+                }
+                Value::Other
+            }
+            _ => Value::Other,
+        })
+    }
+
+    fn skip_regular(&mut self) {
+        while self.peek().is_some_and(|b| !is_delimiter(b)) {
+            self.pos += 1;
+        }
+    }
+
+    /// A `(...)` string, with nested parentheses and escapes
+    fn literal_string(&mut self) -> Vec<u8> {
+        let mut text = Vec::new();
+        let mut depth = 0;
+        self.pos += 1;
+        while let Some(b) = self.peek() {
+            self.pos += 1;
+            match b {
+                b'(' => {
+                    depth += 1;
+                    text.push(b);
+                }
+                b')' if depth == 0 => break,
+                b')' => {
+                    depth -= 1;
+                    text.push(b);
+                }
+                b'\\' => {
+                    let Some(escaped) = self.peek() else { break };
+                    self.pos += 1;
+                    match escaped {
+                        b'n' => text.push(b'\n'),
+                        b'r' => text.push(b'\r'),
+                        b't' => text.push(b'\t'),
+                        b'b' => text.push(0x08),
+                        b'f' => text.push(0x0C),
+                        b'0'..=b'7' => {
+                            let mut value = u32::from(escaped - b'0');
+                            for _ in 0..2 {
+                                match self.peek() {
+                                    Some(d @ b'0'..=b'7') => {
+                                        value = value * 8 + u32::from(d - b'0');
+                                        self.pos += 1;
+                                    }
+                                    _ => break,
+                                }
+                            }
+                            text.push(value as u8);
+                        }
+                        // A backslash before a line break continues the line
+                        b'\r' => {
+                            if self.peek() == Some(b'\n') {
+                                self.pos += 1;
+                            }
+                        }
+                        b'\n' => {}
+                        other => text.push(other),
+                    }
+                }
+                _ => text.push(b),
+            }
+        }
+        text
+    }
+
+    /// A `<...>` string of hex digits; a missing final digit counts as 0
+    fn hex_string(&mut self) -> Vec<u8> {
+        self.pos += 1;
+        let mut digits = Vec::new();
+        while let Some(b) = self.peek() {
+            self.pos += 1;
+            if b == b'>' {
+                break;
+            }
+            if let Some(digit) = (b as char).to_digit(16) {
+                digits.push(digit as u8);
+            }
+        }
+        digits
+            .chunks(2)
+            .map(|pair| (pair[0] << 4) | pair.get(1).copied().unwrap_or(0))
+            .collect()
+    }
+
+    /// Skip inline image data, which runs from `ID` to `EI`
+    fn skip_inline_image(&mut self) {
+        let rest = &self.data[self.pos..];
+        let end = rest
+            .windows(4)
+            .position(|w| w[0].is_ascii_whitespace() && &w[1..3] == b"EI" && is_delimiter(w[3]))
+            .map_or(rest.len(), |at| at + 3);
+        self.pos += end;
+    }
+}
+
+/// Decode a PDF string: UTF-16BE with a byte order mark, Latin-1 otherwise
+fn decode_string(bytes: &[u8]) -> String {
+    match bytes.strip_prefix(&[0xFE, 0xFF]) {
+        Some(utf16) => {
+            let units = utf16
+                .chunks_exact(2)
+                .map(|pair| u16::from_be_bytes([pair[0], pair[1]]));
+            char::decode_utf16(units)
+                .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                .collect()
+        }
+        None => bytes.iter().map(|&b| char::from(b)).collect(),
+    }
+}
+
+/// Collects shown text, starting a new line where the text moves down
+#[derive(Default)]
+struct TextWriter {
+    text: String,
+    line_break: bool,
+}
+
+impl TextWriter {
+    fn show(&mut self, bytes: &[u8]) {
+        if self.line_break && !self.text.is_empty() {
+            self.text.push('\n');
+        }
+        self.line_break = false;
+        self.text.push_str(&decode_string(bytes));
+    }
+
+    fn space(&mut self) {
+        if !self.line_break && !self.text.is_empty() && !self.text.ends_with(' ') {
+            self.text.push(' ');
+        }
+    }
+}
+
+/// The text shown by a page's content stream
+fn content_text(content: &[u8]) -> String {
+    let mut lexer = Lexer {
+        data: content,
+        pos: 0,
+    };
+    let mut writer = TextWriter::default();
+    let mut operands: Vec<Operand> = Vec::new();
+    // Operands of enclosing arrays while one is being read
+    let mut arrays: Vec<Vec<Operand>> = Vec::new();
+
+    while let Some(token) = lexer.next_token() {
+        let operator = match token {
+            Token::Operand(operand) => {
+                arrays.last_mut().unwrap_or(&mut operands).push(operand);
+                continue;
+            }
+            Token::ArrayStart => {
+                arrays.push(Vec::new());
+                continue;
+            }
+            Token::DictStart | Token::DictEnd => {
+                arrays
+                    .last_mut()
+                    .unwrap_or(&mut operands)
+                    .push(Operand::Other);
+                continue;
+            }
+            Token::ArrayEnd => {
+                if let Some(array) = arrays.pop() {
+                    arrays
+                        .last_mut()
+                        .unwrap_or(&mut operands)
+                        .push(Operand::Array(array));
+                }
+                continue;
+            }
+            Token::Operator(operator) => operator,
+        };
+
+        match operator {
+            b"Tj" => {
+                if let Some(Operand::Text(text)) = operands.last() {
+                    writer.show(text);
+                }
+            }
+            b"'" | b"\"" => {
+                writer.line_break = true;
+                if let Some(Operand::Text(text)) = operands.last() {
+                    writer.show(text);
+                }
+            }
+            b"TJ" => {
+                if let Some(Operand::Array(parts)) = operands.last() {
+                    for part in parts {
+                        match part {
+                            Operand::Text(text) => writer.show(text),
+                            Operand::Number(kerning) if *kerning < TJ_SPACE => writer.space(),
+                            _ => {}
+                        }
+                    }
+                }
+            }
+            b"Td" | b"TD" => {
+                if let [.., Operand::Number(_), Operand::Number(ty)] = operands.as_slice() {
+                    if *ty != 0.0 {
+                        writer.line_break = true;
+                    } else {
+                        writer.space();
+                    }
+                }
+            }
+            b"T*" | b"ET" | b"Tm" => writer.line_break = true,
+            b"ID" => lexer.skip_inline_image(),
+            _ => {}
+        }
+        operands.clear();
+        arrays.clear();
+    }
+    writer.text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    #[test]
+    fn test_content_text_operators() {
+        let content = b"BT /F1 12 Tf 72 720 Td (Hello, ) Tj (world) Tj 0 -14 Td \
+            [(Ker) -50 (ned) -400 (words)] TJ T* (next \\(line\\)) Tj ET \
+            BT <48692021> Tj ET";
+        assert_eq!(
+            content_text(content),
+            "Hello, world\nKerned words\nnext (line)\nHi !"
+        );
+    }
+
+    #[test]
+    fn test_content_text_escapes_and_encodings() {
+        assert_eq!(content_text(br"BT (caf\351 \101\n) Tj ET"), "caf\u{E9} A\n");
+        assert_eq!(content_text(b"BT <FEFF00E9> Tj ET"), "\u{E9}");
+        // Inline images are skipped rather than read as text
+        assert_eq!(
+            content_text(b"BI /W 1 /H 1 ID \x00(junk) Tj\n EI BT (after) Tj ET"),
+            "after"
+        );
+    }
+
+    /// A PDF with one object per entry of `objects`, numbered from 1, and
+    /// no cross-reference table or trailer
+    fn pdf(objects: &[&[u8]]) -> Vec<u8> {
+        let mut data = b"%PDF-1.4\n".to_vec();
+        for (i, object) in objects.iter().enumerate() {
+            data.extend(format!("{} 0 obj\n", i + 1).as_bytes());
+            data.extend(*object);
+            data.extend(b"\nendobj\n");
+        }
+        data
+    }
+
+    fn stream(dict: &str, content: &[u8]) -> Vec<u8> {
+        let mut object = format!("<< {} /Length {} >>\nstream\n", dict, content.len()).into_bytes();
+        object.extend(content);
+        object.extend(b"\nendstream");
+        object
+    }
+
+    #[test]
+    fn test_pages_follow_the_page_tree() {
+        let mut deflated = ZlibEncoder::new(Vec::new(), Compression::default());
+        deflated.write_all(b"BT (second) Tj ET").unwrap();
+        let second = stream("/Filter /FlateDecode", &deflated.finish().unwrap());
+        let first = stream("", b"BT (first) Tj ET");
+        let data = pdf(&[
+            b"<< /Type /Catalog /Pages 2 0 R >>",
+            b"<< /Type /Pages /Kids [5 0 R 3 0 R] /Count 2 >>",
+            b"<< /Type /Page /Parent 2 0 R /Contents 4 0 R >>",
+            &second,
+            b"<< /Type /Page /Parent 2 0 R /Contents [6 0 R] >>",
+            &first,
+        ]);
+
+        let mut pages = BasicPages::parse(data).unwrap();
+        assert_eq!(pages.page_count(), 2);
+        assert_eq!(pages.page_text(0).unwrap(), "first");
+        assert_eq!(pages.page_text(1).unwrap(), "second");
+    }
+
+    #[test]
+    fn test_unreadable_streams_fail_their_page() {
+        let bad = stream("/Filter /FlateDecode", b"not zlib");
+        let lzw = stream("/Filter /LZWDecode", b"??");
+        let data = pdf(&[
+            b"<< /Type /Page /Contents 2 0 R >>",
+            &bad,
+            b"<< /Type /Page /Contents 4 0 R >>",
+            &lzw,
+            b"<< /Type /Page /Contents 9 0 R >>",
+        ]);
+
+        let mut pages = BasicPages::parse(data).unwrap();
+        assert_eq!(pages.page_count(), 3);
+        assert!(pages.page_text(0).unwrap_err().contains("inflate"));
+        assert!(pages
+            .page_text(1)
+            .unwrap_err()
+            .contains("unsupported filter"));
+        assert!(pages.page_text(2).unwrap_err().contains("missing"));
+    }
+
+    #[test]
+    fn test_inflated_streams_are_capped() {
+        let mut deflated = ZlibEncoder::new(Vec::new(), Compression::best());
+        let zeros = vec![0; 1024 * 1024];
+        for _ in 0..=MAX_STREAM_LEN >> 20 {
+            deflated.write_all(&zeros).unwrap();
+        }
+        let bomb = stream("/Filter /FlateDecode", &deflated.finish().unwrap());
+        let data = pdf(&[b"<< /Type /Page /Contents 2 0 R >>", &bomb]);
+
+        let mut pages = BasicPages::parse(data).unwrap();
+        let err = pages.page_text(0).unwrap_err();
+        assert!(err.contains("inflates past 64 MiB"), "{}", err);
+    }
+
+    #[test]
+    fn test_deep_page_trees_are_cut_off() {
+        // The root holds a page (object 2) and a chain of /Pages nodes,
+        // each the only kid of the one before, ending in a page below the
+        // depth limit
+        let chain = MAX_TREE_DEPTH + 1;
+        let mut objects = vec![
+            b"<< /Type /Pages /Kids [2 0 R 3 0 R] >>".to_vec(),
+            b"<< /Type /Page /Parent 1 0 R >>".to_vec(),
+        ];
+        for n in 3..3 + chain {
+            objects.push(
+                format!("<< /Type /Pages /Parent 1 0 R /Kids [{} 0 R] >>", n + 1).into_bytes(),
+            );
+        }
+        objects.push(b"<< /Type /Page /Parent 1 0 R >>".to_vec());
+        let objects: Vec<&[u8]> = objects.iter().map(Vec::as_slice).collect();
+
+        let pages = BasicPages::parse(pdf(&objects)).unwrap();
+        assert_eq!(pages.pages, [2]);
+    }
+
+    #[test]
+    fn test_form_fields() {
+        let data = pdf(&[
+            b"<< /Type /Catalog /Pages 2 0 R /AcroForm << /Fields [4 0 R 5 0 R 7 0 R] >> >>",
+            b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>",
+            b"<< /Type /Page /Parent 2 0 R >>",
+            b"<< /FT /Tx /T (name) /V (Ada\rLovelace) >>",
+            b"<< /T (address) /Kids [6 0 R] >>",
+            b"<< /FT /Tx /T (city) /Parent 5 0 R /V <FEFF004C006F006E0064006F006E> >>",
+            b"<< /FT /Btn /T (agree) /V /Yes >>",
+        ]);
+
+        let pages = BasicPages::parse(data).unwrap();
+        let fields: Vec<String> = pages
+            .form_fields()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            fields,
+            ["name: Ada\nLovelace", "address.city: London", "agree: yes"]
+        );
+    }
+
+    #[test]
+    fn test_rejects_files_without_pages() {
+        assert_eq!(
+            BasicPages::parse(b"plain text".to_vec()).err().unwrap(),
+            "not a PDF file"
+        );
+        let data = pdf(&[b"<< /Type /Catalog >>"]);
+        assert_eq!(BasicPages::parse(data).err().unwrap(), "no pages found");
+    }
+}
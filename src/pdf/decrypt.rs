@@ -0,0 +1,92 @@
+//! Opening password-protected PDFs in the `basic` backend.
+//!
+//! pdf_oxide can only open encrypted files whose user password is empty,
+//! but its standard security handler is public, so the basic backend uses it
+//! for key derivation and RC4/AES decryption. Only user passwords are
+//! checked; an owner password alone does not unlock a file.
+
+use crate::error::CatboardError;
+use pdf_oxide::encryption::EncryptionHandler;
+use pdf_oxide::parser::parse_object;
+use std::path::Path;
+
+/// Why an encrypted PDF could not be unlocked
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum UnlockError {
+    /// The file needs a password and none was given
+    PasswordRequired,
+    /// The password given doesn't unlock the file
+    WrongPassword,
+    /// The encryption dictionary is damaged or uses an unsupported handler
+    Unsupported(String),
+}
+
+impl UnlockError {
+    pub(super) fn into_error(self, path: &Path) -> CatboardError {
+        match self {
+            UnlockError::PasswordRequired => CatboardError::PasswordRequired(path.to_path_buf()),
+            UnlockError::WrongPassword => CatboardError::WrongPassword(path.to_path_buf()),
+            UnlockError::Unsupported(message) => CatboardError::extraction(path, message),
+        }
+    }
+}
+
+/// Decrypts the strings and streams of an unlocked PDF
+pub(super) struct Decryptor {
+    handler: EncryptionHandler,
+}
+
+impl Decryptor {
+    /// Unlock a PDF from its `/Encrypt` dictionary and the first entry of
+    /// its trailer `/ID`.
+    ///
+    /// The empty password is tried when `password` is `None` or doesn't
+    /// match, since many files are encrypted only to set permissions.
+    pub(super) fn unlock(
+        encrypt_dict: &[u8],
+        file_id: Vec<u8>,
+        password: Option<&str>,
+    ) -> Result<Self, UnlockError> {
+        let (_, dict) = parse_object(encrypt_dict)
+            .map_err(|_| UnlockError::Unsupported("unreadable /Encrypt dictionary".to_string()))?;
+        let mut handler = EncryptionHandler::new(&dict, file_id)
+            .map_err(|e| UnlockError::Unsupported(e.to_string()))?;
+
+        for candidate in password.into_iter().chain([""]) {
+            let unlocked = handler
+                .authenticate(candidate.as_bytes())
+                .map_err(|e| UnlockError::Unsupported(e.to_string()))?;
+            if unlocked {
+                return Ok(Self { handler });
+            }
+        }
+        Err(match password {
+            Some(_) => UnlockError::WrongPassword,
+            None => UnlockError::PasswordRequired,
+        })
+    }
+
+    /// Decrypt the raw data of a stream in object `number`
+    pub(super) fn decrypt(
+        &self,
+        data: &[u8],
+        number: u32,
+        generation: u32,
+    ) -> Result<Vec<u8>, String> {
+        self.handler
+            .decrypt_stream(data, number, generation)
+            .map_err(|e| format!("could not decrypt object {}: {}", number, e))
+    }
+
+    /// Decrypt a string in object `number`
+    pub(super) fn decrypt_string(
+        &self,
+        data: &[u8],
+        number: u32,
+        generation: u32,
+    ) -> Result<Vec<u8>, String> {
+        self.handler
+            .decrypt_string(data, number, generation)
+            .map_err(|e| format!("could not decrypt object {}: {}", number, e))
+    }
+}
@@ -0,0 +1,182 @@
+//! Embedded page images, for OCRing scanned PDFs without rendering them.
+//!
+//! Scanners store each page as an image XObject. pdf_oxide's own
+//! `extract_images` skips images whose `/ColorSpace` is an indirect
+//! reference, which is how many scanners write them, so the page tree is
+//! walked here and colour spaces are resolved before pdf_oxide decodes the
+//! image. Only images drawn directly by a page are found, not those nested
+//! in form XObjects.
+
+use crate::error::{CatboardError, Result};
+use pdf_oxide::extractors::{extract_image_from_xobject, ImageData, PdfImage};
+use pdf_oxide::object::Object;
+use pdf_oxide::PdfDocument;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Deepest page tree walked, guarding against reference cycles
+const MAX_TREE_DEPTH: usize = 32;
+
+/// Save the images embedded in each page of a PDF into `dir`, returning
+/// their paths page by page.
+///
+/// JPEG images are written as they are; anything else is encoded as PNG.
+/// Images that can't be decoded are skipped.
+pub(super) fn save_page_images(path: &Path, dir: &Path) -> Result<Vec<Vec<PathBuf>>> {
+    let mut doc =
+        PdfDocument::open(path).map_err(|e| CatboardError::extraction(path, e.to_string()))?;
+    let catalog = doc
+        .catalog()
+        .map_err(|e| CatboardError::extraction(path, e.to_string()))?;
+    let root = catalog
+        .as_dict()
+        .and_then(|catalog| catalog.get("Pages"))
+        .ok_or_else(|| CatboardError::extraction(path, "PDF has no page tree"))?
+        .clone();
+
+    let mut resources = Vec::new();
+    collect_pages(&mut doc, &root, None, 0, &mut resources);
+
+    let mut pages = Vec::with_capacity(resources.len());
+    for (index, resources) in resources.iter().enumerate() {
+        let mut saved = Vec::new();
+        for image in page_images(&mut doc, resources.as_ref()) {
+            let extension = match image.data() {
+                ImageData::Jpeg(_) => "jpg",
+                _ => "png",
+            };
+            let file = dir.join(format!(
+                "page{}_{:03}.{}",
+                index + 1,
+                saved.len() + 1,
+                extension
+            ));
+            let written = match image.data() {
+                ImageData::Jpeg(_) => image.save_as_jpeg(&file),
+                _ => image.save_as_png(&file),
+            };
+            if written.is_ok() {
+                saved.push(file);
+            }
+        }
+        pages.push(saved);
+    }
+    Ok(pages)
+}
+
+/// Load `object` if it's a reference
+fn resolve(doc: &mut PdfDocument, object: &Object) -> Option<Object> {
+    match object.as_reference() {
+        Some(reference) => doc.load_object(reference).ok(),
+        None => Some(object.clone()),
+    }
+}
+
+/// Walk the page tree under `node` in order, recording each page's
+/// resources, inherited from its ancestors when it has none of its own
+fn collect_pages(
+    doc: &mut PdfDocument,
+    node: &Object,
+    inherited: Option<&Object>,
+    depth: usize,
+    pages: &mut Vec<Option<Object>>,
+) {
+    if depth > MAX_TREE_DEPTH {
+        return;
+    }
+    let Some(node) = resolve(doc, node) else {
+        return;
+    };
+    let Some(dict) = node.as_dict() else {
+        return;
+    };
+    let resources = dict.get("Resources").or(inherited);
+
+    match dict.get("Kids").and_then(|kids| resolve(doc, kids)) {
+        Some(Object::Array(kids)) => {
+            for kid in &kids {
+                collect_pages(doc, kid, resources, depth + 1, pages);
+            }
+        }
+        _ => pages.push(resources.cloned()),
+    }
+}
+
+/// Decode the image XObjects in a page's resources, in name order
+fn page_images(doc: &mut PdfDocument, resources: Option<&Object>) -> Vec<PdfImage> {
+    let Some(resources) = resources.and_then(|resources| resolve(doc, resources)) else {
+        return Vec::new();
+    };
+    let Some(xobjects) = resources
+        .as_dict()
+        .and_then(|resources| resources.get("XObject"))
+        .and_then(|xobjects| resolve(doc, xobjects))
+    else {
+        return Vec::new();
+    };
+    let Some(xobjects) = xobjects.as_dict() else {
+        return Vec::new();
+    };
+
+    // Dictionary order is arbitrary; scanners name images Im1, Im2, ...
+    let xobjects: BTreeMap<&String, &Object> = xobjects.iter().collect();
+    let mut images = Vec::new();
+    for xobject in xobjects.into_values() {
+        let Some(mut image) = resolve(doc, xobject) else {
+            continue;
+        };
+        let Object::Stream { dict, .. } = &mut image else {
+            continue;
+        };
+        if dict.get("Subtype").and_then(Object::as_name) != Some("Image") {
+            continue;
+        }
+        if let Some(colour_space) = dict.get("ColorSpace").cloned() {
+            if let Some(resolved) = resolve_colour_space(doc, &colour_space) {
+                dict.insert("ColorSpace".to_string(), resolved);
+            }
+        }
+        if let Ok(image) = extract_image_from_xobject(Some(doc), &image, xobject.as_reference()) {
+            images.push(image);
+        }
+    }
+    images
+}
+
+/// Resolve a colour space and the references inside it, such as the ICC
+/// profile stream of `[/ICCBased 7 0 R]`
+fn resolve_colour_space(doc: &mut PdfDocument, colour_space: &Object) -> Option<Object> {
+    match resolve(doc, colour_space)? {
+        Object::Array(parts) => Some(Object::Array(
+            parts
+                .iter()
+                .map(|part| resolve(doc, part).unwrap_or_else(|| part.clone()))
+                .collect(),
+        )),
+        resolved => Some(resolved),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_save_page_images() {
+        let dir = TempDir::new().unwrap();
+        // A scan whose image has an indirect ICC colour space
+        let pages =
+            save_page_images(Path::new("tests/2025-12-12_12-11-14.pdf"), dir.path()).unwrap();
+        assert_eq!(pages, [[dir.path().join("page1_001.png")]]);
+        assert!(pages[0][0].is_file());
+    }
+
+    #[test]
+    fn test_text_pages_have_no_images() {
+        let dir = TempDir::new().unwrap();
+        let pages = save_page_images(Path::new("tests/three-pages.pdf"), dir.path()).unwrap();
+        assert_eq!(pages.len(), 3);
+        assert!(pages.iter().all(Vec::is_empty));
+    }
+}
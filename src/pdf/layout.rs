@@ -0,0 +1,192 @@
+//! Layout-preserving text for `--pdf-layout`.
+//!
+//! Positioned spans are placed on a character grid, the way `pdftotext
+//! -layout` does: spans on the same baseline share a line, horizontal
+//! position becomes indentation measured in average character widths, and
+//! unusually large vertical gaps become blank lines. Columns and table cells
+//! therefore stay side by side instead of being read one after another.
+
+/// A run of text and where it sits on the page, in PDF units with `y`
+/// increasing up the page
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Span {
+    pub(super) text: String,
+    pub(super) x: f32,
+    pub(super) y: f32,
+    pub(super) width: f32,
+    pub(super) height: f32,
+}
+
+/// Character width assumed when no span has a measurable width
+const DEFAULT_CHAR_WIDTH: f32 = 6.0;
+
+/// Narrowest character width used, so near-zero widths can't spread spans
+/// across millions of columns
+const MIN_CHAR_WIDTH: f32 = 1.0;
+
+/// Furthest column a span is indented to
+const MAX_LINE_WIDTH: usize = 1000;
+
+/// Most blank lines a vertical gap turns into
+const MAX_BLANK_LINES: usize = 2;
+
+/// Lay out a page's spans as lines of text
+pub(super) fn render(spans: &[Span]) -> String {
+    let mut spans: Vec<&Span> = spans.iter().filter(|s| !s.text.trim().is_empty()).collect();
+    if spans.is_empty() {
+        return String::new();
+    }
+
+    let char_width = char_width(&spans);
+    let left = spans.iter().map(|s| s.x).fold(f32::INFINITY, f32::min);
+
+    // Top of the page first, then left to right
+    spans.sort_by(|a, b| b.y.total_cmp(&a.y).then(a.x.total_cmp(&b.x)));
+    let mut lines: Vec<Vec<&Span>> = Vec::new();
+    for span in spans {
+        match lines.last_mut() {
+            Some(line) if same_line(line[0], span) => line.push(span),
+            _ => lines.push(vec![span]),
+        }
+    }
+    for line in &mut lines {
+        line.sort_by(|a, b| a.x.total_cmp(&b.x));
+    }
+
+    let pitch = line_pitch(&lines);
+    let mut text = String::new();
+    for (i, line) in lines.iter().enumerate() {
+        if i > 0 {
+            text.push('\n');
+            let gap = lines[i - 1][0].y - line[0].y;
+            let blank = ((gap / pitch).round() as usize).saturating_sub(1);
+            text.push_str(&"\n".repeat(blank.min(MAX_BLANK_LINES)));
+        }
+        text.push_str(&render_line(line, left, char_width));
+    }
+    text
+}
+
+/// Average width of a character across all spans, at least
+/// [`MIN_CHAR_WIDTH`]
+fn char_width(spans: &[&Span]) -> f32 {
+    let chars: usize = spans.iter().map(|s| s.text.chars().count()).sum();
+    let width: f32 = spans.iter().map(|s| s.width).sum();
+    if chars == 0 || width <= 0.0 {
+        DEFAULT_CHAR_WIDTH
+    } else {
+        (width / chars as f32).max(MIN_CHAR_WIDTH)
+    }
+}
+
+/// Whether `span` sits on the same baseline as the line starting with
+/// `first`, allowing for sub- and superscripts
+fn same_line(first: &Span, span: &Span) -> bool {
+    let tolerance = first.height.min(span.height).max(1.0) / 2.0;
+    (first.y - span.y).abs() < tolerance
+}
+
+/// The usual distance between consecutive lines: the smallest gap that
+/// isn't just a sub- or superscript (infinite for a single line)
+fn line_pitch(lines: &[Vec<&Span>]) -> f32 {
+    lines
+        .windows(2)
+        .map(|pair| (pair[0][0].y - pair[1][0].y, pair[0][0].height))
+        .filter(|&(gap, height)| gap >= height / 2.0)
+        .map(|(gap, _)| gap)
+        .fold(f32::INFINITY, f32::min)
+}
+
+fn render_line(line: &[&Span], left: f32, char_width: f32) -> String {
+    let mut text = String::new();
+    let mut columns = 0;
+    let mut end = f32::NEG_INFINITY;
+    for span in line {
+        let column = ((span.x - left) / char_width).round().max(0.0) as usize;
+        let column = column.min(MAX_LINE_WIDTH);
+        if column > columns {
+            text.push_str(&" ".repeat(column - columns));
+            columns = column;
+        } else if columns > 0 && span.x - end > char_width / 4.0 && !text.ends_with(' ') {
+            // Crowded, but still separate words
+            text.push(' ');
+            columns += 1;
+        }
+        text.push_str(&span.text);
+        columns += span.text.chars().count();
+        end = span.x + span.width;
+    }
+    text.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A span whose characters are 6 units wide
+    fn span(text: &str, x: f32, y: f32) -> Span {
+        Span {
+            text: text.to_string(),
+            x,
+            y,
+            width: 6.0 * text.chars().count() as f32,
+            height: 12.0,
+        }
+    }
+
+    #[test]
+    fn test_columns_stay_side_by_side() {
+        // Drawn a column at a time, as tables often are
+        let spans = [
+            span("Name", 72.0, 700.0),
+            span("Ann", 72.0, 686.0),
+            span("Age", 132.0, 700.0),
+            span("34", 132.0, 686.0),
+        ];
+        assert_eq!(render(&spans), "Name      Age\nAnn       34");
+    }
+
+    #[test]
+    fn test_vertical_gaps_become_blank_lines() {
+        let spans = [
+            span("one", 72.0, 700.0),
+            span("two", 72.0, 686.0),
+            span("three", 72.0, 644.0),
+            span("far below", 72.0, 100.0),
+        ];
+        assert_eq!(render(&spans), "one\ntwo\n\n\nthree\n\n\nfar below");
+    }
+
+    #[test]
+    fn test_same_line_spans_are_merged() {
+        let spans = [
+            span("x", 72.0, 700.0),
+            // A superscript, slightly raised
+            span("2", 78.0, 704.0),
+            span("indented", 96.0, 686.0),
+            // Touching spans join; a small gap keeps a space
+            span("over", 72.0, 672.0),
+            span("lap", 96.0, 672.0),
+            span("ping", 116.5, 672.0),
+        ];
+        assert_eq!(render(&spans), "x2\n    indented\noverlap ping");
+    }
+
+    #[test]
+    fn test_indentation_is_bounded() {
+        // Near-zero widths and a span far off to the right
+        let tiny = |text: &str, x: f32| Span {
+            width: 0.001,
+            ..span(text, x, 700.0)
+        };
+        let line = render(&[tiny("a", 0.0), tiny("b", 1.0e9)]);
+        assert_eq!(line.len(), MAX_LINE_WIDTH + 1);
+        assert!(line.starts_with("a ") && line.ends_with(" b"));
+    }
+
+    #[test]
+    fn test_blank_page() {
+        assert_eq!(render(&[]), "");
+        assert_eq!(render(&[span("  ", 0.0, 0.0)]), "");
+    }
+}
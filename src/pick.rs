@@ -0,0 +1,156 @@
+//! Interactive file picker for `--pick`.
+//!
+//! Lists candidate files with numbers and reads a selection such as
+//! `1 3`, `1,3` or `2-4` from the user.
+
+use crate::error::{CatboardError, Result, ResultExt};
+use crate::walk::ExtensionFilter;
+use std::fs;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+/// List the files directly inside `dir` that pass `filter`, sorted by name
+pub fn list_candidates(dir: &Path, filter: &ExtensionFilter) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).with_path(dir)? {
+        let entry = entry.with_path(dir)?;
+        let path = entry.path();
+        if path.is_file() && filter.matches(&path) {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Parse a 1-based selection like `1 3`, `1,3` or `2-4` into 0-based indices.
+///
+/// Indices are returned in the order given, without duplicates.
+///
+/// # Errors
+/// - `InvalidArguments` for empty input, malformed numbers or out-of-range picks
+pub fn parse_selection(input: &str, count: usize) -> Result<Vec<usize>> {
+    let invalid = |msg: String| CatboardError::InvalidArguments(msg);
+    let parse_number = |s: &str| {
+        let n: usize = s
+            .trim()
+            .parse()
+            .map_err(|_| invalid(format!("'{}' is not a file number", s.trim())))?;
+        if n == 0 || n > count {
+            return Err(invalid(format!("{} is not between 1 and {}", n, count)));
+        }
+        Ok(n - 1)
+    };
+
+    let mut indices = Vec::new();
+    for part in input.split(|c: char| c == ',' || c.is_whitespace()) {
+        if part.is_empty() {
+            continue;
+        }
+        let range = match part.split_once('-') {
+            Some((start, end)) => parse_number(start)?..=parse_number(end)?,
+            None => {
+                let n = parse_number(part)?;
+                n..=n
+            }
+        };
+        for index in range {
+            if !indices.contains(&index) {
+                indices.push(index);
+            }
+        }
+    }
+
+    if indices.is_empty() {
+        return Err(invalid("no files selected".to_string()));
+    }
+    Ok(indices)
+}
+
+/// Show `candidates` on `prompt` and read the user's selection from `input`
+///
+/// # Errors
+/// - `NoFilesSpecified` if there is nothing to pick from
+/// - `InvalidArguments` if the selection can't be parsed
+pub fn pick_files<R: BufRead, W: Write>(
+    candidates: &[PathBuf],
+    mut input: R,
+    mut prompt: W,
+) -> Result<Vec<PathBuf>> {
+    if candidates.is_empty() {
+        return Err(CatboardError::NoFilesSpecified);
+    }
+
+    for (i, path) in candidates.iter().enumerate() {
+        let name = path.file_name().unwrap_or(path.as_os_str());
+        writeln!(prompt, "{:>3}) {}", i + 1, Path::new(name).display()).with_path("-")?;
+    }
+    write!(prompt, "Select files (e.g. 1 3 or 2-4): ").with_path("-")?;
+    prompt.flush().with_path("-")?;
+
+    let mut line = String::new();
+    input.read_line(&mut line).with_path("-")?;
+
+    Ok(parse_selection(&line, candidates.len())?
+        .into_iter()
+        .map(|i| candidates[i].clone())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_selection_forms() {
+        assert_eq!(parse_selection("2", 3).unwrap(), vec![1]);
+        assert_eq!(parse_selection("1 3", 3).unwrap(), vec![0, 2]);
+        assert_eq!(parse_selection("3,1\n", 3).unwrap(), vec![2, 0]);
+        assert_eq!(parse_selection("2-4, 1 2", 5).unwrap(), vec![1, 2, 3, 0]);
+    }
+
+    #[test]
+    fn test_parse_selection_invalid() {
+        assert!(parse_selection("", 3).is_err());
+        assert!(parse_selection("0", 3).is_err());
+        assert!(parse_selection("4", 3).is_err());
+        assert!(parse_selection("one", 3).is_err());
+        assert!(parse_selection("1-9", 3).is_err());
+    }
+
+    #[test]
+    fn test_list_candidates_filters_and_sorts() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("subdir")).unwrap();
+        fs::write(dir.path().join("b.rs"), "").unwrap();
+        fs::write(dir.path().join("a.rs"), "").unwrap();
+        fs::write(dir.path().join("c.lock"), "").unwrap();
+
+        let filter = ExtensionFilter::new(Vec::<String>::new(), ["lock"]);
+        let files = list_candidates(dir.path(), &filter).unwrap();
+        assert_eq!(
+            files,
+            vec![dir.path().join("a.rs"), dir.path().join("b.rs")]
+        );
+    }
+
+    #[test]
+    fn test_pick_files_reads_selection() {
+        let candidates = vec![PathBuf::from("a.rs"), PathBuf::from("b.rs")];
+        let mut prompt = Vec::new();
+
+        let picked = pick_files(&candidates, "2\n".as_bytes(), &mut prompt).unwrap();
+        assert_eq!(picked, vec![PathBuf::from("b.rs")]);
+
+        let prompt = String::from_utf8(prompt).unwrap();
+        assert!(prompt.contains("  1) a.rs"));
+        assert!(prompt.contains("  2) b.rs"));
+    }
+
+    #[test]
+    fn test_pick_files_no_candidates() {
+        let result = pick_files(&[], "1\n".as_bytes(), Vec::new());
+        assert!(matches!(result, Err(CatboardError::NoFilesSpecified)));
+    }
+}
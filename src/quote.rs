@@ -0,0 +1,119 @@
+//! Quoting for embedding copied text in shell commands or JSON.
+
+use crate::error::{CatboardError, Result};
+use crate::transform::Transform;
+
+/// Quote `text` as a single POSIX shell word.
+///
+/// Everything goes inside single quotes, where the shell treats every byte
+/// literally (newlines and control characters included). Embedded single
+/// quotes are written as `'\''`. Shell strings cannot hold NUL, so text
+/// containing one is rejected rather than silently truncated.
+pub fn shell_quote(text: &str) -> Result<String> {
+    if text.contains('\0') {
+        return Err(CatboardError::TransformError {
+            name: "shell-quote",
+            message: "text contains a NUL byte, which shell strings cannot hold".to_string(),
+        });
+    }
+    Ok(format!("'{}'", text.replace('\'', r"'\''")))
+}
+
+/// Encode `text` as a JSON string literal, quotes included
+pub fn json_string(text: &str) -> String {
+    serde_json::Value::from(text).to_string()
+}
+
+/// Which quoting `--shell-quote` or `--json-string` applies
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quote {
+    Shell,
+    Json,
+}
+
+impl Transform for Quote {
+    fn apply(&self, input: &str) -> Result<String> {
+        match self {
+            Quote::Shell => shell_quote(input),
+            Quote::Json => Ok(json_string(input)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_quote_plain() {
+        assert_eq!(shell_quote("hello world").unwrap(), "'hello world'");
+        assert_eq!(shell_quote("").unwrap(), "''");
+    }
+
+    #[test]
+    fn test_shell_quote_metacharacters_are_literal() {
+        assert_eq!(
+            shell_quote("$HOME `id` $(rm -rf /) \"x\" \\ * ; |").unwrap(),
+            "'$HOME `id` $(rm -rf /) \"x\" \\ * ; |'"
+        );
+    }
+
+    #[test]
+    fn test_shell_quote_single_quotes() {
+        assert_eq!(shell_quote("it's").unwrap(), r"'it'\''s'");
+        assert_eq!(shell_quote("''").unwrap(), r"''\'''\'''");
+    }
+
+    #[test]
+    fn test_shell_quote_newlines_and_controls() {
+        assert_eq!(shell_quote("a\nb\tc\x1b[0m").unwrap(), "'a\nb\tc\x1b[0m'");
+    }
+
+    #[test]
+    fn test_shell_quote_rejects_nul() {
+        assert!(matches!(
+            shell_quote("a\0b"),
+            Err(CatboardError::TransformError {
+                name: "shell-quote",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_shell_quote_round_trips_through_sh() {
+        let text = "it's \"tricky\"\n$PATH `x` \\n '' \u{E9}";
+        let quoted = shell_quote(text).unwrap();
+        let output = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(format!("printf '%s' {}", quoted))
+            .output();
+        // Skip where no POSIX shell is available
+        if let Ok(output) = output {
+            assert_eq!(String::from_utf8(output.stdout).unwrap(), text);
+        }
+    }
+
+    #[test]
+    fn test_json_string_escapes() {
+        assert_eq!(json_string("plain"), r#""plain""#);
+        assert_eq!(json_string(r#"say "hi""#), r#""say \"hi\"""#);
+        assert_eq!(json_string(r"C:\path"), r#""C:\\path""#);
+        assert_eq!(json_string("a\nb\r\tc"), r#""a\nb\r\tc""#);
+        assert_eq!(json_string("\0\x07\x1b"), r#""\u0000\u0007\u001b""#);
+        assert_eq!(json_string("caf\u{E9}"), "\"caf\u{E9}\"");
+    }
+
+    #[test]
+    fn test_json_string_round_trips() {
+        let text = "line \"one\"\n\ttwo \\ \u{1F600} \0";
+        let decoded: String = serde_json::from_str(&json_string(text)).unwrap();
+        assert_eq!(decoded, text);
+    }
+
+    #[test]
+    fn test_as_transform() {
+        assert_eq!(Quote::Shell.apply("a'b").unwrap(), r"'a'\''b'");
+        assert_eq!(Quote::Json.apply("a\"b").unwrap(), r#""a\"b""#);
+    }
+}
@@ -0,0 +1,388 @@
+//! Selecting part of the payload with `--head`, `--tail`, `--bytes` and
+//! `--chars`, and part of each file with `--line-range`.
+//!
+//! Every selection returns a slice of the input, so kept lines keep their
+//! original endings. `tail_lines` scans backwards from the end, so its cost
+//! grows with the lines kept rather than the size of the text.
+
+use crate::error::Result;
+use crate::transform::Transform;
+use std::fmt;
+use std::str::FromStr;
+
+/// The first `n` lines of `text`
+pub fn head_lines(text: &str, n: usize) -> &str {
+    if n == 0 {
+        return "";
+    }
+    match text.match_indices('\n').nth(n - 1) {
+        Some((end, _)) => &text[..=end],
+        None => text,
+    }
+}
+
+/// The last `n` lines of `text`
+pub fn tail_lines(text: &str, n: usize) -> &str {
+    if n == 0 {
+        return "";
+    }
+    // A final newline ends the last line rather than starting another
+    let body = text.strip_suffix('\n').unwrap_or(text);
+    let mut start = body.len();
+    for _ in 0..n {
+        match body[..start].rfind('\n') {
+            Some(newline) => start = newline,
+            None => return text,
+        }
+    }
+    &text[start + 1..]
+}
+
+/// A range of 0-based offsets written `START-END`, `START-` or `-END`
+///
+/// `END` is exclusive, so `100-200` covers offsets 100 through 199 and
+/// `-200` the first 200. Offsets past the end of the text are clamped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OffsetRange {
+    pub start: usize,
+    pub end: Option<usize>,
+}
+
+impl OffsetRange {
+    /// Clamp the range to a text of `len` units
+    fn clamp(&self, len: usize) -> (usize, usize) {
+        let end = self.end.map_or(len, |end| end.min(len));
+        (self.start.min(end), end)
+    }
+}
+
+impl FromStr for OffsetRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let invalid = || {
+            format!(
+                "'{}' is not a range (expected START-END, START- or -END)",
+                s
+            )
+        };
+        let (start, end) = s.split_once('-').ok_or_else(invalid)?;
+        let parse = |n: &str| n.trim().parse::<usize>().map_err(|_| invalid());
+        let range = match (start.trim().is_empty(), end.trim().is_empty()) {
+            (true, true) => return Err(invalid()),
+            (false, true) => OffsetRange {
+                start: parse(start)?,
+                end: None,
+            },
+            (true, false) => OffsetRange {
+                start: 0,
+                end: Some(parse(end)?),
+            },
+            (false, false) => OffsetRange {
+                start: parse(start)?,
+                end: Some(parse(end)?),
+            },
+        };
+        match range.end {
+            Some(end) if end < range.start => Err(format!("range '{}' ends before it starts", s)),
+            _ => Ok(range),
+        }
+    }
+}
+
+impl fmt::Display for OffsetRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.end {
+            Some(end) => write!(f, "{}-{}", self.start, end),
+            None => write!(f, "{}-", self.start),
+        }
+    }
+}
+
+/// The bytes of `text` in `range`, shrunk inwards to UTF-8 boundaries
+///
+/// A range that starts or ends inside a multi-byte character leaves that
+/// character out rather than splitting it.
+pub fn byte_range(text: &str, range: OffsetRange) -> &str {
+    let (mut start, mut end) = range.clamp(text.len());
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    while end > start && !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[start..end.max(start)]
+}
+
+/// The characters (Unicode scalar values) of `text` in `range`
+pub fn char_range(text: &str, range: OffsetRange) -> &str {
+    let offset = |n: usize| text.char_indices().nth(n).map_or(text.len(), |(i, _)| i);
+    let start = offset(range.start);
+    let end = range.end.map_or(text.len(), offset);
+    &text[start..end.max(start)]
+}
+
+/// A range of 1-based line numbers written `START:END`, both inclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl FromStr for LineRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let invalid = || format!("'{}' is not a line range (expected START:END)", s);
+        let (start, end) = s.split_once(':').ok_or_else(invalid)?;
+        let parse = |n: &str| n.trim().parse::<usize>().map_err(|_| invalid());
+        let range = LineRange {
+            start: parse(start)?,
+            end: parse(end)?,
+        };
+        if range.start == 0 {
+            return Err(format!("line range '{}' must start at line 1 or later", s));
+        }
+        if range.end < range.start {
+            return Err(format!("line range '{}' ends before it starts", s));
+        }
+        Ok(range)
+    }
+}
+
+impl fmt::Display for LineRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+/// Lines `range.start` through `range.end` of `text`, with the range
+/// actually covered once `end` is clamped to the last line
+///
+/// `None` if `text` has fewer than `range.start` lines.
+pub fn line_range(text: &str, range: LineRange) -> Option<(&str, LineRange)> {
+    let mut start = None;
+    let mut end = 0;
+    let mut last = 0;
+    for (index, line) in text.split_inclusive('\n').enumerate() {
+        let number = index + 1;
+        if number == range.start {
+            start = Some(end);
+        }
+        end += line.len();
+        last = number;
+        if number == range.end {
+            break;
+        }
+    }
+    let start = start?;
+    Some((
+        &text[start..end],
+        LineRange {
+            start: range.start,
+            end: last,
+        },
+    ))
+}
+
+/// `--head`, `--tail`, `--bytes` or `--chars` as a transform
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Selection {
+    Head(usize),
+    Tail(usize),
+    Bytes(OffsetRange),
+    Chars(OffsetRange),
+}
+
+impl Transform for Selection {
+    fn apply(&self, input: &str) -> Result<String> {
+        Ok(match *self {
+            Selection::Head(n) => head_lines(input, n),
+            Selection::Tail(n) => tail_lines(input, n),
+            Selection::Bytes(range) => byte_range(input, range),
+            Selection::Chars(range) => char_range(input, range),
+        }
+        .to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "one\ntwo\nthree\nfour\n";
+
+    #[test]
+    fn test_head() {
+        assert_eq!(head_lines(TEXT, 1), "one\n");
+        assert_eq!(head_lines(TEXT, 3), "one\ntwo\nthree\n");
+        assert_eq!(head_lines(TEXT, 0), "");
+    }
+
+    #[test]
+    fn test_tail() {
+        assert_eq!(tail_lines(TEXT, 1), "four\n");
+        assert_eq!(tail_lines(TEXT, 3), "two\nthree\nfour\n");
+        assert_eq!(tail_lines(TEXT, 0), "");
+    }
+
+    #[test]
+    fn test_n_larger_than_line_count() {
+        assert_eq!(head_lines(TEXT, 4), TEXT);
+        assert_eq!(head_lines(TEXT, 100), TEXT);
+        assert_eq!(tail_lines(TEXT, 4), TEXT);
+        assert_eq!(tail_lines(TEXT, 100), TEXT);
+        assert_eq!(head_lines("", 5), "");
+        assert_eq!(tail_lines("", 5), "");
+    }
+
+    #[test]
+    fn test_without_final_newline() {
+        let text = "a\nb\nc";
+        assert_eq!(head_lines(text, 2), "a\nb\n");
+        assert_eq!(head_lines(text, 3), "a\nb\nc");
+        assert_eq!(tail_lines(text, 1), "c");
+        assert_eq!(tail_lines(text, 2), "b\nc");
+    }
+
+    #[test]
+    fn test_keeps_crlf_and_blank_lines() {
+        let text = "a\r\n\r\nb\r\n";
+        assert_eq!(head_lines(text, 2), "a\r\n\r\n");
+        assert_eq!(tail_lines(text, 2), "\r\nb\r\n");
+        assert_eq!(tail_lines("x\n\n", 1), "\n");
+    }
+
+    fn range(s: &str) -> OffsetRange {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_range_forms() {
+        assert_eq!(
+            range("100-200"),
+            OffsetRange {
+                start: 100,
+                end: Some(200)
+            }
+        );
+        assert_eq!(
+            range("100-"),
+            OffsetRange {
+                start: 100,
+                end: None
+            }
+        );
+        assert_eq!(
+            range("-200"),
+            OffsetRange {
+                start: 0,
+                end: Some(200)
+            }
+        );
+        assert_eq!(range(" 5 - 5 ").to_string(), "5-5");
+        assert_eq!(range("7-").to_string(), "7-");
+    }
+
+    #[test]
+    fn test_parse_range_invalid() {
+        for bad in ["", "-", "100", "a-b", "1-2-3", "-5-", "10-5"] {
+            assert!(bad.parse::<OffsetRange>().is_err(), "{:?} parsed", bad);
+        }
+    }
+
+    #[test]
+    fn test_byte_range() {
+        assert_eq!(byte_range("hello world", range("0-5")), "hello");
+        assert_eq!(byte_range("hello world", range("6-")), "world");
+        assert_eq!(byte_range("hello world", range("-4")), "hell");
+        assert_eq!(byte_range("hello", range("3-100")), "lo");
+        assert_eq!(byte_range("hello", range("10-")), "");
+        assert_eq!(byte_range("hello", range("2-2")), "");
+    }
+
+    #[test]
+    fn test_byte_range_snaps_to_utf8_boundaries() {
+        // "é" is two bytes (1..3) and "日" three (4..7)
+        let text = "aé b日c";
+        assert_eq!(byte_range(text, range("0-3")), "aé");
+        assert_eq!(byte_range(text, range("0-2")), "a");
+        assert_eq!(byte_range(text, range("2-")), " b日c");
+        assert_eq!(byte_range(text, range("5-6")), "");
+        assert_eq!(byte_range(text, range("4-8")), "b日");
+    }
+
+    #[test]
+    fn test_char_range() {
+        let text = "aé b日c";
+        assert_eq!(char_range(text, range("0-2")), "aé");
+        assert_eq!(char_range(text, range("3-5")), "b日");
+        assert_eq!(char_range(text, range("5-")), "c");
+        assert_eq!(char_range(text, range("-1")), "a");
+        assert_eq!(char_range(text, range("4-100")), "日c");
+        assert_eq!(char_range(text, range("100-")), "");
+    }
+
+    fn lines(s: &str) -> LineRange {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_line_range() {
+        assert_eq!(lines("40:55"), LineRange { start: 40, end: 55 });
+        assert_eq!(lines("3:3"), LineRange { start: 3, end: 3 });
+        assert_eq!(lines("40:55").to_string(), "40-55");
+        for bad in ["", "40", "40-55", ":5", "5:", "a:b", "0:3", "5:4"] {
+            assert!(bad.parse::<LineRange>().is_err(), "{:?} parsed", bad);
+        }
+    }
+
+    #[test]
+    fn test_line_range() {
+        assert_eq!(
+            line_range(TEXT, lines("2:3")),
+            Some(("two\nthree\n", lines("2:3")))
+        );
+        assert_eq!(
+            line_range(TEXT, lines("1:1")),
+            Some(("one\n", lines("1:1")))
+        );
+        assert_eq!(
+            line_range(TEXT, lines("4:4")),
+            Some(("four\n", lines("4:4")))
+        );
+        assert_eq!(
+            line_range("a\r\nb", lines("2:2")),
+            Some(("b", lines("2:2")))
+        );
+    }
+
+    #[test]
+    fn test_line_range_clamps_end() {
+        assert_eq!(
+            line_range(TEXT, lines("3:100")),
+            Some(("three\nfour\n", lines("3:4")))
+        );
+        assert_eq!(
+            line_range("a\nb", lines("1:9")),
+            Some(("a\nb", lines("1:2")))
+        );
+    }
+
+    #[test]
+    fn test_line_range_start_past_end() {
+        assert_eq!(line_range(TEXT, lines("5:6")), None);
+        assert_eq!(line_range("", lines("1:1")), None);
+    }
+
+    #[test]
+    fn test_as_transform() {
+        assert_eq!(Selection::Head(2).apply(TEXT).unwrap(), "one\ntwo\n");
+        assert_eq!(Selection::Tail(2).apply(TEXT).unwrap(), "three\nfour\n");
+        assert_eq!(Selection::Bytes(range("4-7")).apply(TEXT).unwrap(), "two");
+        assert_eq!(
+            Selection::Chars(range("-3")).apply("日本語です").unwrap(),
+            "日本語"
+        );
+    }
+}
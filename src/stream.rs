@@ -0,0 +1,322 @@
+//! Streaming inputs to a writer for `--stream-to`.
+//!
+//! The clipboard needs the whole payload as one string, so copying holds
+//! everything in memory. Streaming instead passes each input through in
+//! fixed-size chunks, so memory use stays flat however big the input is.
+//! The cost is that nothing can look at the payload as a whole: there is no
+//! text extraction, transcoding or transform, only the same binary check
+//! and separators as a normal copy. The exception is `--strip-ansi`, whose
+//! [`AnsiStripper`] works chunk by chunk. UTF-16 input with a byte order
+//! mark passes the binary check as it does for a copy, but is written
+//! undecoded.
+
+use crate::ansi::AnsiStripper;
+use crate::error::{CatboardError, Result};
+use crate::file::{is_binary_within, is_stdin_path, is_utf16, open_file, BINARY_CHECK_SIZE};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Bytes copied per read once the binary check has passed
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// How much of the output is remembered to decide whether the next
+/// separator is already there; longer separators are always written
+const TAIL_SIZE: usize = 256;
+
+/// UTF-8 byte order mark, dropped from the start of each input as a normal
+/// read does
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Writes inputs one after another with a separator at each boundary,
+/// skipping a separator the previous input already ends with
+pub struct StreamJoiner<W: Write> {
+    out: W,
+    destination: PathBuf,
+    tail: Vec<u8>,
+    parts: usize,
+    written: u64,
+    binary_check: usize,
+    ansi: Option<AnsiStripper>,
+}
+
+impl<W: Write> StreamJoiner<W> {
+    pub fn new(out: W) -> Self {
+        Self {
+            out,
+            destination: PathBuf::from("-"),
+            tail: Vec::with_capacity(TAIL_SIZE),
+            parts: 0,
+            written: 0,
+            binary_check: BINARY_CHECK_SIZE,
+            ansi: None,
+        }
+    }
+
+    /// Search the first `bytes` of each input for a null byte instead of
+    /// [`BINARY_CHECK_SIZE`]. With `0` every chunk is searched, so a late
+    /// null byte fails the push after earlier chunks were already written.
+    pub fn binary_check_bytes(mut self, bytes: usize) -> Self {
+        self.binary_check = bytes;
+        self
+    }
+
+    /// Remove escape sequences from each input as it's written. Separators
+    /// are written as they are, and a sequence cut off at the end of one
+    /// input doesn't carry over into the next.
+    pub fn strip_ansi(mut self, strip: bool) -> Self {
+        self.ansi = strip.then(AnsiStripper::new);
+        self
+    }
+
+    /// Report write errors against `path` rather than `-`
+    pub fn destination(mut self, path: &Path) -> Self {
+        self.destination = path.to_path_buf();
+        self
+    }
+
+    /// Total bytes written so far, separators included
+    pub fn written(&self) -> u64 {
+        self.written
+    }
+
+    /// The writer, once every input has been pushed
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+
+    /// Stream the file at `path` (or stdin for `-`) as the next input,
+    /// returning the number of input bytes written
+    ///
+    /// # Errors
+    /// - `FileNotFound` or `PermissionDenied` if the file can't be opened
+    /// - `BinaryFile` if its first chunk contains a null byte and it doesn't
+    ///   start with a UTF-16 byte order mark; nothing of it is written (see
+    ///   [`StreamJoiner::binary_check_bytes`])
+    /// - `IoError` if reading or writing fails part way
+    pub fn push_path(&mut self, path: &Path, separator: &str) -> Result<u64> {
+        if is_stdin_path(path) {
+            self.push(path, io::stdin().lock(), separator)
+        } else {
+            self.push(path, open_file(path)?, separator)
+        }
+    }
+
+    /// Stream `reader` as the next input, reporting errors against `path`
+    pub fn push<R: Read>(&mut self, path: &Path, mut reader: R, separator: &str) -> Result<u64> {
+        let read_err = |source| CatboardError::io(path, source);
+
+        let whole = self.binary_check == 0;
+        let head_size = if whole {
+            BINARY_CHECK_SIZE
+        } else {
+            self.binary_check
+        };
+        let mut head = Vec::with_capacity(head_size.min(CHUNK_SIZE));
+        (&mut reader)
+            .take(head_size as u64)
+            .read_to_end(&mut head)
+            .map_err(read_err)?;
+        if is_binary_within(&head, 0) {
+            return Err(CatboardError::BinaryFile(path.to_path_buf()));
+        }
+        let check_chunks = whole && !is_utf16(&head);
+        let head = head.strip_prefix(UTF8_BOM).unwrap_or(&head);
+
+        if self.parts > 0 && self.written > 0 && !self.tail.ends_with(separator.as_bytes()) {
+            self.write(separator.as_bytes())?;
+        }
+        self.parts += 1;
+        if let Some(stripper) = &mut self.ansi {
+            *stripper = AnsiStripper::new();
+        }
+
+        let mut copied = head.len() as u64;
+        self.write_input(head)?;
+        let mut chunk = vec![0; CHUNK_SIZE];
+        loop {
+            let n = match reader.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(read_err(e)),
+            };
+            if check_chunks && chunk[..n].contains(&0) {
+                return Err(CatboardError::BinaryFile(path.to_path_buf()));
+            }
+            self.write_input(&chunk[..n])?;
+            copied += n as u64;
+        }
+        Ok(copied)
+    }
+
+    /// Write input bytes, stripped of escape sequences if asked
+    fn write_input(&mut self, bytes: &[u8]) -> Result<()> {
+        let Some(stripper) = &mut self.ansi else {
+            return self.write(bytes);
+        };
+        let mut kept = Vec::with_capacity(bytes.len());
+        stripper.push(bytes, &mut kept);
+        self.write(&kept)
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<()> {
+        self.out
+            .write_all(bytes)
+            .map_err(|source| CatboardError::io(&self.destination, source))?;
+        self.written += bytes.len() as u64;
+
+        let keep = bytes.len().min(TAIL_SIZE);
+        let excess = (self.tail.len() + keep).saturating_sub(TAIL_SIZE);
+        self.tail.drain(..excess);
+        self.tail.extend_from_slice(&bytes[bytes.len() - keep..]);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn stream(parts: &[&[u8]], separator: &str) -> Result<Vec<u8>> {
+        let mut joiner = StreamJoiner::new(Vec::new());
+        for part in parts {
+            joiner.push(Path::new("part"), *part, separator)?;
+        }
+        Ok(joiner.into_inner())
+    }
+
+    #[test]
+    fn test_separators_match_a_normal_join() {
+        assert_eq!(stream(&[b"a", b"b"], "\n").unwrap(), b"a\nb");
+        assert_eq!(stream(&[b"a\n", b"b\n"], "\n").unwrap(), b"a\nb\n");
+        assert_eq!(stream(&[b"", b"a"], "\n").unwrap(), b"a");
+        assert_eq!(stream(&[b"a", b"", b"b"], "\n").unwrap(), b"a\nb");
+        assert_eq!(stream(&[b"a---", b"b"], "---").unwrap(), b"a---b");
+        assert_eq!(stream(&[], "\n").unwrap(), b"");
+    }
+
+    #[test]
+    fn test_binary_input_is_rejected_before_writing() {
+        let mut joiner = StreamJoiner::new(Vec::new());
+        joiner.push(Path::new("a"), &b"text"[..], "\n").unwrap();
+        let result = joiner.push(Path::new("b.bin"), &b"\x01\0\x02"[..], "\n");
+        assert!(matches!(result, Err(CatboardError::BinaryFile(_))));
+        assert_eq!(joiner.into_inner(), b"text");
+    }
+
+    #[test]
+    fn test_binary_check_window_is_configurable() {
+        let mut bytes = vec![b'a'; 100];
+        bytes.push(0);
+
+        let mut narrow = StreamJoiner::new(Vec::new()).binary_check_bytes(100);
+        assert!(narrow.push(Path::new("late"), &bytes[..], "\n").is_ok());
+
+        let mut wide = StreamJoiner::new(Vec::new()).binary_check_bytes(101);
+        assert!(matches!(
+            wide.push(Path::new("late"), &bytes[..], "\n"),
+            Err(CatboardError::BinaryFile(_))
+        ));
+    }
+
+    #[test]
+    fn test_binary_check_zero_scans_every_chunk() {
+        let mut bytes = vec![b'a'; CHUNK_SIZE * 2];
+        bytes.push(0);
+
+        let mut default = StreamJoiner::new(Vec::new());
+        assert!(default.push(Path::new("late"), &bytes[..], "\n").is_ok());
+
+        let mut whole = StreamJoiner::new(Vec::new()).binary_check_bytes(0);
+        assert!(matches!(
+            whole.push(Path::new("late"), &bytes[..], "\n"),
+            Err(CatboardError::BinaryFile(_))
+        ));
+    }
+
+    #[test]
+    fn test_large_input_streams_in_chunks() {
+        let big = vec![b'x'; CHUNK_SIZE * 3 + 17];
+        let mut joiner = StreamJoiner::new(Vec::new());
+        let copied = joiner.push(Path::new("big"), &big[..], "\n").unwrap();
+        assert_eq!(copied, big.len() as u64);
+        joiner.push(Path::new("next"), &b"end"[..], "\n").unwrap();
+        assert_eq!(joiner.written(), big.len() as u64 + 4);
+        let out = joiner.into_inner();
+        assert_eq!(&out[..big.len()], &big[..]);
+        assert_eq!(&out[big.len()..], b"\nend");
+    }
+
+    #[test]
+    fn test_strip_ansi_across_chunks() {
+        // A color sequence straddling the first chunk boundary
+        let mut colored = vec![b'x'; BINARY_CHECK_SIZE - 3];
+        colored.extend(b"\x1b[01;31mred\x1b[0m\n");
+        let mut joiner = StreamJoiner::new(Vec::new()).strip_ansi(true);
+        joiner.push(Path::new("a"), &colored[..], "\n").unwrap();
+        // An unfinished sequence doesn't swallow the next input
+        joiner
+            .push(Path::new("b"), &b"cut\x1b[1"[..], "\n")
+            .unwrap();
+        joiner.push(Path::new("c"), &b"next"[..], "\n").unwrap();
+
+        let mut expected = vec![b'x'; BINARY_CHECK_SIZE - 3];
+        expected.extend(b"red\ncut\nnext");
+        assert_eq!(joiner.into_inner(), expected);
+    }
+
+    #[test]
+    fn test_utf8_bom_dropped() {
+        assert_eq!(stream(&[b"\xEF\xBB\xBFa", b"b"], "\n").unwrap(), b"a\nb");
+    }
+
+    #[test]
+    fn test_utf16_input_is_not_binary() {
+        let utf16 = b"\xFF\xFEh\0i\0";
+        assert_eq!(stream(&[utf16], "\n").unwrap(), utf16);
+
+        let mut long = utf16.to_vec();
+        long.extend(b"x\0".repeat(CHUNK_SIZE));
+        let mut whole = StreamJoiner::new(Vec::new()).binary_check_bytes(0);
+        assert!(whole.push(Path::new("utf16"), &long[..], "\n").is_ok());
+    }
+
+    /// Fails every write, as a full disk does
+    struct FullDisk;
+
+    impl Write for FullDisk {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::Other, "no space left"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_write_errors_name_the_destination() {
+        let mut joiner = StreamJoiner::new(FullDisk).destination(Path::new("out.txt"));
+        match joiner.push(Path::new("a"), &b"text"[..], "\n") {
+            Err(CatboardError::IoError { path, .. }) => assert_eq!(path, Path::new("out.txt")),
+            other => panic!("expected an I/O error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_push_path_reads_files() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("a.txt");
+        fs::write(&path, "from disk\n").unwrap();
+
+        let mut joiner = StreamJoiner::new(Vec::new());
+        assert_eq!(joiner.push_path(&path, "\n").unwrap(), 10);
+        assert!(matches!(
+            joiner.push_path(&dir.path().join("missing"), "\n"),
+            Err(CatboardError::FileNotFound(_))
+        ));
+        assert_eq!(joiner.into_inner(), b"from disk\n");
+    }
+}
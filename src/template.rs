@@ -0,0 +1,197 @@
+//! Placeholder substitution for `--template`.
+//!
+//! Supported placeholders:
+//! - `{filename}` - the file's path as given (or the stdin label)
+//! - `{content}` - the file's contents
+//! - `{date}` - today's date as `YYYY-MM-DD` (UTC)
+//! - `{bytes}` - content length in bytes
+//! - `{lines}` - number of lines in the content
+//!
+//! Write `{{` and `}}` for literal braces. Placeholders are expanded in a
+//! single pass, so braces inside substituted values are never re-expanded.
+
+use crate::error::{CatboardError, Result};
+use crate::transform::Transform;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Values available to a template for one file
+#[derive(Debug, Clone)]
+pub struct TemplateValues<'a> {
+    pub filename: &'a str,
+    pub content: &'a str,
+    pub date: &'a str,
+}
+
+/// Transform that renders a template around its input as `{content}`
+#[derive(Debug, Clone)]
+pub struct Template {
+    pub template: String,
+    pub filename: String,
+    pub date: String,
+}
+
+impl Transform for Template {
+    fn apply(&self, input: &str) -> Result<String> {
+        render_template(
+            &self.template,
+            &TemplateValues {
+                filename: &self.filename,
+                content: input,
+                date: &self.date,
+            },
+        )
+    }
+}
+
+/// Expand the placeholders in `template`.
+///
+/// # Errors
+/// - `TemplateError` for unknown placeholders or unbalanced braces
+pub fn render_template(template: &str, values: &TemplateValues) -> Result<String> {
+    let mut output = String::with_capacity(template.len() + values.content.len());
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                output.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                output.push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => name.push(c),
+                        None => {
+                            return Err(CatboardError::TemplateError(format!(
+                                "unclosed placeholder '{{{}'",
+                                name
+                            )))
+                        }
+                    }
+                }
+                match name.as_str() {
+                    "filename" => output.push_str(values.filename),
+                    "content" => output.push_str(values.content),
+                    "date" => output.push_str(values.date),
+                    "bytes" => output.push_str(&values.content.len().to_string()),
+                    "lines" => output.push_str(&values.content.lines().count().to_string()),
+                    _ => {
+                        return Err(CatboardError::TemplateError(format!(
+                            "unknown placeholder '{{{}}}'",
+                            name
+                        )))
+                    }
+                }
+            }
+            '}' => {
+                return Err(CatboardError::TemplateError(
+                    "unmatched '}' (use '}}' for a literal brace)".to_string(),
+                ))
+            }
+            _ => output.push(c),
+        }
+    }
+
+    Ok(output)
+}
+
+/// Format a point in time as a `YYYY-MM-DD` UTC date
+pub fn utc_date(time: SystemTime) -> String {
+    let days = time
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() / 86_400)
+        .unwrap_or(0) as i64;
+    let (year, month, day) = civil_from_days(days);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// The UTC `(year, month, day)` of a count of days since the Unix epoch
+pub(crate) fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    // Civil-from-days conversion (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn values(content: &str) -> TemplateValues<'_> {
+        TemplateValues {
+            filename: "src/main.rs",
+            content,
+            date: "2024-03-01",
+        }
+    }
+
+    #[test]
+    fn test_render_all_placeholders() {
+        let result = render_template(
+            "// {filename} ({bytes} bytes, {lines} lines) {date}\n{content}",
+            &values("fn main() {}\n"),
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            "// src/main.rs (13 bytes, 1 lines) 2024-03-01\nfn main() {}\n"
+        );
+    }
+
+    #[test]
+    fn test_render_escaped_braces() {
+        let result = render_template("{{filename}} is {filename}", &values("")).unwrap();
+        assert_eq!(result, "{filename} is src/main.rs");
+    }
+
+    #[test]
+    fn test_render_does_not_expand_substituted_values() {
+        let result = render_template("{content}", &values("{filename}")).unwrap();
+        assert_eq!(result, "{filename}");
+    }
+
+    #[test]
+    fn test_render_unknown_placeholder() {
+        let result = render_template("{author}", &values(""));
+        assert!(matches!(result, Err(CatboardError::TemplateError(_))));
+    }
+
+    #[test]
+    fn test_render_unbalanced_braces() {
+        assert!(render_template("{content", &values("")).is_err());
+        assert!(render_template("content}", &values("")).is_err());
+    }
+
+    #[test]
+    fn test_utc_date() {
+        assert_eq!(utc_date(UNIX_EPOCH), "1970-01-01");
+        // 2024-02-29 12:00:00 UTC
+        let leap_day = UNIX_EPOCH + Duration::from_secs(1_709_208_000);
+        assert_eq!(utc_date(leap_day), "2024-02-29");
+    }
+
+    #[test]
+    fn test_template_transform() {
+        let transform = Template {
+            template: "{filename}: {content}".to_string(),
+            filename: "a.txt".to_string(),
+            date: "2024-01-01".to_string(),
+        };
+        assert_eq!(transform.apply("hi").unwrap(), "a.txt: hi");
+    }
+}
@@ -0,0 +1,155 @@
+//! Composable text transforms applied before copying.
+//!
+//! The CLI builds its pipelines from the enabled flags in a fixed,
+//! canonical order:
+//!
+//! 1. Per file, after extraction and `--line-range`:
+//!    1. filename comment (`--include-filename-comment`)
+//!    2. template (`--template`)
+//! 2. Once, on the joined payload:
+//!    1. escape sequence removal (`--strip-ansi`)
+//!    2. environment expansion (`--expand-env`)
+//!    3. match counting (`--count-matches`, which reports without changing
+//!       the text)
+//!    4. line filtering (`--grep`)
+//!    5. selection (`--head`, `--tail`, `--bytes` or `--chars`)
+//!    6. JSON reformatting (`--json-pretty` or `--json-minify`)
+//!    7. Unicode normalization (`--normalize`)
+//!    8. number formatting (`--reformat-numbers`)
+//!    9. redaction (`--redact`)
+//!    10. column truncation (`--truncate-cols`)
+//!    11. URL encoding (`--url-encode` or `--url-decode`)
+//!    12. quoting (`--shell-quote` or `--json-string`)
+//!    13. trailing newline (`--ensure-trailing-newline` or
+//!        `--strip-trailing-newline`)
+//!
+//! Secret scanning (`--scan-secrets`) runs after every transform and only
+//! inspects the payload. Library users can assemble their own pipelines
+//! from the same pieces with [`apply_transforms`].
+
+use crate::error::Result;
+
+/// A single step that rewrites text
+pub trait Transform {
+    fn apply(&self, input: &str) -> Result<String>;
+}
+
+/// Any `Fn(&str) -> Result<String>` closure is a transform
+impl<F> Transform for F
+where
+    F: Fn(&str) -> Result<String>,
+{
+    fn apply(&self, input: &str) -> Result<String> {
+        self(input)
+    }
+}
+
+/// Run `text` through `transforms` in order, stopping at the first error
+pub fn apply_transforms(text: &str, transforms: &[Box<dyn Transform + '_>]) -> Result<String> {
+    apply_transforms_owned(text.to_string(), transforms)
+}
+
+/// Like [`apply_transforms`], taking ownership of `text` so an empty
+/// pipeline returns it without a copy
+pub fn apply_transforms_owned(
+    text: String,
+    transforms: &[Box<dyn Transform + '_>],
+) -> Result<String> {
+    transforms
+        .iter()
+        .try_fold(text, |text, transform| transform.apply(&text))
+}
+
+/// Make the payload end, or not end, with a line ending
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrailingNewline {
+    /// Append `\n` unless the text already ends with one (empty text is left alone)
+    Ensure,
+    /// Remove a single trailing `\n` or `\r\n`
+    Strip,
+}
+
+impl Transform for TrailingNewline {
+    fn apply(&self, input: &str) -> Result<String> {
+        let output = match self {
+            TrailingNewline::Ensure if !input.is_empty() && !input.ends_with('\n') => {
+                format!("{}\n", input)
+            }
+            TrailingNewline::Strip => input
+                .strip_suffix('\n')
+                .map(|rest| rest.strip_suffix('\r').unwrap_or(rest))
+                .unwrap_or(input)
+                .to_string(),
+            _ => input.to_string(),
+        };
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::CatboardError;
+
+    #[test]
+    fn test_empty_pipeline_is_identity() {
+        assert_eq!(apply_transforms("same", &[]).unwrap(), "same");
+    }
+
+    #[test]
+    fn test_owned_empty_pipeline_keeps_allocation() {
+        let text = String::from("payload");
+        let ptr = text.as_ptr();
+        let result = apply_transforms_owned(text, &[]).unwrap();
+        assert_eq!(result, "payload");
+        assert_eq!(result.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn test_transforms_run_in_order() {
+        let transforms: Vec<Box<dyn Transform>> = vec![
+            Box::new(|s: &str| Ok(format!("{}a", s))),
+            Box::new(|s: &str| Ok(s.to_uppercase())),
+            Box::new(|s: &str| Ok(format!("{}b", s))),
+        ];
+        assert_eq!(apply_transforms("x", &transforms).unwrap(), "XAb");
+    }
+
+    #[test]
+    fn test_error_stops_pipeline() {
+        let transforms: Vec<Box<dyn Transform>> = vec![
+            Box::new(|_: &str| Err(CatboardError::TemplateError("boom".to_string()))),
+            Box::new(|_: &str| -> Result<String> { panic!("must not run") }),
+        ];
+        assert!(matches!(
+            apply_transforms("x", &transforms),
+            Err(CatboardError::TemplateError(_))
+        ));
+    }
+
+    #[test]
+    fn test_ensure_trailing_newline_appends_when_missing() {
+        let ensure = TrailingNewline::Ensure;
+        assert_eq!(ensure.apply("line").unwrap(), "line\n");
+        assert_eq!(ensure.apply("a\nb").unwrap(), "a\nb\n");
+    }
+
+    #[test]
+    fn test_ensure_trailing_newline_keeps_existing() {
+        let ensure = TrailingNewline::Ensure;
+        assert_eq!(ensure.apply("line\n").unwrap(), "line\n");
+        assert_eq!(ensure.apply("line\r\n").unwrap(), "line\r\n");
+        assert_eq!(ensure.apply("line\n\n").unwrap(), "line\n\n");
+        assert_eq!(ensure.apply("").unwrap(), "");
+    }
+
+    #[test]
+    fn test_strip_trailing_newline_removes_one() {
+        let strip = TrailingNewline::Strip;
+        assert_eq!(strip.apply("line\n").unwrap(), "line");
+        assert_eq!(strip.apply("line\r\n").unwrap(), "line");
+        assert_eq!(strip.apply("line\n\n").unwrap(), "line\n");
+        assert_eq!(strip.apply("line").unwrap(), "line");
+        assert_eq!(strip.apply("line\r").unwrap(), "line\r");
+    }
+}
@@ -0,0 +1,185 @@
+//! Percent-encoding for `--url-encode` and `--url-decode`.
+
+use crate::error::{CatboardError, Result};
+use crate::transform::Transform;
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use std::fmt;
+use std::str::FromStr;
+
+/// Everything except RFC 3986 unreserved characters, like JavaScript's
+/// `encodeURIComponent`
+const COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// Like JavaScript's `encodeURI`: URL delimiters such as `/`, `?` and `&`
+/// are kept so a whole URL stays usable
+const FULL: &AsciiSet = &COMPONENT
+    .remove(b';')
+    .remove(b',')
+    .remove(b'/')
+    .remove(b'?')
+    .remove(b':')
+    .remove(b'@')
+    .remove(b'&')
+    .remove(b'=')
+    .remove(b'+')
+    .remove(b'$')
+    .remove(b'!')
+    .remove(b'*')
+    .remove(b'\'')
+    .remove(b'(')
+    .remove(b')')
+    .remove(b'#');
+
+/// Which characters `--url-encode` escapes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncodeSet {
+    /// Escape everything but unreserved characters (for a query value or path segment)
+    Component,
+    /// Keep URL delimiters (for a whole URL)
+    Full,
+}
+
+impl fmt::Display for EncodeSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            EncodeSet::Component => "component",
+            EncodeSet::Full => "full",
+        })
+    }
+}
+
+impl FromStr for EncodeSet {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "component" => Ok(EncodeSet::Component),
+            "full" => Ok(EncodeSet::Full),
+            _ => Err(format!(
+                "unknown encode set '{}' (expected component or full)",
+                s
+            )),
+        }
+    }
+}
+
+/// Percent-encode `text` as UTF-8
+pub fn url_encode(text: &str, set: EncodeSet) -> String {
+    let ascii_set = match set {
+        EncodeSet::Component => COMPONENT,
+        EncodeSet::Full => FULL,
+    };
+    utf8_percent_encode(text, ascii_set).to_string()
+}
+
+/// Decode `%XX` escapes; fails if the result is not valid UTF-8.
+///
+/// `+` is left alone, since it only means a space in form bodies.
+pub fn url_decode(text: &str) -> Result<String> {
+    percent_decode_str(text)
+        .decode_utf8()
+        .map(|decoded| decoded.into_owned())
+        .map_err(|e| CatboardError::TransformError {
+            name: "url-decode",
+            message: e.to_string(),
+        })
+}
+
+/// `--url-encode` as a transform
+#[derive(Debug, Clone, Copy)]
+pub struct UrlEncode(pub EncodeSet);
+
+impl Transform for UrlEncode {
+    fn apply(&self, input: &str) -> Result<String> {
+        Ok(url_encode(input, self.0))
+    }
+}
+
+/// `--url-decode` as a transform
+#[derive(Debug, Clone, Copy)]
+pub struct UrlDecode;
+
+impl Transform for UrlDecode {
+    fn apply(&self, input: &str) -> Result<String> {
+        url_decode(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_component_escapes_reserved_characters() {
+        assert_eq!(
+            url_encode("a b&c=d/e?f#g", EncodeSet::Component),
+            "a%20b%26c%3Dd%2Fe%3Ff%23g"
+        );
+        assert_eq!(url_encode("-._~", EncodeSet::Component), "-._~");
+        assert_eq!(url_encode("100%", EncodeSet::Component), "100%25");
+    }
+
+    #[test]
+    fn test_full_keeps_url_delimiters() {
+        assert_eq!(
+            url_encode("https://x.io/a b?q=1&r=[2]#top", EncodeSet::Full),
+            "https://x.io/a%20b?q=1&r=%5B2%5D#top"
+        );
+    }
+
+    #[test]
+    fn test_encodes_unicode_and_newlines_as_utf8() {
+        assert_eq!(
+            url_encode("caf\u{E9}\n", EncodeSet::Component),
+            "caf%C3%A9%0A"
+        );
+        assert_eq!(url_encode("caf\u{E9}\n", EncodeSet::Full), "caf%C3%A9%0A");
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(url_decode("a%20b%2Fc").unwrap(), "a b/c");
+        assert_eq!(url_decode("caf%C3%A9").unwrap(), "caf\u{E9}");
+        assert_eq!(url_decode("a+b").unwrap(), "a+b");
+        // Malformed escapes pass through unchanged
+        assert_eq!(url_decode("100%").unwrap(), "100%");
+    }
+
+    #[test]
+    fn test_decode_invalid_utf8() {
+        assert!(matches!(
+            url_decode("%FF"),
+            Err(CatboardError::TransformError {
+                name: "url-decode",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let text = "key=va lue&x=/path?#frag\n\u{1F600} 100% \"quoted\"";
+        for set in [EncodeSet::Component, EncodeSet::Full] {
+            assert_eq!(url_decode(&url_encode(text, set)).unwrap(), text);
+        }
+    }
+
+    #[test]
+    fn test_parse_encode_set() {
+        assert_eq!("component".parse(), Ok(EncodeSet::Component));
+        assert_eq!("FULL".parse(), Ok(EncodeSet::Full));
+        assert!("path".parse::<EncodeSet>().is_err());
+        assert_eq!(EncodeSet::Full.to_string(), "full");
+    }
+
+    #[test]
+    fn test_as_transforms() {
+        let encoded = UrlEncode(EncodeSet::Component).apply("a b").unwrap();
+        assert_eq!(encoded, "a%20b");
+        assert_eq!(UrlDecode.apply(&encoded).unwrap(), "a b");
+    }
+}
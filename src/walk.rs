@@ -0,0 +1,467 @@
+//! Recursive directory expansion for copying whole directories.
+//!
+//! Entries are visited in sorted order so the combined clipboard content is
+//! deterministic across runs and platforms.
+
+use crate::cancel::{self, CancellationToken};
+use crate::error::{CatboardError, Result, ResultExt};
+use ignore::WalkBuilder;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// How symlinks found while walking a directory are treated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SymlinkPolicy {
+    /// Follow symlinks to files and directories wherever they point
+    Follow,
+    /// Read symlinked files only if they resolve inside the walked root;
+    /// never descend into symlinked directories
+    #[default]
+    NoFollow,
+}
+
+/// Case-insensitive allow/deny lists of file extensions
+#[derive(Debug, Clone, Default)]
+pub struct ExtensionFilter {
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl ExtensionFilter {
+    /// Create a filter; an empty `include` list allows every extension.
+    ///
+    /// Extensions may be given with or without a leading dot.
+    pub fn new<I, E>(include: I, exclude: E) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+        E: IntoIterator,
+        E::Item: AsRef<str>,
+    {
+        fn normalize<T: AsRef<str>>(ext: T) -> String {
+            ext.as_ref().trim().trim_start_matches('.').to_lowercase()
+        }
+        Self {
+            include: include.into_iter().map(normalize).collect(),
+            exclude: exclude.into_iter().map(normalize).collect(),
+        }
+    }
+
+    /// Check if a path passes the filter, based on its extension alone
+    pub fn matches(&self, path: &Path) -> bool {
+        let ext = path.extension().map(|e| e.to_string_lossy().to_lowercase());
+
+        let included = self.include.is_empty()
+            || ext
+                .as_ref()
+                .map(|e| self.include.contains(e))
+                .unwrap_or(false);
+        let excluded = ext
+            .as_ref()
+            .map(|e| self.exclude.contains(e))
+            .unwrap_or(false);
+
+        included && !excluded
+    }
+}
+
+/// Options controlling which files a directory walk yields
+#[derive(Debug, Clone)]
+pub struct WalkOptions {
+    pub symlinks: SymlinkPolicy,
+    pub extensions: ExtensionFilter,
+    /// Skip paths matched by `.gitignore`, `.ignore` and git exclude files,
+    /// as well as the `.git` directory itself
+    pub gitignore: bool,
+    /// Checked before each entry; once cancelled, the walk stops with
+    /// `Cancelled`
+    pub cancel: Option<CancellationToken>,
+}
+
+impl Default for WalkOptions {
+    fn default() -> Self {
+        Self {
+            symlinks: SymlinkPolicy::default(),
+            extensions: ExtensionFilter::default(),
+            gitignore: true,
+            cancel: None,
+        }
+    }
+}
+
+/// Recursively collect the files under `root` in sorted order.
+///
+/// Ignore files are applied first, so extension filters only see paths that
+/// survived them. Directory cycles created by followed symlinks are visited
+/// only once, and broken symlinks are skipped.
+///
+/// # Errors
+/// - `FileNotFound` if `root` doesn't exist
+/// - `PermissionDenied` if a directory can't be listed
+/// - `IoError` for other I/O failures
+/// - `Cancelled` if [`WalkOptions::cancel`] is cancelled part way
+pub fn walk_dir<P: AsRef<Path>>(root: P, options: &WalkOptions) -> Result<Vec<PathBuf>> {
+    walk_dir_with_progress(root, options, None)
+}
+
+/// Like [`walk_dir`], calling `progress` with the number of files found so
+/// far each time one is added
+pub fn walk_dir_with_progress<P: AsRef<Path>>(
+    root: P,
+    options: &WalkOptions,
+    mut progress: Option<&mut dyn FnMut(usize)>,
+) -> Result<Vec<PathBuf>> {
+    let root = root.as_ref();
+    let canonical_root = root.canonicalize().with_path(root)?;
+
+    let mut builder = WalkBuilder::new(root);
+    builder
+        .standard_filters(options.gitignore)
+        .hidden(false)
+        .require_git(false)
+        .follow_links(options.symlinks == SymlinkPolicy::Follow)
+        .sort_by_file_name(|a, b| a.cmp(b));
+    if options.gitignore {
+        builder.filter_entry(|entry| entry.file_name() != ".git");
+    }
+
+    let mut files = Vec::new();
+    for entry in builder.build() {
+        cancel::check(options.cancel.as_ref(), || {
+            format!("walking '{}' after {} file(s)", root.display(), files.len())
+        })?;
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => match e.into_io_error() {
+                // Broken symlinks vanish from the listing
+                Some(io) if io.kind() == io::ErrorKind::NotFound => continue,
+                Some(io) => return Err(CatboardError::from_io(root, io)),
+                // Symlink loops and malformed ignore files are not fatal
+                None => continue,
+            },
+        };
+
+        let path = entry.path();
+        let Some(file_type) = entry.file_type() else {
+            continue;
+        };
+
+        if file_type.is_dir() || !options.extensions.matches(path) {
+            continue;
+        }
+
+        // Unfollowed symlinks are only read when they stay inside the root
+        if file_type.is_symlink() && !symlink_stays_inside(path, &canonical_root) {
+            continue;
+        }
+
+        files.push(entry.into_path());
+        if let Some(report) = progress.as_mut() {
+            report(files.len());
+        }
+    }
+
+    Ok(files)
+}
+
+/// Whether `path` was last modified at or after `since`
+///
+/// # Errors
+/// - `FileNotFound`, `PermissionDenied` or `IoError` if the modification
+///   time can't be read
+pub fn modified_since(path: &Path, since: SystemTime) -> Result<bool> {
+    let modified = fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .with_path(path)?;
+    Ok(modified >= since)
+}
+
+/// Check if a symlink resolves to a file inside `root`
+fn symlink_stays_inside(link: &Path, root: &Path) -> bool {
+    match link.canonicalize() {
+        Ok(target) => target.starts_with(root) && target.is_file(),
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::CatboardError;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn file_names(files: &[PathBuf], root: &Path) -> Vec<String> {
+        files
+            .iter()
+            .map(|p| p.strip_prefix(root).unwrap().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_walk_dir_sorted_and_recursive() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("sub")).unwrap();
+        fs::write(dir.path().join("b.txt"), "b").unwrap();
+        fs::write(dir.path().join("a.txt"), "a").unwrap();
+        fs::write(dir.path().join("sub").join("c.txt"), "c").unwrap();
+
+        let files = walk_dir(dir.path(), &WalkOptions::default()).unwrap();
+        let sub_c = Path::new("sub").join("c.txt");
+        assert_eq!(
+            file_names(&files, dir.path()),
+            vec!["a.txt", "b.txt", sub_c.to_str().unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_extension_filter_include_and_exclude() {
+        let filter = ExtensionFilter::new(["rs", ".MD"], ["md"]);
+        assert!(filter.matches(Path::new("main.rs")));
+        assert!(filter.matches(Path::new("MAIN.RS")));
+        assert!(!filter.matches(Path::new("README.md")));
+        assert!(!filter.matches(Path::new("notes.txt")));
+        assert!(!filter.matches(Path::new("Makefile")));
+
+        let filter = ExtensionFilter::new(Vec::<String>::new(), ["lock"]);
+        assert!(filter.matches(Path::new("Makefile")));
+        assert!(filter.matches(Path::new("Cargo.toml")));
+        assert!(!filter.matches(Path::new("Cargo.lock")));
+    }
+
+    #[test]
+    fn test_walk_dir_cancelled_mid_walk() {
+        let dir = TempDir::new().unwrap();
+        for name in ["a.txt", "b.txt", "c.txt", "d.txt"] {
+            fs::write(dir.path().join(name), name).unwrap();
+        }
+        let token = CancellationToken::new();
+        let options = WalkOptions {
+            cancel: Some(token.clone()),
+            ..Default::default()
+        };
+
+        let mut seen = 0;
+        let mut cancel_after_two = |found| {
+            seen = found;
+            if found == 2 {
+                token.cancel();
+            }
+        };
+        let err =
+            walk_dir_with_progress(dir.path(), &options, Some(&mut cancel_after_two)).unwrap_err();
+        assert_eq!(seen, 2);
+        assert!(matches!(
+            &err,
+            CatboardError::Cancelled(msg) if msg.ends_with("after 2 file(s)")
+        ));
+    }
+
+    #[test]
+    fn test_walk_dir_cancelled_before_start() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("a.txt"), "a").unwrap();
+        let token = CancellationToken::new();
+        token.cancel();
+        let options = WalkOptions {
+            cancel: Some(token),
+            ..Default::default()
+        };
+        assert!(matches!(
+            walk_dir(dir.path(), &options),
+            Err(CatboardError::Cancelled(_))
+        ));
+    }
+
+    #[test]
+    fn test_walk_dir_progress_counts_files() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("sub")).unwrap();
+        fs::write(dir.path().join("a.txt"), "a").unwrap();
+        fs::write(dir.path().join("sub").join("b.txt"), "b").unwrap();
+
+        let mut counts = Vec::new();
+        let mut record = |found| counts.push(found);
+        let files =
+            walk_dir_with_progress(dir.path(), &WalkOptions::default(), Some(&mut record)).unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(counts, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_walk_dir_filters_extensions() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("sub")).unwrap();
+        fs::write(dir.path().join("main.RS"), "fn main() {}").unwrap();
+        fs::write(dir.path().join("notes.txt"), "notes").unwrap();
+        fs::write(dir.path().join("sub").join("lib.rs"), "").unwrap();
+        // Excluded binary files are skipped before anything reads them
+        fs::write(dir.path().join("image.png"), [0x89, 0x00]).unwrap();
+
+        let options = WalkOptions {
+            extensions: ExtensionFilter::new(["rs"], Vec::<String>::new()),
+            ..Default::default()
+        };
+        let files = walk_dir(dir.path(), &options).unwrap();
+        let sub_lib = Path::new("sub").join("lib.rs");
+        assert_eq!(
+            file_names(&files, dir.path()),
+            vec!["main.RS", sub_lib.to_str().unwrap()]
+        );
+
+        let options = WalkOptions {
+            extensions: ExtensionFilter::new(Vec::<String>::new(), ["png", "rs"]),
+            ..Default::default()
+        };
+        let files = walk_dir(dir.path(), &options).unwrap();
+        assert_eq!(file_names(&files, dir.path()), vec!["notes.txt"]);
+    }
+
+    #[test]
+    fn test_walk_dir_respects_gitignore() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("target").join("debug")).unwrap();
+        fs::create_dir(dir.path().join(".git")).unwrap();
+        fs::write(dir.path().join(".gitignore"), "target/\n*.log\n").unwrap();
+        fs::write(dir.path().join("main.rs"), "fn main() {}").unwrap();
+        fs::write(dir.path().join("debug.log"), "log").unwrap();
+        fs::write(dir.path().join("target").join("debug").join("out"), "bin").unwrap();
+        fs::write(dir.path().join(".git").join("HEAD"), "ref").unwrap();
+
+        let files = walk_dir(dir.path(), &WalkOptions::default()).unwrap();
+        assert_eq!(
+            file_names(&files, dir.path()),
+            vec![".gitignore", "main.rs"]
+        );
+
+        // Ignore files apply before extension filters
+        let options = WalkOptions {
+            extensions: ExtensionFilter::new(["log", "rs"], Vec::<String>::new()),
+            ..Default::default()
+        };
+        let files = walk_dir(dir.path(), &options).unwrap();
+        assert_eq!(file_names(&files, dir.path()), vec!["main.rs"]);
+
+        let options = WalkOptions {
+            gitignore: false,
+            ..Default::default()
+        };
+        let files = walk_dir(dir.path(), &options).unwrap();
+        let git_head = Path::new(".git").join("HEAD");
+        let target_out = Path::new("target").join("debug").join("out");
+        assert_eq!(
+            file_names(&files, dir.path()),
+            vec![
+                git_head.to_str().unwrap(),
+                ".gitignore",
+                "debug.log",
+                "main.rs",
+                target_out.to_str().unwrap()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_walk_dir_not_found() {
+        let result = walk_dir("/nonexistent/dir", &WalkOptions::default());
+        assert!(matches!(result, Err(CatboardError::FileNotFound(_))));
+    }
+
+    #[cfg(unix)]
+    mod symlinks {
+        use super::*;
+        use std::os::unix::fs::symlink;
+
+        /// Root with an inside link, an outside link, a linked dir and a broken link
+        fn setup() -> (TempDir, TempDir) {
+            let root = TempDir::new().unwrap();
+            let outside = TempDir::new().unwrap();
+            fs::write(root.path().join("real.txt"), "real").unwrap();
+            fs::write(outside.path().join("secret.txt"), "secret").unwrap();
+
+            symlink(
+                root.path().join("real.txt"),
+                root.path().join("inside_link"),
+            )
+            .unwrap();
+            symlink(
+                outside.path().join("secret.txt"),
+                root.path().join("outside_link"),
+            )
+            .unwrap();
+            symlink(outside.path(), root.path().join("outside_dir")).unwrap();
+            symlink(root.path().join("missing"), root.path().join("broken_link")).unwrap();
+            (root, outside)
+        }
+
+        #[test]
+        fn test_no_follow_skips_links_outside_root() {
+            let (root, _outside) = setup();
+            let options = WalkOptions {
+                symlinks: SymlinkPolicy::NoFollow,
+                ..Default::default()
+            };
+
+            let files = walk_dir(root.path(), &options).unwrap();
+            assert_eq!(
+                file_names(&files, root.path()),
+                vec!["inside_link", "real.txt"]
+            );
+        }
+
+        #[test]
+        fn test_follow_reads_links_outside_root() {
+            let (root, _outside) = setup();
+            let options = WalkOptions {
+                symlinks: SymlinkPolicy::Follow,
+                ..Default::default()
+            };
+
+            let files = walk_dir(root.path(), &options).unwrap();
+            let linked_secret = Path::new("outside_dir").join("secret.txt");
+            assert_eq!(
+                file_names(&files, root.path()),
+                vec![
+                    "inside_link",
+                    linked_secret.to_str().unwrap(),
+                    "outside_link",
+                    "real.txt"
+                ]
+            );
+        }
+
+        #[test]
+        fn test_follow_survives_directory_cycle() {
+            let root = TempDir::new().unwrap();
+            fs::write(root.path().join("a.txt"), "a").unwrap();
+            symlink(root.path(), root.path().join("loop")).unwrap();
+
+            let options = WalkOptions {
+                symlinks: SymlinkPolicy::Follow,
+                ..Default::default()
+            };
+            let files = walk_dir(root.path(), &options).unwrap();
+            assert_eq!(file_names(&files, root.path()), vec!["a.txt"]);
+        }
+    }
+
+    #[test]
+    fn test_modified_since() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("app.log");
+        fs::write(&path, "log").unwrap();
+        let hour_ago = SystemTime::now() - std::time::Duration::from_secs(3600);
+        filetime::set_file_mtime(&path, filetime::FileTime::from_system_time(hour_ago)).unwrap();
+
+        let two_hours_ago = hour_ago - std::time::Duration::from_secs(3600);
+        assert!(modified_since(&path, two_hours_ago).unwrap());
+        assert!(modified_since(&path, hour_ago).unwrap());
+        assert!(!modified_since(&path, SystemTime::now()).unwrap());
+        assert!(matches!(
+            modified_since(&dir.path().join("missing"), hour_ago),
+            Err(CatboardError::FileNotFound(_))
+        ));
+    }
+}
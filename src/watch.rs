@@ -0,0 +1,230 @@
+//! File watching for re-copying files whenever they change.
+//!
+//! Parent directories are watched rather than the files themselves, so
+//! editors that save by writing a temp file and renaming it over the
+//! original are still picked up.
+
+use crate::cancel::CancellationToken;
+use crate::error::{CatboardError, Result};
+use notify::{Event, RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::Duration;
+
+/// Default quiet period that collapses a burst of writes into one change
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// How often a cancellable watch checks its token between events
+const CANCEL_POLL: Duration = Duration::from_millis(100);
+
+/// How [`watch_files_with`] watches
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    pub debounce: Duration,
+    /// Checked between events; once cancelled, watching stops cleanly
+    pub cancel: Option<CancellationToken>,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        Self {
+            debounce: DEFAULT_DEBOUNCE,
+            cancel: None,
+        }
+    }
+}
+
+/// Watch files and call `on_change` after each (debounced) modification.
+///
+/// Blocks until `on_change` returns `ControlFlow::Break`, or forever
+/// otherwise. Events arriving within `debounce` of each other are collapsed
+/// into a single call.
+///
+/// # Errors
+/// - `FileNotFound` if a watched file doesn't exist
+/// - `WatchError` if the platform watcher fails
+pub fn watch_files<F>(paths: &[PathBuf], debounce: Duration, on_change: F) -> Result<()>
+where
+    F: FnMut() -> ControlFlow<()>,
+{
+    let options = WatchOptions {
+        debounce,
+        cancel: None,
+    };
+    watch_files_with(paths, &options, on_change)
+}
+
+/// [`watch_files`] that also returns `Ok` once `options.cancel` is
+/// cancelled, within a fraction of a second
+///
+/// # Errors
+/// - `FileNotFound` if a watched file doesn't exist
+/// - `WatchError` if the platform watcher fails
+pub fn watch_files_with<F>(
+    paths: &[PathBuf],
+    options: &WatchOptions,
+    mut on_change: F,
+) -> Result<()>
+where
+    F: FnMut() -> ControlFlow<()>,
+{
+    let targets = paths
+        .iter()
+        .map(|p| {
+            p.canonicalize()
+                .map_err(|_| CatboardError::FileNotFound(p.clone()))
+        })
+        .collect::<Result<HashSet<_>>>()?;
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher =
+        notify::recommended_watcher(tx).map_err(|e| CatboardError::WatchError(e.to_string()))?;
+
+    let dirs: HashSet<&Path> = targets.iter().filter_map(|p| p.parent()).collect();
+    for dir in dirs {
+        watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .map_err(|e| CatboardError::WatchError(e.to_string()))?;
+    }
+
+    let cancelled = || {
+        options
+            .cancel
+            .as_ref()
+            .is_some_and(|token| token.is_cancelled())
+    };
+    loop {
+        let event = if options.cancel.is_some() {
+            match rx.recv_timeout(CANCEL_POLL) {
+                Ok(event) => event,
+                Err(RecvTimeoutError::Timeout) if cancelled() => return Ok(()),
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(e) => return Err(CatboardError::WatchError(e.to_string())),
+            }
+        } else {
+            rx.recv()
+                .map_err(|e| CatboardError::WatchError(e.to_string()))?
+        };
+        if !is_relevant(&event, &targets) {
+            continue;
+        }
+
+        // Drain the rest of the burst before re-copying
+        while rx.recv_timeout(options.debounce).is_ok() {}
+        if cancelled() {
+            return Ok(());
+        }
+
+        if on_change().is_break() {
+            return Ok(());
+        }
+    }
+}
+
+/// Check if an event modifies or creates one of the watched files
+fn is_relevant(event: &notify::Result<Event>, targets: &HashSet<PathBuf>) -> bool {
+    match event {
+        Ok(event) => {
+            (event.kind.is_modify() || event.kind.is_create())
+                && event.paths.iter().any(|p| targets.contains(p))
+        }
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{AccessKind, EventKind, ModifyKind};
+    use std::fs;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_is_relevant_filters_paths_and_kinds() {
+        let target = PathBuf::from("/watched/file.txt");
+        let targets = HashSet::from([target.clone()]);
+
+        let modify = Event::new(EventKind::Modify(ModifyKind::Any)).add_path(target.clone());
+        assert!(is_relevant(&Ok(modify), &targets));
+
+        let other =
+            Event::new(EventKind::Modify(ModifyKind::Any)).add_path("/watched/other.txt".into());
+        assert!(!is_relevant(&Ok(other), &targets));
+
+        let access = Event::new(EventKind::Access(AccessKind::Any)).add_path(target);
+        assert!(!is_relevant(&Ok(access), &targets));
+    }
+
+    #[test]
+    fn test_watch_files_missing_file() {
+        let result = watch_files(
+            &[PathBuf::from("/nonexistent/file.txt")],
+            DEFAULT_DEBOUNCE,
+            || ControlFlow::Break(()),
+        );
+        assert!(matches!(result, Err(CatboardError::FileNotFound(_))));
+    }
+
+    #[test]
+    fn test_watch_files_stops_when_cancelled() {
+        let dir = TempDir::new().unwrap();
+        let file_path = dir.path().join("watched.txt");
+        fs::write(&file_path, "initial").unwrap();
+
+        let token = CancellationToken::new();
+        let canceller = {
+            let token = token.clone();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(50));
+                token.cancel();
+            })
+        };
+        let options = WatchOptions {
+            debounce: DEFAULT_DEBOUNCE,
+            cancel: Some(token),
+        };
+        let result = watch_files_with(&[file_path], &options, || unreachable!());
+        canceller.join().unwrap();
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_watch_files_detects_change() {
+        let dir = TempDir::new().unwrap();
+        let file_path = dir.path().join("watched.txt");
+        fs::write(&file_path, "initial").unwrap();
+
+        // Keep writing until the watcher reports a change, since the watcher
+        // may not be registered yet when the first write lands
+        let done = Arc::new(AtomicBool::new(false));
+        let writer = {
+            let done = Arc::clone(&done);
+            let file_path = file_path.clone();
+            thread::spawn(move || {
+                for i in 0..50 {
+                    if done.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    fs::write(&file_path, format!("update {}", i)).unwrap();
+                    thread::sleep(Duration::from_millis(100));
+                }
+            })
+        };
+
+        let mut changes = 0;
+        let result = watch_files(&[file_path], Duration::from_millis(50), || {
+            changes += 1;
+            ControlFlow::Break(())
+        });
+        done.store(true, Ordering::SeqCst);
+        writer.join().unwrap();
+
+        assert!(result.is_ok());
+        assert_eq!(changes, 1);
+    }
+}
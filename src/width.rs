@@ -0,0 +1,167 @@
+//! Grapheme and display-width measurement.
+//!
+//! Byte and `char` counts overstate what a user sees: a flag emoji is
+//! several chars but one grapheme, and CJK characters take two terminal
+//! columns. These helpers back the `info` statistics and `--truncate-cols`.
+
+use crate::error::Result;
+use crate::transform::Transform;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Size of a piece of text, measured several ways
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextStats {
+    pub bytes: usize,
+    pub chars: usize,
+    /// User-perceived characters (extended grapheme clusters)
+    pub graphemes: usize,
+    pub lines: usize,
+    /// Runs of non-whitespace
+    pub words: usize,
+    /// Display width of the widest line, in terminal columns
+    pub max_columns: usize,
+}
+
+impl TextStats {
+    pub fn of(text: &str) -> Self {
+        Self {
+            bytes: text.len(),
+            chars: text.chars().count(),
+            graphemes: text.graphemes(true).count(),
+            lines: text.lines().count(),
+            words: text.split_whitespace().count(),
+            max_columns: text.lines().map(display_width).max().unwrap_or(0),
+        }
+    }
+}
+
+/// Number of terminal columns `text` occupies on a single line
+pub fn display_width(text: &str) -> usize {
+    text.width()
+}
+
+/// Cut every line of `text` to at most `max_columns` display columns.
+///
+/// Lines are only cut between grapheme clusters, so combining marks stay
+/// with their base character and a wide character that would straddle the
+/// limit is dropped whole. Line endings are preserved.
+pub fn truncate_cols(text: &str, max_columns: usize) -> String {
+    text.split_inclusive('\n')
+        .map(|line| {
+            let body = line.trim_end_matches(['\n', '\r']);
+            let ending = &line[body.len()..];
+            let mut width = 0;
+            let kept: String = body
+                .graphemes(true)
+                .take_while(|grapheme| {
+                    width += display_width(grapheme);
+                    width <= max_columns
+                })
+                .collect();
+            kept + ending
+        })
+        .collect()
+}
+
+/// `--truncate-cols` as a transform
+#[derive(Debug, Clone, Copy)]
+pub struct TruncateCols(pub usize);
+
+impl Transform for TruncateCols {
+    fn apply(&self, input: &str) -> Result<String> {
+        Ok(truncate_cols(input, self.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_ascii() {
+        let stats = TextStats::of("hello\nhi\n");
+        assert_eq!(
+            stats,
+            TextStats {
+                bytes: 9,
+                chars: 9,
+                graphemes: 9,
+                lines: 2,
+                words: 2,
+                max_columns: 5,
+            }
+        );
+    }
+
+    #[test]
+    fn test_stats_wide_characters() {
+        // Two CJK characters: 6 bytes, 2 chars, 4 columns
+        let stats = TextStats::of("\u{4E2D}\u{6587}");
+        assert_eq!(stats.bytes, 6);
+        assert_eq!(stats.chars, 2);
+        assert_eq!(stats.graphemes, 2);
+        assert_eq!(stats.max_columns, 4);
+    }
+
+    #[test]
+    fn test_stats_combining_marks() {
+        // "e" + combining acute is two chars but one grapheme and one column
+        let stats = TextStats::of("cafe\u{301}");
+        assert_eq!(stats.chars, 5);
+        assert_eq!(stats.graphemes, 4);
+        assert_eq!(stats.max_columns, 4);
+    }
+
+    #[test]
+    fn test_stats_emoji_sequence() {
+        // Family emoji joined by zero-width joiners is one grapheme
+        let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+        let stats = TextStats::of(family);
+        assert_eq!(stats.chars, 5);
+        assert_eq!(stats.graphemes, 1);
+    }
+
+    #[test]
+    fn test_stats_empty() {
+        assert_eq!(TextStats::of("").max_columns, 0);
+        assert_eq!(TextStats::of("").graphemes, 0);
+    }
+
+    #[test]
+    fn test_truncate_ascii_per_line() {
+        assert_eq!(truncate_cols("abcdef\nxy\n", 3), "abc\nxy\n");
+        assert_eq!(truncate_cols("abc", 10), "abc");
+        assert_eq!(truncate_cols("abc", 0), "");
+    }
+
+    #[test]
+    fn test_truncate_keeps_crlf() {
+        assert_eq!(truncate_cols("abcdef\r\nghi", 2), "ab\r\ngh");
+    }
+
+    #[test]
+    fn test_truncate_drops_straddling_wide_character() {
+        // Each CJK character is two columns; the third would need columns 5-6
+        assert_eq!(
+            truncate_cols("\u{4E2D}\u{6587}\u{5B57}", 5),
+            "\u{4E2D}\u{6587}"
+        );
+        assert_eq!(
+            truncate_cols("\u{4E2D}\u{6587}\u{5B57}", 4),
+            "\u{4E2D}\u{6587}"
+        );
+    }
+
+    #[test]
+    fn test_truncate_keeps_combining_marks_with_base() {
+        assert_eq!(truncate_cols("cafe\u{301}s", 4), "cafe\u{301}");
+        assert_eq!(truncate_cols("cafe\u{301}s", 3), "caf");
+    }
+
+    #[test]
+    fn test_truncate_as_transform() {
+        let transform: &dyn Transform = &TruncateCols(2);
+        assert_eq!(transform.apply("abc\ndef").unwrap(), "ab\nde");
+    }
+}